@@ -0,0 +1,48 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::{
+	endian::{BE, LE},
+	owned::OwnedBytes,
+	spans::Spanned,
+	testing::check_bytes,
+};
+use parst_fuzz::{limited, read_write};
+use std::{
+	borrow::Cow,
+	collections::{BTreeMap, HashMap, VecDeque},
+	marker::PhantomData,
+	ops::{Range, RangeInclusive},
+	rc::Rc,
+	sync::Arc,
+};
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		check_bytes::<Vec<u8>, _>(data, ());
+		check_bytes::<Vec<LE<u32>>, _>(data, ());
+		check_bytes::<Vec<(u8, BE<u16>)>, _>(data, ());
+
+		read_write::<Vec<bool>, _, _>(data, ());
+		read_write::<Vec<char>, _, _>(data, ());
+		// Elements which can take up none of the input
+		read_write::<Vec<()>, _, _>(data, ());
+		read_write::<Vec<Option<u8>>, _, _>(data, ());
+		read_write::<Vec<Vec<u16>>, _, _>(data, ());
+		read_write::<Box<[LE<u16>]>, _, _>(data, ());
+		read_write::<VecDeque<u8>, _, _>(data, ());
+		read_write::<BTreeMap<u8, u8>, _, _>(data, ());
+		read_write::<HashMap<u8, ()>, _, _>(data, ());
+		read_write::<Option<LE<u32>>, _, _>(data, ());
+		read_write::<Range<u8>, _, _>(data, ());
+		read_write::<RangeInclusive<LE<u16>>, _, _>(data, ());
+		read_write::<Box<u32>, _, _>(data, ());
+		read_write::<Rc<u8>, _, _>(data, ());
+		read_write::<Arc<u8>, _, _>(data, ());
+		read_write::<PhantomData<u8>, _, _>(data, ());
+		read_write::<[LE<u32>; 3], _, _>(data, ());
+		read_write::<Spanned<u16>, _, _>(data, ());
+		read_write::<OwnedBytes, _, _>(data, ());
+		read_write::<Cow<[u8]>, _, _>(data, ());
+	});
+});
@@ -0,0 +1,81 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::{
+	endian::{BE, LE},
+	testing::{check_arbitrary, check_bytes},
+	Arbitrary, Deparsable, Parsable,
+};
+use parst_extra::{strings::NulTerminated, var::VarStructs};
+use parst_fuzz::{limited, read_write};
+
+#[derive(Debug, PartialEq, Parsable, Deparsable, Arbitrary)]
+#[parst(src = "[u8]", tag = "PF")]
+struct Header {
+	#[parst(matches = "1..=3")]
+	version: u8,
+	#[parst(assert_ne = "LE(0)")]
+	len: LE<u16>,
+	#[parst(skip)]
+	cached: u32,
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable, Arbitrary)]
+#[parst(src = "[u8]", dis = "u8")]
+enum Record {
+	#[parst(dis = "0")]
+	Empty,
+	#[parst(dis = "1")]
+	Number(BE<u32>),
+	#[parst(dis = "2")]
+	Pair(u8, LE<i16>),
+	#[parst(dis_pat = "3..=9")]
+	Small(#[parst(dis)] u8),
+	#[parst(dis_pat = "_")]
+	Other(#[parst(dis)] u8, [LE<u16>; 2]),
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable, Arbitrary)]
+#[parst(src = "[u8]")]
+struct File {
+	header: Header,
+	records: Vec<Record>,
+}
+
+// Input can nest these as deeply as it likes, which stops at the limit instead of the stack
+#[derive(Debug, Parsable, Deparsable)]
+#[parst(src = "[u8]", dis = "u8", max_depth = "64")]
+enum Expr {
+	#[parst(dis = "0")]
+	Num(LE<i32>),
+	#[parst(dis = "1")]
+	Neg(Box<Expr>),
+	#[parst(dis = "2")]
+	Add(Box<Expr>, Box<Expr>),
+	#[parst(dis = "3")]
+	List(VarStructs<u8, Expr>),
+}
+
+#[derive(Debug, Parsable, Deparsable)]
+#[parst(src = "[u8]", lifetime = "'a")]
+struct Borrowed<'a> {
+	name: NulTerminated<'a>,
+	count: u8,
+	#[parst(ctx = "count as usize")]
+	values: &'a [u8],
+	rest: &'a [u8],
+}
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		check_arbitrary::<Header, _>(data, ());
+		check_arbitrary::<Record, _>(data, ());
+		check_arbitrary::<File, _>(data, ());
+
+		check_bytes::<Header, _>(data, ());
+		check_bytes::<Record, _>(data, ());
+		check_bytes::<Borrowed, _>(data, ());
+		check_bytes::<Expr, _>(data, ());
+		read_write::<File, _, _>(data, ());
+	});
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::element::{Any, Literal, Not, Peek, Satisfy, Take};
+use parst_fuzz::{limited, read_write};
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		read_write::<Any<u8>, _, _>(data, ());
+		read_write::<Take<u8, 3>, _, _>(data, ());
+		read_write::<Satisfy<u8>, _, _>(data, |b: &u8| b.is_ascii_digit());
+		read_write::<Literal<u8>, _, _>(data, b"GET ");
+		read_write::<Literal<u8>, _, _>(data, &b"\x7fELF"[..]);
+		read_write::<Peek<u32>, _, _>(data, ());
+		read_write::<Not<u8>, _, _>(data, ());
+		read_write::<Vec<Peek<u8>>, _, _>(data, ());
+
+		let chars = data.iter().map(|&b| char::from(b)).collect::<Vec<_>>();
+		read_write::<Take<char, 2>, _, _>(&chars[..], ());
+	});
+});
@@ -0,0 +1,34 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::{endian::LE, Parsable};
+use parst_extra::{
+	lazy::{LazyArray, LazySeq},
+	strings::NulTerminated,
+};
+use parst_fuzz::{count, limited, read_write};
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		let (n, rest) = count(data);
+		read_write::<LazyArray<LE<u32>>, _, _>(rest, n);
+		read_write::<LazyArray<LE<u32>>, _, _>(rest, usize::MAX - n);
+		read_write::<LazySeq<NulTerminated>, _, _>(rest, n);
+
+		if let Ok((array, _)) = LazyArray::<LE<u16>>::read(rest, n) {
+			array.iter().for_each(drop);
+			let _ = array.get(n / 2);
+		}
+		if let Ok((seq, _)) = LazySeq::<NulTerminated>::read(rest, ()) {
+			let _ = seq.len();
+			let _ = seq.get(n);
+			seq.iter().for_each(drop);
+		}
+		// Elements which can take up none of the bytes
+		if let Ok((seq, _)) = LazySeq::<()>::read(rest, ()) {
+			let _ = seq.len();
+			let _ = seq.get(n);
+			seq.iter().for_each(drop);
+		}
+	});
+});
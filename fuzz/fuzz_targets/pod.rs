@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::endian::{PackedBE, PackedLE};
+use parst_fuzz::{count, limited, read_write};
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		read_write::<&u8, _, _>(data, ());
+		read_write::<&[u8; 16], _, _>(data, ());
+		read_write::<&PackedLE<u32>, _, _>(data, ());
+		read_write::<&[[u8; 3]; 2], _, _>(data, ());
+
+		let (n, rest) = count(data);
+		read_write::<&[u8], _, _>(rest, n);
+		read_write::<&[PackedBE<u16>], _, _>(rest, n);
+		read_write::<&[PackedLE<u64>], _, _>(rest, usize::MAX - n);
+	});
+});
@@ -0,0 +1,52 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::{
+	endian::{PackedBE, PackedLE, BE, LE},
+	testing::check_bytes,
+};
+use parst_fuzz::{limited, read_write};
+use std::{
+	borrow::Cow,
+	net::{Ipv4Addr, Ipv6Addr},
+	num::NonZeroU32,
+	time::Duration,
+};
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		// Every value of these has exactly one encoding
+		check_bytes::<u8, _>(data, ());
+		check_bytes::<i16, _>(data, ());
+		check_bytes::<u32, _>(data, ());
+		check_bytes::<i64, _>(data, ());
+		check_bytes::<u128, _>(data, ());
+		check_bytes::<LE<u16>, _>(data, ());
+		check_bytes::<BE<i32>, _>(data, ());
+		check_bytes::<LE<u64>, _>(data, ());
+		check_bytes::<BE<i128>, _>(data, ());
+		check_bytes::<PackedLE<u32>, _>(data, ());
+		check_bytes::<PackedBE<i64>, _>(data, ());
+		check_bytes::<[u8; 4], _>(data, ());
+		check_bytes::<(u8, LE<u16>, BE<u32>), _>(data, ());
+		check_bytes::<Ipv4Addr, _>(data, ());
+		check_bytes::<Ipv6Addr, _>(data, ());
+
+		read_write::<f32, _, _>(data, ());
+		read_write::<LE<f64>, _, _>(data, ());
+		read_write::<bool, _, _>(data, ());
+		read_write::<char, _, _>(data, ());
+		read_write::<NonZeroU32, _, _>(data, ());
+		read_write::<Duration, _, _>(data, ());
+		read_write::<(), _, _>(data, ());
+		read_write::<&[u8], _, _>(data, ());
+		read_write::<String, _, _>(data, ());
+
+		if let Ok(text) = std::str::from_utf8(data) {
+			read_write::<&str, _, _>(text, ());
+			read_write::<String, _, _>(text, ());
+			read_write::<Box<str>, _, _>(text, ());
+			read_write::<Cow<str>, _, _>(text, ());
+		}
+	});
+});
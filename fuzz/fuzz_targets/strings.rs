@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::testing::check_bytes;
+use parst_extra::strings::{FixedStr, Latin1, NulTerminated, Utf16Be, Utf16Le, VarStr};
+use parst_fuzz::{count, limited, read, read_write};
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		check_bytes::<NulTerminated, _>(data, ());
+		check_bytes::<VarStr<u8>, _>(data, ());
+		check_bytes::<VarStr<u32>, _>(data, ());
+
+		read_write::<FixedStr<8>, _, _>(data, ());
+		read_write::<Utf16Le, _, _>(data, ());
+		read_write::<Utf16Be, _, _>(data, ());
+		read_write::<Latin1, _, _>(data, ());
+		read_write::<Vec<NulTerminated>, _, _>(data, ());
+
+		let (n, rest) = count(data);
+		read::<Utf16Le, _, _>(rest, n);
+		read::<Utf16Be, _, _>(rest, usize::MAX - n);
+		read::<Latin1, _, _>(rest, n);
+	});
+});
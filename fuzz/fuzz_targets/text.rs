@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst_extra::text::{Comma, Dec, Hex, Ident, Newline, Quoted, SeparatedBy, Ws, Ws1};
+use parst_fuzz::{limited, read_write};
+
+fuzz_target!(|data: &[u8]| {
+	let Ok(text) = std::str::from_utf8(data) else {
+		return;
+	};
+	limited(|| {
+		read_write::<Dec<u8>, _, _>(text, ());
+		read_write::<Dec<i64>, _, _>(text, ());
+		read_write::<Dec<u128>, _, _>(text, ());
+		read_write::<Dec<f64>, _, _>(text, ());
+		read_write::<Hex<u32>, _, _>(text, ());
+		read_write::<Ident, _, _>(text, ());
+		read_write::<Quoted, _, _>(text, ());
+		read_write::<Ws, _, _>(text, ());
+		read_write::<Ws1, _, _>(text, ());
+		read_write::<Comma, _, _>(text, ());
+		read_write::<SeparatedBy<Dec<u16>, Comma>, _, _>(text, ());
+		read_write::<SeparatedBy<Quoted, Newline>, _, _>(text, ());
+		// Items and separators which can take up none of the input
+		read_write::<SeparatedBy<Ws, Ws>, _, _>(text, ());
+		read_write::<Vec<(Ident, Ws)>, _, _>(text, ());
+	});
+});
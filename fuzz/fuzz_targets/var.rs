@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parst::testing::check_bytes;
+use parst_extra::var::{Cond, ConsumingVec, Counted, Limited, VarBytes, VarBytesOwned, VarStructs};
+use parst_fuzz::{count, limited, read_write};
+
+fuzz_target!(|data: &[u8]| {
+	limited(|| {
+		check_bytes::<VarBytes<u8>, _>(data, ());
+		check_bytes::<VarBytes<u32>, _>(data, ());
+		check_bytes::<VarStructs<u32, u16>, _>(data, ());
+
+		read_write::<VarBytes<u64>, _, _>(data, ());
+		read_write::<VarBytesOwned<u16>, _, _>(data, ());
+		// Lengths which can claim far more elements than the input holds
+		read_write::<VarStructs<u64, ()>, _, _>(data, ());
+		read_write::<VarStructs<u32, VarStructs<u32, u8>>, _, _>(data, ());
+		read_write::<ConsumingVec<u16>, _, _>(data, ());
+
+		let (n, rest) = count(data);
+		read_write::<Counted<u8>, _, _>(rest, n);
+		read_write::<Counted<()>, _, _>(rest, usize::MAX - n);
+		read_write::<Limited<u32>, _, _>(rest, n);
+		read_write::<Limited<Vec<u16>>, _, _>(rest, usize::MAX - n);
+		read_write::<Cond<u16>, _, _>(rest, n % 2 == 0);
+	});
+});
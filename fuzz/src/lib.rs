@@ -0,0 +1,41 @@
+// Shared by the targets, each of which reads every type it covers out of the fuzzer's data under a
+// limit on allocations. Run one with `cargo fuzz run <target>` from this directory
+
+use parst::{
+	limits::{with_limits, Limits},
+	Deparsable, Parsable,
+};
+
+// Far more than any of the inputs could need when the lengths in them are honest
+pub const LIMITS: Limits = Limits::UNLIMITED.with_max_bytes(1 << 24);
+
+pub fn limited(f: impl FnOnce()) { with_limits(LIMITS, f) }
+
+// Reads a `T`, which may fail but must not panic
+pub fn read<'a, T, Src, Ctx>(data: &'a Src, context: Ctx)
+where
+	Src: ?Sized,
+	T: Parsable<'a, Src, Ctx>,
+{
+	let _ = T::read(data, context);
+}
+
+// Reads a `T` and writes back anything read, neither of which may panic
+pub fn read_write<'a, T, Src, Ctx>(data: &'a Src, context: Ctx)
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx> + Deparsable<Ctx>,
+{
+	if let Ok((value, _)) = T::read(data, context) {
+		let _ = value.write(&mut Vec::new(), context);
+	}
+}
+
+// Splits off a count for the types read with one, kept small enough to be plausible
+pub fn count(data: &[u8]) -> (usize, &[u8]) {
+	match data.split_first() {
+		Some((&first, rest)) => (usize::from(first), rest),
+		None => (0, data),
+	}
+}
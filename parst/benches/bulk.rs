@@ -0,0 +1,131 @@
+#![feature(test, array_try_from_fn)]
+
+extern crate test;
+
+// Each fast path is measured against the element by element code the collections used before it,
+// copied below, reading and writing the same elements
+
+use parst::{
+	endian::{BE, LE},
+	Deparsable, PResultBytes, Parsable,
+};
+use std::{array::try_from_fn, io::Write};
+use test::{black_box, Bencher};
+
+const LEN: usize = 4096;
+
+fn source() -> Vec<u8> { (0..LEN * 4).map(|i| i as u8).collect() }
+
+fn read_array_each<'a, T, const N: usize>(mut source: &'a [u8]) -> PResultBytes<'a, [T; N]>
+where
+	T: Parsable<'a, [u8]>,
+{
+	try_from_fn(|_| {
+		let (element, this_bytes) = T::read(source, ())?;
+		source = this_bytes;
+		Ok(element)
+	})
+	.map(|array| (array, source))
+}
+
+fn read_vec_each<'a, T>(mut source: &'a [u8]) -> PResultBytes<'a, Vec<T>>
+where
+	T: Parsable<'a, [u8]>,
+{
+	let mut v = Vec::new();
+	while let Ok((element, remainder)) = T::read(source, ()) {
+		v.push(element);
+		source = remainder;
+	}
+	Ok((v, source))
+}
+
+fn write_vec_each<T>(values: &[T], w: &mut impl Write) -> std::io::Result<()>
+where
+	T: Deparsable,
+{
+	for element in values {
+		element.write(&mut *w, ())?;
+	}
+	Ok(())
+}
+
+#[bench]
+fn array_u8_bulk(b: &mut Bencher) {
+	let source = source();
+	b.iter(|| <[u8; LEN]>::read(black_box(&source), ()).unwrap().0);
+}
+
+#[bench]
+fn array_u8_each(b: &mut Bencher) {
+	let source = source();
+	b.iter(|| read_array_each::<u8, LEN>(black_box(&source)).unwrap().0);
+}
+
+#[bench]
+fn array_le_u32_bulk(b: &mut Bencher) {
+	let source = source();
+	b.iter(|| <[LE<u32>; LEN]>::read(black_box(&source), ()).unwrap().0);
+}
+
+#[bench]
+fn array_le_u32_each(b: &mut Bencher) {
+	let source = source();
+	b.iter(|| {
+		read_array_each::<LE<u32>, LEN>(black_box(&source))
+			.unwrap()
+			.0
+	});
+}
+
+#[bench]
+fn vec_be_u32_bulk(b: &mut Bencher) {
+	let source = source();
+	b.iter(|| Vec::<BE<u32>>::read(black_box(&source), ()).unwrap().0);
+}
+
+#[bench]
+fn vec_be_u32_each(b: &mut Bencher) {
+	let source = source();
+	b.iter(|| read_vec_each::<BE<u32>>(black_box(&source)).unwrap().0);
+}
+
+#[bench]
+fn write_vec_u8_bulk(b: &mut Bencher) {
+	let values = source();
+	let mut out = Vec::with_capacity(values.len());
+	b.iter(|| {
+		out.clear();
+		black_box(&values).write(&mut out, ()).unwrap();
+	});
+}
+
+#[bench]
+fn write_vec_u8_each(b: &mut Bencher) {
+	let values = source();
+	let mut out = Vec::with_capacity(values.len());
+	b.iter(|| {
+		out.clear();
+		write_vec_each(black_box(&values), &mut out).unwrap();
+	});
+}
+
+#[bench]
+fn write_vec_le_u32_bulk(b: &mut Bencher) {
+	let values = (0..LEN as u32).map(LE).collect::<Vec<_>>();
+	let mut out = Vec::with_capacity(LEN * 4);
+	b.iter(|| {
+		out.clear();
+		black_box(&values).write(&mut out, ()).unwrap();
+	});
+}
+
+#[bench]
+fn write_vec_le_u32_each(b: &mut Bencher) {
+	let values = (0..LEN as u32).map(LE).collect::<Vec<_>>();
+	let mut out = Vec::with_capacity(LEN * 4);
+	b.iter(|| {
+		out.clear();
+		write_vec_each(black_box(&values), &mut out).unwrap();
+	});
+}
@@ -0,0 +1,98 @@
+// Reading out of and writing into the buffers of the `bytes` crate. `Bytes` fields read inside
+// `read` become handles on the buffer being read rather than copies of it
+
+use crate::{error::Error, owned::ToStatic, Deparsable, PResultBytes, Parsable};
+use bytes::BufMut;
+use std::cell::RefCell;
+
+pub use bytes::{Bytes, BytesMut};
+
+thread_local! {
+	// The buffer being read by `read`, which `Bytes` fields are taken out of
+	static BUFFER: RefCell<Bytes> = const { RefCell::new(Bytes::new()) };
+}
+
+// A handle on `slice`, sharing the buffer being read when the slice is part of it and otherwise
+// copied out
+fn share(slice: &[u8]) -> Bytes {
+	BUFFER.with_borrow(|buffer| {
+		let range = buffer.as_ptr_range();
+		let slice_range = slice.as_ptr_range();
+		match range.start <= slice_range.start && slice_range.end <= range.end {
+			true if !slice.is_empty() => buffer.slice_ref(slice),
+			_ => Bytes::copy_from_slice(slice),
+		}
+	})
+}
+
+// Reads a `T` out of `bytes`, along with a handle on what it leaves
+pub fn read<'a, T, Ctx>(bytes: &'a Bytes, context: Ctx) -> Result<(T, Bytes), Error>
+where
+	T: Parsable<'a, [u8], Ctx>,
+{
+	struct Restore(Bytes);
+
+	impl Drop for Restore {
+		fn drop(&mut self) { BUFFER.set(std::mem::take(&mut self.0)) }
+	}
+
+	let _restore = Restore(BUFFER.replace(bytes.clone()));
+	let (value, remainder) = T::read(bytes, context).map_err(|(e, _)| e)?;
+	Ok((value, bytes.slice_ref(remainder)))
+}
+
+// Writes `value` onto the end of `dst`, leaving `dst` as it was when the value can not be written.
+// `Deparsable` only writes to an `io::Write`, which `BufMut::writer` turns each write into a
+// `put_slice` for, so the bytes go straight into `dst`. Other `BufMut`s could be written the same way,
+// but only a `BytesMut` can be cut back when the value fails part way through
+pub fn write<T, Ctx>(value: &T, dst: &mut BytesMut, context: Ctx) -> std::io::Result<()>
+where
+	T: Deparsable<Ctx> + ?Sized,
+{
+	let start = dst.len();
+	let mut w = dst.writer();
+	let result = value.write(&mut w, context);
+	if result.is_err() {
+		w.into_inner().truncate(start);
+	}
+	result
+}
+
+// Read through `read` or `ParstCodec`, the bytes are a handle on the buffer being read. Read out of
+// any other `[u8]`, there is no buffer to share and they are copied
+impl<'a> Parsable<'a, [u8]> for Bytes {
+	#[inline]
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> { Ok((share(source), &[])) }
+}
+
+// The context is the number of bytes
+impl<'a> Parsable<'a, [u8], usize> for Bytes {
+	#[inline]
+	fn read(source: &'a [u8], context: usize) -> PResultBytes<'a, Self> {
+		let (bytes, source) = source
+			.split_at_checked(context)
+			.ok_or((Error::NotEnoughBytes, source))?;
+		Ok((share(bytes), source))
+	}
+}
+
+// Whatever the context, the bytes are written as they are
+impl<Ctx> Deparsable<Ctx> for Bytes {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> {
+		w.write_all(self)
+	}
+}
+
+impl<Ctx> Deparsable<Ctx> for BytesMut {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> {
+		w.write_all(self)
+	}
+}
+
+impl ToStatic for Bytes {
+	type Static = Bytes;
+
+	fn to_static(&self) -> Bytes { self.clone() }
+}
@@ -0,0 +1,134 @@
+use crate::{error::Error, Deparsable, Parsable};
+use bytes::BytesMut;
+use std::{io, marker::PhantomData};
+use tokio_util::codec::{Decoder, Encoder};
+
+// Splits a stream into `T`s, a frame being complete as soon as a `T` can be parsed out of what has
+// arrived so far. A `T` which reads until its source ends is cut short at whatever has arrived.
+// Frames are split off the buffer and read again through `bytes::read`, so that `Bytes` fields
+// share it rather than being copied out
+pub struct ParstCodec<T, Ctx = ()> {
+	context: Ctx,
+	max_frame: usize,
+	frame: PhantomData<fn() -> T>,
+}
+
+impl<T> ParstCodec<T> {
+	pub fn new() -> Self { Self::with_context(()) }
+}
+
+impl<T> Default for ParstCodec<T> {
+	fn default() -> Self { Self::new() }
+}
+
+impl<T, Ctx> ParstCodec<T, Ctx> {
+	pub fn with_context(context: Ctx) -> Self {
+		Self {
+			context,
+			max_frame: 1 << 20,
+			frame: PhantomData,
+		}
+	}
+
+	pub fn with_max_frame(self, max_frame: usize) -> Self { Self { max_frame, ..self } }
+
+	pub fn max_frame(&self) -> usize { self.max_frame }
+}
+
+impl<T, Ctx> Clone for ParstCodec<T, Ctx>
+where
+	Ctx: Clone,
+{
+	fn clone(&self) -> Self {
+		Self {
+			context: self.context.clone(),
+			max_frame: self.max_frame,
+			frame: PhantomData,
+		}
+	}
+}
+
+impl<T, Ctx> std::fmt::Debug for ParstCodec<T, Ctx>
+where
+	Ctx: std::fmt::Debug,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ParstCodec")
+			.field("context", &self.context)
+			.field("max_frame", &self.max_frame)
+			.finish()
+	}
+}
+
+impl<T, Ctx> Decoder for ParstCodec<T, Ctx>
+where
+	Ctx: Copy,
+	T: for<'a> Parsable<'a, [u8], Ctx>,
+{
+	type Item = T;
+	type Error = io::Error;
+
+	fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<T>> {
+		if src.is_empty() {
+			return Ok(None);
+		}
+		let consumed = match T::read(src, self.context) {
+			Ok((_, remainder)) => src.len() - remainder.len(),
+			// The frame might continue past what has arrived so far
+			Err((Error::NotEnoughBytes, _)) if src.len() < self.max_frame => return Ok(None),
+			Err((Error::NotEnoughBytes, _)) => return Err(Error::LimitExceeded.into()),
+			Err((e, _)) => return Err(e.into()),
+		};
+		// A frame which takes up nothing would be read forever
+		if consumed == 0 {
+			return Err(Error::InvalidInput.into());
+		}
+		let frame = src.split_to(consumed).freeze();
+		let (frame, _) = crate::bytes::read(&frame, self.context)?;
+		Ok(Some(frame))
+	}
+
+	fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<T>> {
+		match self.decode(src)? {
+			None if !src.is_empty() => Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				"stream ended part way through a frame",
+			)),
+			frame => Ok(frame),
+		}
+	}
+}
+
+impl<T, Ctx> Encoder<&T> for ParstCodec<T, Ctx>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+{
+	type Error = io::Error;
+
+	fn encode(&mut self, frame: &T, dst: &mut BytesMut) -> io::Result<()> {
+		let start = dst.len();
+		crate::bytes::write(frame, dst, self.context)?;
+		// Nothing of a frame which is too long is left to be sent
+		if dst.len() - start > self.max_frame {
+			dst.truncate(start);
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"frame is longer than the maximum",
+			));
+		}
+		Ok(())
+	}
+}
+
+impl<T, Ctx> Encoder<T> for ParstCodec<T, Ctx>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+{
+	type Error = io::Error;
+
+	fn encode(&mut self, frame: T, dst: &mut BytesMut) -> io::Result<()> {
+		<Self as Encoder<&T>>::encode(self, &frame, dst)
+	}
+}
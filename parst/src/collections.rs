@@ -1,5 +1,18 @@
-use crate::{Deparsable, PResult, Parsable};
-use std::{array::try_from_fn, marker::PhantomData, ops::DerefMut};
+use crate::{
+	error::Error,
+	limits,
+	pod::FixedSize,
+	schema::{tuple_schema, Schema, TypeSchema},
+	Deparsable, PResult, PResultStr, Parsable,
+};
+use std::{
+	collections::{BTreeMap, HashMap, VecDeque},
+	hash::{BuildHasher, Hash},
+	marker::PhantomData,
+	ops::{Deref, Range, RangeInclusive},
+	rc::Rc,
+	sync::Arc,
+};
 
 impl<'a, T, Src, Ctx, const N: usize> Parsable<'a, Src, Ctx> for [T; N]
 where
@@ -8,13 +21,8 @@ where
 	T: Parsable<'a, Src, Ctx>,
 {
 	#[inline]
-	fn read(mut source: &'a Src, context: Ctx) -> PResult<Self, Src> {
-		try_from_fn(|_| {
-			let (element, this_bytes) = Parsable::read(source, context)?;
-			source = this_bytes;
-			Ok(element)
-		})
-		.map(|array| (array, source))
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		T::read_array(source, context)
 	}
 }
 
@@ -24,9 +32,8 @@ where
 	T: Deparsable<Ctx>,
 {
 	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
-		self.iter_mut()
-			.try_for_each(|element| element.write(&mut *w, context))
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		T::write_slice(self, w, context)
 	}
 }
 
@@ -48,7 +55,7 @@ macro_rules! impl_tuple {
             )+
 		{
 			#[inline]
-			fn read(source: &'a Src, context: Ctx) -> PResult<Self, Src> {
+			fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
                 $(
                     let ($N, source) = Parsable::read(source, context)?;
                 )+
@@ -56,6 +63,24 @@ macro_rules! impl_tuple {
 			}
 		}
 
+		impl<$( $T ),+> FixedSize for ($( $T, )+)
+		where
+			$(
+				$T: FixedSize,
+			)+
+		{
+			const SIZE: usize = 0 $( + $T::SIZE )+;
+		}
+
+		impl<$( $T ),+> Schema for ($( $T, )+)
+		where
+			$(
+				$T: Schema,
+			)+
+		{
+			fn schema() -> TypeSchema { tuple_schema(vec![$( $T::schema() ),+]) }
+		}
+
 		impl<Ctx, $( $T ),+> Deparsable<Ctx> for ($( $T, )+)
 		where
 			$(
@@ -64,7 +89,7 @@ macro_rules! impl_tuple {
 			)+
 		{
 			#[inline]
-			fn write(&mut self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+			fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
 				let ( $( $N, )+ ) = self;
 				$(
 					$N.write(&mut *w, context)?;
@@ -75,7 +100,7 @@ macro_rules! impl_tuple {
 	};
 }
 
-impl_tuple!(a A b B c C d D e E f F g G h H);
+impl_tuple!(a A b B c C d D e E f F g G h H i I j J k K l L m M n N o O p P);
 
 impl<'a, Src, Ctx, T> Parsable<'a, Src, Ctx> for Vec<T>
 where
@@ -84,13 +109,8 @@ where
 	T: Parsable<'a, Src, Ctx>,
 {
 	#[inline]
-	fn read(mut source: &'a Src, context: Ctx) -> PResult<Self, Src> {
-		let mut v = Vec::new();
-		while let Ok((element, remainder)) = Parsable::read(source, context) {
-			v.push(element);
-			source = remainder;
-		}
-		Ok((v, source))
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		T::read_vec(source, context, None)
 	}
 }
 
@@ -100,11 +120,8 @@ where
 	T: Deparsable<Ctx>,
 {
 	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
-		for element in self {
-			element.write(&mut *w, context)?;
-		}
-		Ok(())
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		T::write_slice(self, w, context)
 	}
 }
 
@@ -114,7 +131,7 @@ where
 	T: Parsable<'a, Src, Ctx>,
 {
 	#[inline]
-	fn read(source: &'a Src, context: Ctx) -> PResult<Self, Src> {
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
 		let (boxed, source) = Parsable::read(source, context)?;
 		Ok((Box::new(boxed), source))
 	}
@@ -125,8 +142,242 @@ where
 	T: Deparsable<Ctx>,
 {
 	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
-		self.deref_mut().write(&mut *w, context)
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		self.deref().write(&mut *w, context)
+	}
+}
+
+impl<'a, Src, Ctx, T> Parsable<'a, Src, Ctx> for Box<[T]>
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let (v, source) = Vec::read(source, context)?;
+		Ok((v.into_boxed_slice(), source))
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for Box<[T]>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		T::write_slice(self, w, context)
+	}
+}
+
+impl<'a> Parsable<'a, str> for Box<str> {
+	#[inline]
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> { Ok((source.into(), "")) }
+}
+
+impl Deparsable for Box<str> {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(self.as_bytes())
+	}
+}
+
+macro_rules! impl_shared {
+	($( $ptr:ident )*) => {
+		$(
+			impl<'a, Src, Ctx, T> Parsable<'a, Src, Ctx> for $ptr<T>
+			where
+				Src: ?Sized,
+				T: Parsable<'a, Src, Ctx>,
+			{
+				#[inline]
+				fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+					let (inner, source) = Parsable::read(source, context)?;
+					Ok(($ptr::new(inner), source))
+				}
+			}
+
+			impl<T, Ctx> Deparsable<Ctx> for $ptr<T>
+			where
+				T: Deparsable<Ctx> + ?Sized,
+			{
+				#[inline]
+				fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+					self.deref().write(&mut *w, context)
+				}
+			}
+		)*
+	};
+}
+
+impl_shared!(Rc Arc);
+
+impl<'a, Src, Ctx, T> Parsable<'a, Src, Ctx> for VecDeque<T>
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let (v, source) = Vec::read(source, context)?;
+		Ok((v.into(), source))
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for VecDeque<T>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		let (front, back) = self.as_slices();
+		T::write_slice(front, &mut *w, context)?;
+		T::write_slice(back, w, context)
+	}
+}
+
+// Maps are sequences of key value pairs. A `BTreeMap` writes its keys in order, so keys out of order
+// or repeated are rejected and every map read writes back to the same bytes
+impl<'a, Src, Ctx, K, V> Parsable<'a, Src, Ctx> for BTreeMap<K, V>
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	K: Parsable<'a, Src, Ctx> + Ord,
+	V: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(mut source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let mut map = BTreeMap::new();
+		loop {
+			let ((key, value), remainder) = match <(K, V)>::read(source, context) {
+				Ok(read) => read,
+				Err((e, s)) if e.is_limit() => return Err((e, s)),
+				Err(_) => break,
+			};
+			limits::reserve::<(K, V)>(1).map_err(|e| (e, source))?;
+			if map.last_key_value().is_some_and(|(last, _)| *last >= key) {
+				return Err((Error::InvalidInput, source));
+			}
+			map.insert(key, value);
+			source = remainder;
+		}
+		Ok((map, source))
+	}
+}
+
+impl<K, V, Ctx> Deparsable<Ctx> for BTreeMap<K, V>
+where
+	Ctx: Copy,
+	K: Deparsable<Ctx>,
+	V: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		self.iter()
+			.try_for_each(|pair| pair.write(&mut *w, context))
+	}
+}
+
+// A `HashMap` writes its entries in no set order, so only they survive writing it and reading it
+// back, and a repeated key is rejected
+impl<'a, Src, Ctx, K, V, S> Parsable<'a, Src, Ctx> for HashMap<K, V, S>
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	K: Parsable<'a, Src, Ctx> + Eq + Hash,
+	V: Parsable<'a, Src, Ctx>,
+	S: BuildHasher + Default,
+{
+	#[inline]
+	fn read(mut source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let mut map = HashMap::default();
+		loop {
+			let ((key, value), remainder) = match <(K, V)>::read(source, context) {
+				Ok(read) => read,
+				Err((e, s)) if e.is_limit() => return Err((e, s)),
+				Err(_) => break,
+			};
+			limits::reserve::<(K, V)>(1).map_err(|e| (e, source))?;
+			if map.insert(key, value).is_some() {
+				return Err((Error::InvalidInput, source));
+			}
+			source = remainder;
+		}
+		Ok((map, source))
+	}
+}
+
+impl<K, V, S, Ctx> Deparsable<Ctx> for HashMap<K, V, S>
+where
+	Ctx: Copy,
+	K: Deparsable<Ctx>,
+	V: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		self.iter()
+			.try_for_each(|pair| pair.write(&mut *w, context))
+	}
+}
+
+impl<'a, Src, Ctx, T> Parsable<'a, Src, Ctx> for Range<T>
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let ((start, end), source) = <(T, T)>::read(source, context)?;
+		Ok((start..end, source))
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for Range<T>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		(&self.start, &self.end).write(w, context)
+	}
+}
+
+impl<'a, Src, Ctx, T> Parsable<'a, Src, Ctx> for RangeInclusive<T>
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let ((start, end), source) = <(T, T)>::read(source, context)?;
+		Ok((start..=end, source))
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for RangeInclusive<T>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		(self.start(), self.end()).write(w, context)
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for &T
+where
+	T: Deparsable<Ctx> + ?Sized,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		(**self).write(&mut *w, context)
 	}
 }
 
@@ -136,9 +387,10 @@ where
 	T: Parsable<'a, Src, Ctx>,
 {
 	#[inline]
-	fn read(source: &'a Src, context: Ctx) -> PResult<Self, Src> {
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
 		match Parsable::read(source, context) {
 			Ok((inner, source)) => Ok((Some(inner), source)),
+			Err((e, s)) if e.is_limit() => Err((e, s)),
 			Err(_) => Ok((None, source)),
 		}
 	}
@@ -149,7 +401,7 @@ where
 	T: Deparsable<Ctx>,
 {
 	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
 		match self {
 			Some(inner) => inner.write(&mut *w, context),
 			None => Ok(()),
@@ -162,5 +414,10 @@ where
 	Src: ?Sized,
 {
 	#[inline]
-	fn read(source: &Src, _context: Ctx) -> PResult<Self, Src> { Ok((PhantomData, source)) }
+	fn read(source: &Src, _context: Ctx) -> PResult<'_, Self, Src> { Ok((PhantomData, source)) }
+}
+
+impl<T, Ctx> Deparsable<Ctx> for PhantomData<T> {
+	#[inline]
+	fn write(&self, _w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> { Ok(()) }
 }
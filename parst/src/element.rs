@@ -0,0 +1,242 @@
+use crate::{error::Error, owned::ToStatic, Deparsable, PResult, Parsable};
+use std::{borrow::Cow, marker::PhantomData};
+
+pub use crate::helpers::try_split_array;
+
+// Building blocks for sources made of any element type, such as the tokens from a lexer
+
+// Any single element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Any<T>(pub T);
+
+impl<'a, T> Parsable<'a, [T]> for Any<T>
+where
+	T: Clone,
+{
+	#[inline]
+	fn read(source: &'a [T], _context: ()) -> PResult<'a, Self, [T]> {
+		let (element, source) = source
+			.split_first()
+			.ok_or((Error::NotEnoughBytes, source))?;
+		Ok((Any(element.clone()), source))
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for Any<T>
+where
+	T: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		self.0.write(w, context)
+	}
+}
+
+// Exactly `N` elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Take<T, const N: usize>(pub [T; N]);
+
+impl<'a, T, const N: usize> Parsable<'a, [T]> for Take<T, N>
+where
+	T: Clone,
+{
+	#[inline]
+	fn read(source: &'a [T], _context: ()) -> PResult<'a, Self, [T]> {
+		let (elements, source) = try_split_array(source).ok_or((Error::NotEnoughBytes, source))?;
+		Ok((Take(elements.clone()), source))
+	}
+}
+
+impl<T, Ctx, const N: usize> Deparsable<Ctx> for Take<T, N>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		self.0.write(w, context)
+	}
+}
+
+// A single element accepted by the predicate given as the context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Satisfy<T>(pub T);
+
+impl<'a, T, F> Parsable<'a, [T], F> for Satisfy<T>
+where
+	T: Clone,
+	F: Fn(&T) -> bool,
+{
+	#[inline]
+	fn read(source: &'a [T], context: F) -> PResult<'a, Self, [T]> {
+		match source.split_first() {
+			Some((element, remainder)) if context(element) => {
+				Ok((Satisfy(element.clone()), remainder))
+			}
+			Some(_) => Err((Error::InvalidInput, source)),
+			None => Err((Error::NotEnoughBytes, source)),
+		}
+	}
+}
+
+// The predicate is ignored when writing, so the derive can give the same context both ways
+impl<T, F> Deparsable<F> for Satisfy<T>
+where
+	T: Deparsable,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: F) -> std::io::Result<()> {
+		self.0.write(w, ())
+	}
+}
+
+// The sequence of elements given as the context
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Literal<'a, T: Clone>(pub Cow<'a, [T]>);
+
+impl<'a, T> Literal<'a, T>
+where
+	T: Clone + PartialEq,
+{
+	#[inline]
+	fn read_literal(source: &'a [T], literal: &[T]) -> PResult<'a, Self, [T]> {
+		match source.strip_prefix(literal) {
+			Some(remainder) => Ok((Literal(Cow::Borrowed(&source[..literal.len()])), remainder)),
+			None if source.len() < literal.len() && literal.starts_with(source) => {
+				Err((Error::NotEnoughBytes, source))
+			}
+			None => Err((Error::InvalidInput, source)),
+		}
+	}
+}
+
+impl<'a, T> Parsable<'a, [T], &[T]> for Literal<'a, T>
+where
+	T: Clone + PartialEq,
+{
+	#[inline]
+	fn read(source: &'a [T], context: &[T]) -> PResult<'a, Self, [T]> {
+		Self::read_literal(source, context)
+	}
+}
+
+impl<'a, T, const N: usize> Parsable<'a, [T], &[T; N]> for Literal<'a, T>
+where
+	T: Clone + PartialEq,
+{
+	#[inline]
+	fn read(source: &'a [T], context: &[T; N]) -> PResult<'a, Self, [T]> {
+		Self::read_literal(source, context)
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for Literal<'_, T>
+where
+	T: Clone + Deparsable,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> {
+		self.0
+			.iter()
+			.try_for_each(|element| element.write(&mut *w, ()))
+	}
+}
+
+// Parses a `P` without consuming anything. Nothing is written back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Peek<P>(pub P);
+
+impl<'a, Src, Ctx, P> Parsable<'a, Src, Ctx> for Peek<P>
+where
+	Src: ?Sized,
+	P: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let (peeked, _) = P::read(source, context)?;
+		Ok((Peek(peeked), source))
+	}
+}
+
+impl<P, Ctx> Deparsable<Ctx> for Peek<P> {
+	#[inline]
+	fn write(&self, _w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> { Ok(()) }
+}
+
+// Succeeds without consuming anything when a `P` can not be parsed. Nothing is written back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Not<P>(PhantomData<P>);
+
+impl<'a, Src, Ctx, P> Parsable<'a, Src, Ctx> for Not<P>
+where
+	Src: ?Sized,
+	P: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		match P::read(source, context) {
+			Ok(_) => Err((Error::InvalidInput, source)),
+			Err((e, s)) if e.is_limit() => Err((e, s)),
+			Err(_) => Ok((Not(PhantomData), source)),
+		}
+	}
+}
+
+impl<P, Ctx> Deparsable<Ctx> for Not<P> {
+	#[inline]
+	fn write(&self, _w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> { Ok(()) }
+}
+
+impl<T> ToStatic for Any<T>
+where
+	T: ToStatic,
+{
+	type Static = Any<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Any(self.0.to_static()) }
+}
+
+impl<T, const N: usize> ToStatic for Take<T, N>
+where
+	T: ToStatic,
+{
+	type Static = Take<T::Static, N>;
+
+	fn to_static(&self) -> Self::Static { Take(self.0.to_static()) }
+}
+
+impl<T> ToStatic for Satisfy<T>
+where
+	T: ToStatic,
+{
+	type Static = Satisfy<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Satisfy(self.0.to_static()) }
+}
+
+impl<T> ToStatic for Literal<'_, T>
+where
+	T: Clone + 'static,
+{
+	type Static = Literal<'static, T>;
+
+	fn to_static(&self) -> Self::Static { Literal(Cow::Owned(self.0.to_vec())) }
+}
+
+impl<P> ToStatic for Peek<P>
+where
+	P: ToStatic,
+{
+	type Static = Peek<P::Static>;
+
+	fn to_static(&self) -> Self::Static { Peek(self.0.to_static()) }
+}
+
+impl<P> ToStatic for Not<P>
+where
+	P: ToStatic,
+{
+	type Static = Not<P::Static>;
+
+	fn to_static(&self) -> Self::Static { Not(PhantomData) }
+}
@@ -1,6 +1,16 @@
-use crate::{error::Error, helpers::try_split_array, Deparsable, PResultBytes, Parsable};
+use crate::{
+	error::Error,
+	helpers::try_split_array,
+	owned::ToStatic,
+	pod::{bulk_read, bulk_write, Bulk, FixedSize, FromBytes},
+	schema::{Endian, Schema, TypeSchema},
+	Deparsable, PResultBytes, Parsable,
+};
 
-#[derive(Debug, Clone)]
+// The value is held decoded, in native order and alignment, so the bytes of an `LE` are not its
+// encoding on big endian targets and it can not be `FromBytes`. `PackedLE` is the `FromBytes` form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
 pub struct LE<T>(pub T);
 
 impl<T> AsRef<T> for LE<T> {
@@ -11,7 +21,9 @@ impl<T> AsMut<T> for LE<T> {
 	fn as_mut(&mut self) -> &mut T { &mut self.0 }
 }
 
-#[derive(Debug, Clone)]
+// As with `LE`, `PackedBE` is the `FromBytes` form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
 pub struct BE<T>(pub T);
 
 impl<T> AsRef<T> for BE<T> {
@@ -22,40 +34,109 @@ impl<T> AsMut<T> for BE<T> {
 	fn as_mut(&mut self) -> &mut T { &mut self.0 }
 }
 
+impl<T> FixedSize for LE<T>
+where
+	T: FixedSize,
+{
+	const SIZE: usize = T::SIZE;
+}
+
+impl<T> FixedSize for BE<T>
+where
+	T: FixedSize,
+{
+	const SIZE: usize = T::SIZE;
+}
+
+impl<T> Schema for LE<T>
+where
+	T: Schema,
+{
+	fn schema() -> TypeSchema { T::schema().with_endian("LE", Endian::Little) }
+}
+
+impl<T> Schema for BE<T>
+where
+	T: Schema,
+{
+	fn schema() -> TypeSchema { T::schema().with_endian("BE", Endian::Big) }
+}
+
+impl<T> ToStatic for LE<T>
+where
+	T: ToStatic,
+{
+	type Static = LE<T::Static>;
+
+	fn to_static(&self) -> Self::Static { LE(self.0.to_static()) }
+}
+
+impl<T> ToStatic for BE<T>
+where
+	T: ToStatic,
+{
+	type Static = BE<T::Static>;
+
+	fn to_static(&self) -> Self::Static { BE(self.0.to_static()) }
+}
+
 macro_rules! impl_prim {
 	($ty:ident $size:literal) => {
 		impl Parsable<'_, [u8]> for LE<$ty> {
 			#[inline]
-			fn read(source: &[u8], _context: ()) -> PResultBytes<Self> {
+			fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
 				let (head, source) =
 					try_split_array::<_, $size>(source).ok_or((Error::NotEnoughBytes, source))?;
 				let prim = $ty::from_le_bytes(*head);
 				Ok((Self(prim), source))
 			}
+
+			bulk_read!();
 		}
 
 		impl Parsable<'_, [u8]> for BE<$ty> {
 			#[inline]
-			fn read(source: &[u8], _context: ()) -> PResultBytes<Self> {
+			fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
 				let (head, source) =
 					try_split_array::<_, $size>(source).ok_or((Error::NotEnoughBytes, source))?;
 				let prim = $ty::from_be_bytes(*head);
 				Ok((Self(prim), source))
 			}
+
+			bulk_read!();
 		}
 
 		impl Deparsable for LE<$ty> {
 			#[inline]
-			fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+			fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
 				w.write_all(&self.0.to_le_bytes())
 			}
+
+			bulk_write!();
 		}
 
 		impl Deparsable for BE<$ty> {
 			#[inline]
-			fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+			fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
 				w.write_all(&self.0.to_be_bytes())
 			}
+
+			bulk_write!();
+		}
+
+		// Safety: the wrappers are transparent over primitives
+		unsafe impl Bulk for LE<$ty> {
+			const NATIVE: bool = cfg!(target_endian = "little");
+
+			#[inline]
+			fn swap(self) -> Self { Self($ty::from_le_bytes(self.0.to_ne_bytes())) }
+		}
+
+		unsafe impl Bulk for BE<$ty> {
+			const NATIVE: bool = cfg!(target_endian = "big");
+
+			#[inline]
+			fn swap(self) -> Self { Self($ty::from_be_bytes(self.0.to_ne_bytes())) }
 		}
 	};
 }
@@ -70,4 +151,144 @@ macro_rules! impl_prims {
 	};
 }
 
-impl_prims!(u8 1 i8 1 u16 2 i16 2 u32 4 i32 4 u64 8 i64 8 f32 4 f64 8);
+impl_prims!(u8 1 i8 1 u16 2 i16 2 u32 4 i32 4 u64 8 i64 8 u128 16 i128 16 f32 4 f64 8);
+
+// Primitives which can be stored as bytes in a `PackedLE` or `PackedBE`
+pub trait Primitive: Copy {
+	type Bytes: FromBytes + Default + Eq + std::hash::Hash;
+
+	fn from_le(bytes: Self::Bytes) -> Self;
+	fn from_be(bytes: Self::Bytes) -> Self;
+	fn to_le(self) -> Self::Bytes;
+	fn to_be(self) -> Self::Bytes;
+}
+
+// Little endian values kept as their bytes, so that they have an alignment of 1 and can be used in
+// `FromBytes` types. `LE` holds the decoded value instead
+#[repr(transparent)]
+pub struct PackedLE<T: Primitive>(T::Bytes);
+
+// Big endian values kept as their bytes
+#[repr(transparent)]
+pub struct PackedBE<T: Primitive>(T::Bytes);
+
+macro_rules! impl_packed {
+	($name:ident $from:ident $to:ident $wrapper:ident $endian:expr) => {
+		impl<T: Primitive> $name<T> {
+			#[inline]
+			pub fn new(value: T) -> Self { Self(value.$to()) }
+
+			#[inline]
+			pub fn get(self) -> T { T::$from(self.0) }
+		}
+
+		impl<T: Primitive> Clone for $name<T> {
+			fn clone(&self) -> Self { *self }
+		}
+
+		impl<T: Primitive> Copy for $name<T> {}
+
+		impl<T: Primitive> PartialEq for $name<T> {
+			fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+		}
+
+		impl<T: Primitive> Eq for $name<T> {}
+
+		impl<T: Primitive> std::hash::Hash for $name<T> {
+			fn hash<H: std::hash::Hasher>(&self, state: &mut H) { self.0.hash(state) }
+		}
+
+		impl<T: Primitive> Default for $name<T> {
+			fn default() -> Self { Self(T::Bytes::default()) }
+		}
+
+		impl<T: Primitive + std::fmt::Debug> std::fmt::Debug for $name<T> {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.debug_tuple(stringify!($name)).field(&self.get()).finish()
+			}
+		}
+
+		impl<T: Primitive> From<T> for $name<T> {
+			fn from(value: T) -> Self { Self::new(value) }
+		}
+
+		impl<T: Primitive> From<$name<T>> for $wrapper<T> {
+			fn from(value: $name<T>) -> Self { $wrapper(value.get()) }
+		}
+
+		impl<T: Primitive> From<$wrapper<T>> for $name<T> {
+			fn from(value: $wrapper<T>) -> Self { Self::new(value.0) }
+		}
+
+		// Safety: the only field is an array of bytes
+		unsafe impl<T: Primitive + 'static> FromBytes for $name<T> {}
+
+		impl<'a, T: Primitive + 'static> Parsable<'a, [u8]> for $name<T> {
+			#[inline]
+			fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+				let (value, source) = <&Self>::read(source, ())?;
+				Ok((*value, source))
+			}
+
+			bulk_read!();
+		}
+
+		impl<T: Primitive + 'static> Deparsable for $name<T> {
+			#[inline]
+			fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+				w.write_all(self.as_bytes())
+			}
+
+			bulk_write!();
+		}
+
+		// Safety: the only field is an array of bytes
+		unsafe impl<T: Primitive + 'static> Bulk for $name<T> {
+			const NATIVE: bool = true;
+
+			#[inline]
+			fn swap(self) -> Self { self }
+		}
+
+		impl<T: Primitive + Schema> Schema for $name<T> {
+			fn schema() -> TypeSchema { T::schema().with_endian(stringify!($name), $endian) }
+		}
+
+		impl<T: Primitive> FixedSize for $name<T> {
+			const SIZE: usize = size_of::<T::Bytes>();
+		}
+
+		impl<T: Primitive + 'static> ToStatic for $name<T> {
+			type Static = Self;
+
+			fn to_static(&self) -> Self { *self }
+		}
+	};
+}
+
+impl_packed!(PackedLE from_le to_le LE Endian::Little);
+impl_packed!(PackedBE from_be to_be BE Endian::Big);
+
+macro_rules! impl_primitive {
+	($( $ty:ident $size:literal )*) => {
+		$(
+			impl Primitive for $ty {
+				type Bytes = [u8; $size];
+
+				#[inline]
+				fn from_le(bytes: Self::Bytes) -> Self { $ty::from_le_bytes(bytes) }
+
+				#[inline]
+				fn from_be(bytes: Self::Bytes) -> Self { $ty::from_be_bytes(bytes) }
+
+				#[inline]
+				fn to_le(self) -> Self::Bytes { self.to_le_bytes() }
+
+				#[inline]
+				fn to_be(self) -> Self::Bytes { self.to_be_bytes() }
+			}
+		)*
+	};
+}
+
+impl_primitive!(u8 1 i8 1 u16 2 i16 2 u32 4 i32 4 u64 8 i64 8 u128 16 i128 16 f32 4 f64 8);
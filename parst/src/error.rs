@@ -1,11 +1,31 @@
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
 pub enum Error {
 	#[error("invalid input")]
 	InvalidInput,
 	#[error("not enough bytes")]
 	NotEnoughBytes,
-	#[error("assertion failed")]
-	AssertionFailed,
+	#[error("expected {0:?}")]
+	Expected(&'static str),
+	#[error("invalid {0}")]
+	InvalidEncoding(&'static str),
+	// Describes the check which failed. This used to hold nothing, so matches on
+	// `Error::AssertionFailed` now need `Error::AssertionFailed(_)`
+	#[error("assertion failed: {0}")]
+	AssertionFailed(&'static str),
+	#[error("allocation limit exceeded")]
+	LimitExceeded,
+	#[error("recursion limit reached")]
+	RecursionLimit,
+}
+
+impl Error {
+	// Running out of a limit fails the whole read, where other errors may only end a sequence or
+	// rule out an alternative
+	pub fn is_limit(&self) -> bool { matches!(self, Error::LimitExceeded | Error::RecursionLimit) }
+}
+
+impl From<Error> for std::io::Error {
+	fn from(value: Error) -> Self { std::io::Error::new(std::io::ErrorKind::InvalidData, value) }
 }
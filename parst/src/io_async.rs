@@ -0,0 +1,129 @@
+use crate::{error::Error, pod::FixedSize, Deparsable, Parsable};
+use std::io;
+use tokio::io::{
+	AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+};
+
+fn parse<T, Ctx>(bytes: &[u8], context: Ctx) -> io::Result<T>
+where
+	T: for<'a> Parsable<'a, [u8], Ctx>,
+{
+	T::read(bytes, context)
+		.map(|(value, _)| value)
+		.map_err(|(e, _)| e.into())
+}
+
+// Reads a `T` out of exactly the `T::SIZE` bytes it takes up
+pub async fn read_fixed<T, R, Ctx>(reader: &mut R, context: Ctx) -> io::Result<T>
+where
+	R: AsyncRead + Unpin + ?Sized,
+	T: FixedSize + for<'a> Parsable<'a, [u8], Ctx>,
+{
+	let mut buffer = vec![0; T::SIZE];
+	reader.read_exact(&mut buffer).await?;
+	parse(&buffer, context)
+}
+
+// Reads a length `L`, then a `T` out of exactly that many bytes, skipping whatever it leaves of them.
+// A length over `max_len` is rejected before anything more is read
+pub async fn read_prefixed<L, T, R, Ctx>(
+	reader: &mut R,
+	max_len: usize,
+	context: Ctx,
+) -> io::Result<T>
+where
+	R: AsyncRead + Unpin + ?Sized,
+	L: FixedSize + Into<u64> + for<'a> Parsable<'a, [u8]>,
+	T: for<'a> Parsable<'a, [u8], Ctx>,
+{
+	let len = read_fixed::<L, _, _>(reader, ()).await?.into();
+	if len > max_len as u64 {
+		return Err(Error::LimitExceeded.into());
+	}
+
+	// The buffer only grows as the bytes arrive, rather than trusting the length up front
+	let mut buffer = Vec::new();
+	(&mut *reader).take(len).read_to_end(&mut buffer).await?;
+	if (buffer.len() as u64) < len {
+		return Err(io::ErrorKind::UnexpectedEof.into());
+	}
+	parse(&buffer, context)
+}
+
+// Reads a `T` of any length, taking only the bytes it uses out of the reader and leaving the rest
+// buffered. As with `read_iter`, a `T` which runs out of bytes might only be cut short by what has
+// arrived so far, so more is read before giving up, until the value would be longer than `max_len`.
+// A `T` is returned as soon as it parses, so one reading to the end of its input, such as a `Vec`
+// without a count, only holds what has arrived by then
+pub async fn read_buffered<T, R, Ctx>(reader: &mut R, max_len: usize, context: Ctx) -> io::Result<T>
+where
+	R: AsyncBufRead + Unpin + ?Sized,
+	Ctx: Copy,
+	T: for<'a> Parsable<'a, [u8], Ctx>,
+{
+	// Bytes already taken out of the reader, every one of which belongs to the value
+	let mut taken = Vec::new();
+	loop {
+		let available = reader.fill_buf().await?;
+		let eof = available.is_empty();
+		let before = taken.len();
+		taken.extend_from_slice(available);
+
+		let error = match T::read(&taken, context) {
+			Ok((value, remainder)) => {
+				let used = taken.len() - remainder.len();
+				if used < before {
+					return Err(io::Error::new(
+						io::ErrorKind::InvalidData,
+						"value ended before bytes already taken from the reader",
+					));
+				}
+				reader.consume(used - before);
+				return Ok(value);
+			}
+			Err((e, _)) => e,
+		};
+
+		match error {
+			Error::NotEnoughBytes if eof => return Err(io::ErrorKind::UnexpectedEof.into()),
+			Error::NotEnoughBytes if taken.len() < max_len => reader.consume(taken.len() - before),
+			Error::NotEnoughBytes => return Err(Error::LimitExceeded.into()),
+			e => return Err(e.into()),
+		}
+	}
+}
+
+// Writes the whole of `value` at once, and flushes it
+pub async fn write<T, W, Ctx>(writer: &mut W, value: &T, context: Ctx) -> io::Result<()>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+	T: Deparsable<Ctx> + ?Sized,
+{
+	let mut buffer = Vec::new();
+	value.write(&mut buffer, context)?;
+	writer.write_all(&buffer).await?;
+	writer.flush().await
+}
+
+// Writes `value` after its length as an `L`, to be read back with `read_prefixed`
+pub async fn write_prefixed<L, T, W, Ctx>(writer: &mut W, value: &T, context: Ctx) -> io::Result<()>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+	L: TryFrom<usize> + Deparsable,
+	T: Deparsable<Ctx> + ?Sized,
+{
+	let mut value_bytes = Vec::new();
+	value.write(&mut value_bytes, context)?;
+	let len = L::try_from(value_bytes.len()).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"value is too long for its length prefix",
+		)
+	})?;
+
+	let mut buffer = Vec::new();
+	len.write(&mut buffer, ())?;
+	buffer.extend_from_slice(&value_bytes);
+	writer.write_all(&buffer).await?;
+	writer.flush().await
+}
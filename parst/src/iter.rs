@@ -0,0 +1,208 @@
+use crate::{error::Error, Parsable};
+use std::{io::Read, marker::PhantomData};
+
+// Sources which records can be read from one after another until they run out
+pub trait Source {
+	fn len(&self) -> usize;
+
+	fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+impl<T> Source for [T] {
+	fn len(&self) -> usize { self.len() }
+}
+
+impl Source for str {
+	fn len(&self) -> usize { self.len() }
+}
+
+// Reads one `T` after another, stopping at the end of the source or after the first error
+pub fn iter<'a, T, Src, Ctx>(source: &'a Src, context: Ctx) -> Records<'a, T, Src, Ctx>
+where
+	Src: Source + ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx>,
+{
+	Records {
+		source,
+		offset: 0,
+		failed: false,
+		context,
+		record: PhantomData,
+	}
+}
+
+pub struct Records<'a, T, Src: ?Sized, Ctx> {
+	source: &'a Src,
+	offset: usize,
+	failed: bool,
+	context: Ctx,
+	record: PhantomData<T>,
+}
+
+impl<'a, T, Src, Ctx> Records<'a, T, Src, Ctx>
+where
+	Src: ?Sized,
+{
+	// Where the next record starts, or after an error, where the record which failed starts
+	pub fn offset(&self) -> usize { self.offset }
+
+	pub fn remainder(&self) -> &'a Src { self.source }
+}
+
+impl<'a, T, Src, Ctx> Iterator for Records<'a, T, Src, Ctx>
+where
+	Src: Source + ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx>,
+{
+	type Item = Result<T, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.failed || self.source.is_empty() {
+			return None;
+		}
+		let error = match T::read(self.source, self.context) {
+			// A record which takes up nothing would be read forever
+			Ok((_, remainder)) if remainder.len() == self.source.len() => Error::InvalidInput,
+			Ok((record, remainder)) => {
+				self.offset += self.source.len() - remainder.len();
+				self.source = remainder;
+				return Some(Ok(record));
+			}
+			Err((e, _)) => e,
+		};
+		self.failed = true;
+		Some(Err(error))
+	}
+}
+
+// Reads one `T` after another out of a reader, holding only as much of it as the next record needs
+pub fn read_iter<T, R, Ctx>(reader: R, context: Ctx) -> ReadRecords<T, R, Ctx>
+where
+	R: Read,
+	Ctx: Copy,
+	T: for<'a> Parsable<'a, [u8], Ctx>,
+{
+	ReadRecords {
+		reader,
+		buffer: Vec::new(),
+		start: 0,
+		offset: 0,
+		eof: false,
+		failed: false,
+		max_record: 1 << 20,
+		context,
+		record: PhantomData,
+	}
+}
+
+// A record which could not be parsed out of a reader, and where in the reader it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("record at offset {offset}: {error}")]
+pub struct RecordError {
+	pub offset: u64,
+	pub error: Error,
+}
+
+impl From<RecordError> for std::io::Error {
+	fn from(value: RecordError) -> Self {
+		std::io::Error::new(std::io::ErrorKind::InvalidData, value)
+	}
+}
+
+// A record which can not be parsed might only be cut short by the end of the buffer, so more is
+// read before giving up, until the record would be longer than `max_record`. Records which fail
+// are yielded as a `RecordError` inside the `io::Error`
+pub struct ReadRecords<T, R, Ctx> {
+	reader: R,
+	buffer: Vec<u8>,
+	start: usize,
+	offset: u64,
+	eof: bool,
+	failed: bool,
+	max_record: usize,
+	context: Ctx,
+	record: PhantomData<T>,
+}
+
+impl<T, R, Ctx> ReadRecords<T, R, Ctx>
+where
+	R: Read,
+{
+	pub fn with_max_record(self, max_record: usize) -> Self { Self { max_record, ..self } }
+
+	// Where the next record starts, or after an error, where the record which failed starts
+	pub fn offset(&self) -> u64 { self.offset }
+
+	pub fn into_inner(self) -> R { self.reader }
+
+	fn fill(&mut self) -> std::io::Result<()> {
+		if self.start > self.buffer.len() / 2 {
+			self.buffer.drain(..self.start);
+			self.start = 0;
+		}
+		let len = self.buffer.len();
+		let want = (len - self.start).max(8192).min(self.max_record);
+		self.buffer.resize(len + want, 0);
+		let read = loop {
+			match self.reader.read(&mut self.buffer[len..]) {
+				Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+				result => break result,
+			}
+		};
+		self.buffer.truncate(len + *read.as_ref().unwrap_or(&0));
+		self.eof |= read? == 0;
+		Ok(())
+	}
+}
+
+impl<T, R, Ctx> Iterator for ReadRecords<T, R, Ctx>
+where
+	R: Read,
+	Ctx: Copy,
+	T: for<'a> Parsable<'a, [u8], Ctx>,
+{
+	type Item = std::io::Result<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.failed {
+			return None;
+		}
+		loop {
+			let source = &self.buffer[self.start..];
+			let can_fill = !self.eof && source.len() < self.max_record;
+			let error = match T::read(source, self.context) {
+				Ok((_, remainder)) if remainder.len() == source.len() && !source.is_empty() => {
+					Error::InvalidInput
+				}
+				Ok((record, remainder))
+					if !source.is_empty() && (!can_fill || !remainder.is_empty()) =>
+				{
+					let consumed = source.len() - remainder.len();
+					self.start += consumed;
+					self.offset += consumed as u64;
+					return Some(Ok(record));
+				}
+				Ok(_) => Error::NotEnoughBytes,
+				Err((e, _)) => e,
+			};
+
+			// Only a record cut short might continue past what has been read so far, and any other
+			// error is in the bytes read already
+			if can_fill && (error == Error::NotEnoughBytes || source.is_empty()) {
+				if let Err(e) = self.fill() {
+					self.failed = true;
+					return Some(Err(e));
+				}
+				continue;
+			}
+			if self.eof && self.start == self.buffer.len() {
+				return None;
+			}
+			self.failed = true;
+			let offset = self.offset;
+			return Some(Err(RecordError { offset, error }.into()));
+		}
+	}
+}
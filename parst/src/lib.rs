@@ -1,19 +1,40 @@
-#![feature(split_array)]
 #![feature(array_try_from_fn)]
 
+#[cfg(feature = "bytes")]
+pub mod bytes;
+#[cfg(feature = "codec")]
+pub mod codec;
+pub mod element;
 #[cfg(feature = "endian")]
 pub mod endian;
 pub mod error;
+#[cfg(feature = "async")]
+pub mod io_async;
+pub mod iter;
+pub mod limits;
+pub mod owned;
+pub mod pod;
+pub mod schema;
+pub mod spans;
+pub mod tag;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "trace")]
+pub mod trace;
 
 pub(crate) mod helpers;
 
+pub use iter::{iter, read_iter};
+
 mod collections;
 mod primitives;
 
-use std::io::Write;
+use std::{array::try_from_fn, io::Write};
 
+#[cfg(all(feature = "derive", feature = "testing"))]
+pub use parst_derive::Arbitrary;
 #[cfg(feature = "derive")]
-pub use parst_derive::{Deparsable, Parsable};
+pub use parst_derive::{Deparsable, FromBytes, Parsable, ToStatic};
 
 pub type PResult<'a, O, S, E = crate::error::Error> = std::result::Result<(O, &'a S), (E, &'a S)>;
 pub type PResultBytes<'a, O> = PResult<'a, O, [u8]>;
@@ -23,9 +44,81 @@ pub trait Parsable<'a, Src, Ctx = ()>: Sized
 where
 	Src: ?Sized,
 {
-	fn read(source: &'a Src, context: Ctx) -> PResult<Self, Src>;
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src>;
+
+	// Reads `N` values in a row. Types which can be copied straight out of the source override this
+	// and `read_vec`, which the collections call instead of reading each element
+	#[inline]
+	fn read_array<const N: usize>(mut source: &'a Src, context: Ctx) -> PResult<'a, [Self; N], Src>
+	where
+		Ctx: Copy,
+	{
+		try_from_fn(|_| {
+			let (element, remainder) = Self::read(source, context)?;
+			source = remainder;
+			Ok(element)
+		})
+		.map(|array| (array, source))
+	}
+
+	// Reads `count` values in a row, or as many as can be read when there is no count. Each value
+	// is taken out of the current `limits`
+	#[inline]
+	fn read_vec(
+		mut source: &'a Src,
+		context: Ctx,
+		count: Option<usize>,
+	) -> PResult<'a, Vec<Self>, Src>
+	where
+		Ctx: Copy,
+	{
+		let mut v = Vec::new();
+		match count {
+			Some(count) => {
+				limits::reserve::<Self>(count).map_err(|e| (e, source))?;
+				for read in 0..count {
+					let (element, remainder) = Self::read(source, context)?;
+					// Each value left is read out of the same source, so none of them take up any
+					// of it either and the input does not bound the count
+					if std::ptr::eq(source, remainder) {
+						limits::check_empty(count - read).map_err(|e| (e, source))?;
+					}
+					v.push(element);
+					source = remainder;
+				}
+			}
+			None => loop {
+				let (element, remainder) = match Self::read(source, context) {
+					Ok(read) => read,
+					Err((e, s)) if e.is_limit() => return Err((e, s)),
+					Err(_) => break,
+				};
+				limits::reserve::<Self>(1).map_err(|e| (e, source))?;
+				v.push(element);
+				// A value taking up none of the source would be read forever
+				let progressed = !std::ptr::eq(source, remainder);
+				source = remainder;
+				if !progressed {
+					break;
+				}
+			},
+		}
+		Ok((v, source))
+	}
 }
 
 pub trait Deparsable<Ctx = ()> {
-	fn write(&mut self, w: &mut impl Write, context: Ctx) -> std::io::Result<()>;
+	fn write(&self, w: &mut impl Write, context: Ctx) -> std::io::Result<()>;
+
+	// Writes each value in a row, overridden in the same way as `Parsable::read_vec`
+	#[inline]
+	fn write_slice(slice: &[Self], w: &mut impl Write, context: Ctx) -> std::io::Result<()>
+	where
+		Self: Sized,
+		Ctx: Copy,
+	{
+		slice
+			.iter()
+			.try_for_each(|element| element.write(&mut *w, context))
+	}
 }
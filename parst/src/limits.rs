@@ -0,0 +1,154 @@
+// Bounds on how much memory parsing may set aside for the counts and lengths read out of the input,
+// and on how deeply recursive types may nest, so that hostile input can not ask for more than
+// allowed. They apply to reads on this thread while `with_limits` runs, and outside of it only the
+// `max_depth` of each type is
+
+use crate::{error::Error, PResult};
+use std::cell::Cell;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+	max_bytes: usize,
+	max_depth: usize,
+}
+
+impl Limits {
+	pub const UNLIMITED: Self = Self {
+		max_bytes: usize::MAX,
+		max_depth: usize::MAX,
+	};
+
+	// The total size of the elements that collections may hold across the whole parse
+	pub const fn with_max_bytes(self, max_bytes: usize) -> Self { Self { max_bytes, ..self } }
+
+	// How many reads of types with a `max_depth` may be nested inside each other, on top of the
+	// `max_depth` of each
+	pub const fn with_max_depth(self, max_depth: usize) -> Self { Self { max_depth, ..self } }
+
+	pub const fn max_bytes(&self) -> usize { self.max_bytes }
+
+	pub const fn max_depth(&self) -> usize { self.max_depth }
+}
+
+impl Default for Limits {
+	fn default() -> Self { Self::UNLIMITED }
+}
+
+thread_local! {
+	// What is left of the limits being parsed under
+	static REMAINING: Cell<Limits> = const { Cell::new(Limits::UNLIMITED) };
+	// How many reads of types with a `max_depth` are in progress
+	static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+// Runs `f` with the limits, which can only tighten those already in place. The bytes it uses are
+// taken out of the outer limits afterwards
+pub fn with_limits<R>(limits: Limits, f: impl FnOnce() -> R) -> R {
+	struct Restore {
+		outer: Limits,
+		start: Limits,
+	}
+
+	impl Drop for Restore {
+		fn drop(&mut self) {
+			let used = self.start.max_bytes - REMAINING.get().max_bytes;
+			let max_bytes = match self.outer.max_bytes {
+				usize::MAX => usize::MAX,
+				outer => outer.saturating_sub(used),
+			};
+			REMAINING.set(Limits {
+				max_bytes,
+				..self.outer
+			});
+		}
+	}
+
+	let outer = REMAINING.get();
+	let start = Limits {
+		max_bytes: limits.max_bytes.min(outer.max_bytes),
+		max_depth: limits.max_depth.min(outer.max_depth),
+	};
+	REMAINING.set(start);
+	let _restore = Restore { outer, start };
+	f()
+}
+
+// What is left of the limits on this thread
+pub fn remaining() -> Limits { REMAINING.get() }
+
+// Sets aside room for `count` more values of `T`, each counted as at least a byte so that even
+// values taking no memory can not be read without end
+#[inline]
+pub fn reserve<T>(count: usize) -> Result<(), Error> {
+	let remaining = REMAINING.get();
+	if remaining.max_bytes == usize::MAX {
+		return Ok(());
+	}
+	let bytes = size_of::<T>()
+		.max(1)
+		.checked_mul(count)
+		.filter(|&bytes| bytes <= remaining.max_bytes)
+		.ok_or(Error::LimitExceeded)?;
+	REMAINING.set(Limits {
+		max_bytes: remaining.max_bytes - bytes,
+		..remaining
+	});
+	Ok(())
+}
+
+// How many values read out of none of the input a sequence may hold while `max_bytes` is not set.
+// The input bounds how many of any other values there are, as each takes up some of it
+pub const MAX_EMPTY_VALUES: usize = 1 << 16;
+
+// Checks that `count` more values read out of none of the input fit. Under a `max_bytes` they were
+// set aside already, as each counts as a byte, and otherwise there may be `MAX_EMPTY_VALUES`
+#[inline]
+pub fn check_empty(count: usize) -> Result<(), Error> {
+	match REMAINING.get().max_bytes == usize::MAX && count > MAX_EMPTY_VALUES {
+		true => Err(Error::LimitExceeded),
+		false => Ok(()),
+	}
+}
+
+// Held while reading a type with a `max_depth`. Sequences without a count and options try one
+// more value at the end of their input, so a read one level past the limit is still entered, and
+// only fails once it gives a value. Nothing is entered inside it
+pub struct DepthGuard {
+	past_limit: bool,
+}
+
+impl DepthGuard {
+	#[inline]
+	pub fn enter(max_depth: usize) -> Result<Self, Error> {
+		let depth = DEPTH.get();
+		let max_depth = max_depth.min(REMAINING.get().max_depth);
+		if depth > max_depth {
+			return Err(Error::RecursionLimit);
+		}
+		DEPTH.set(depth + 1);
+		Ok(Self {
+			past_limit: depth == max_depth,
+		})
+	}
+
+	// Leaves the type with the result of reading it out of `source`, which fails if it was read past
+	// the limit
+	#[inline]
+	pub fn leave<'a, T, S>(self, source: &'a S, result: PResult<'a, T, S>) -> PResult<'a, T, S>
+	where
+		S: ?Sized,
+	{
+		match result {
+			Ok(_) if self.past_limit => Err((Error::RecursionLimit, source)),
+			result => result,
+		}
+	}
+}
+
+impl Drop for DepthGuard {
+	#[inline]
+	fn drop(&mut self) { DEPTH.set(DEPTH.get() - 1) }
+}
+
+// How many reads of types with a `max_depth` are in progress on this thread
+pub fn depth() -> usize { DEPTH.get() }
@@ -0,0 +1,213 @@
+use crate::{pod::FromBytes, Deparsable, PResultBytes, PResultStr, Parsable};
+use std::{
+	borrow::Cow,
+	marker::PhantomData,
+	net::{Ipv4Addr, Ipv6Addr},
+	num::{
+		NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+		NonZeroU32, NonZeroU64, NonZeroU8,
+	},
+	ops::Deref,
+	time::Duration,
+};
+
+pub trait ToStatic {
+	type Static: 'static;
+
+	fn to_static(&self) -> Self::Static;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct OwnedBytes(pub Vec<u8>);
+
+impl Deref for OwnedBytes {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] { &self.0 }
+}
+
+impl AsRef<[u8]> for OwnedBytes {
+	fn as_ref(&self) -> &[u8] { &self.0 }
+}
+
+impl From<&[u8]> for OwnedBytes {
+	fn from(value: &[u8]) -> Self { Self(value.to_vec()) }
+}
+
+impl<'a> Parsable<'a, [u8]> for OwnedBytes {
+	#[inline]
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		Ok((Self(source.to_vec()), &[]))
+	}
+}
+
+impl Deparsable for OwnedBytes {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(&self.0)
+	}
+}
+
+impl<'a> Parsable<'a, [u8]> for Cow<'a, [u8]> {
+	#[inline]
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		Ok((Cow::Borrowed(source), &[]))
+	}
+}
+
+impl Deparsable for Cow<'_, [u8]> {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(self)
+	}
+}
+
+impl<'a> Parsable<'a, str> for Cow<'a, str> {
+	#[inline]
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> {
+		Ok((Cow::Borrowed(source), ""))
+	}
+}
+
+impl Deparsable for Cow<'_, str> {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(self.as_bytes())
+	}
+}
+
+macro_rules! impl_static {
+	($( $ty:ty )*) => {
+		$(
+			impl ToStatic for $ty {
+				type Static = Self;
+
+				#[inline]
+				fn to_static(&self) -> Self { *self }
+			}
+		)*
+	};
+}
+
+impl_static!(() bool char u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize f32 f64);
+impl_static!(
+	NonZeroU8 NonZeroI8 NonZeroU16 NonZeroI16 NonZeroU32 NonZeroI32 NonZeroU64 NonZeroI64
+	NonZeroU128 NonZeroI128 Ipv4Addr Ipv6Addr Duration
+);
+
+impl ToStatic for OwnedBytes {
+	type Static = Self;
+
+	fn to_static(&self) -> Self { self.clone() }
+}
+
+impl ToStatic for String {
+	type Static = Self;
+
+	fn to_static(&self) -> Self { self.clone() }
+}
+
+impl ToStatic for &[u8] {
+	type Static = OwnedBytes;
+
+	fn to_static(&self) -> OwnedBytes { OwnedBytes(self.to_vec()) }
+}
+
+impl<T> ToStatic for &T
+where
+	T: FromBytes,
+{
+	type Static = T;
+
+	fn to_static(&self) -> T { **self }
+}
+
+impl ToStatic for &str {
+	type Static = Cow<'static, str>;
+
+	fn to_static(&self) -> Self::Static { Cow::Owned(self.to_string()) }
+}
+
+impl ToStatic for Cow<'_, [u8]> {
+	type Static = Cow<'static, [u8]>;
+
+	fn to_static(&self) -> Self::Static { Cow::Owned(self.to_vec()) }
+}
+
+impl ToStatic for Cow<'_, str> {
+	type Static = Cow<'static, str>;
+
+	fn to_static(&self) -> Self::Static { Cow::Owned(self.to_string()) }
+}
+
+impl<T, const N: usize> ToStatic for [T; N]
+where
+	T: ToStatic,
+{
+	type Static = [T::Static; N];
+
+	fn to_static(&self) -> Self::Static { self.each_ref().map(ToStatic::to_static) }
+}
+
+impl<T> ToStatic for Vec<T>
+where
+	T: ToStatic,
+{
+	type Static = Vec<T::Static>;
+
+	fn to_static(&self) -> Self::Static { self.iter().map(ToStatic::to_static).collect() }
+}
+
+impl<T> ToStatic for Box<T>
+where
+	T: ToStatic,
+{
+	type Static = Box<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Box::new(self.deref().to_static()) }
+}
+
+impl<T> ToStatic for Option<T>
+where
+	T: ToStatic,
+{
+	type Static = Option<T::Static>;
+
+	fn to_static(&self) -> Self::Static { self.as_ref().map(ToStatic::to_static) }
+}
+
+impl<T> ToStatic for PhantomData<T>
+where
+	T: ToStatic,
+{
+	type Static = PhantomData<T::Static>;
+
+	fn to_static(&self) -> Self::Static { PhantomData }
+}
+
+macro_rules! impl_tuple {
+	($name:ident $ty:ident) => {
+		impl_tuple!(@impl $name $ty);
+	};
+	($name:ident $ty:ident $( $N:ident $T:ident )+) => {
+		impl_tuple!($( $N $T )+);
+		impl_tuple!(@impl $name $ty $( $N $T )+);
+	};
+	(@impl $( $N:ident $T:ident )+) => {
+		impl<$( $T ),+> ToStatic for ($( $T, )+)
+		where
+			$(
+				$T: ToStatic,
+			)+
+		{
+			type Static = ($( $T::Static, )+);
+
+			fn to_static(&self) -> Self::Static {
+				let ( $( $N, )+ ) = self;
+				($( $N.to_static(), )+)
+			}
+		}
+	};
+}
+
+impl_tuple!(a A b B c C d D e E f F g G h H i I j J k K l L m M n N o O p P);
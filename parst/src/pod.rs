@@ -0,0 +1,258 @@
+use crate::{error::Error, limits, Deparsable, PResultBytes, Parsable};
+use std::{
+	marker::PhantomData,
+	mem::{size_of, MaybeUninit},
+	net::{Ipv4Addr, Ipv6Addr},
+	num::{
+		NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+		NonZeroU32, NonZeroU64, NonZeroU8,
+	},
+	time::Duration,
+};
+
+/// Types which can be read directly out of any bytes and viewed as bytes in place
+///
+/// # Safety
+///
+/// Implementors must have an alignment of 1, no padding, no interior mutability, and every bit
+/// pattern of the right size must be a valid value. `#[derive(FromBytes)]` checks these for
+/// `#[repr(C)]` structs built from other `FromBytes` types.
+pub unsafe trait FromBytes: Copy + 'static {
+	#[inline]
+	fn ref_from_prefix(source: &[u8]) -> Option<(&Self, &[u8])> {
+		let (bytes, source) = source.split_at_checked(size_of::<Self>())?;
+		// Safety: the bytes are long enough, and any alignment and bit pattern is valid
+		Some((unsafe { &*bytes.as_ptr().cast::<Self>() }, source))
+	}
+
+	#[inline]
+	fn slice_from_prefix(source: &[u8], count: usize) -> Option<(&[Self], &[u8])> {
+		let (bytes, source) = source.split_at_checked(size_of::<Self>().checked_mul(count)?)?;
+		// Safety: as above, for `count` values in a row
+		let slice = unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast::<Self>(), count) };
+		Some((slice, source))
+	}
+
+	#[inline]
+	fn as_bytes(&self) -> &[u8] { Self::slice_as_bytes(std::slice::from_ref(self)) }
+
+	#[inline]
+	fn slice_as_bytes(slice: &[Self]) -> &[u8] {
+		// Safety: there is no padding, so every byte is initialised
+		unsafe { std::slice::from_raw_parts(slice.as_ptr().cast::<u8>(), size_of_val(slice)) }
+	}
+}
+
+unsafe impl FromBytes for u8 {}
+unsafe impl FromBytes for i8 {}
+unsafe impl<T, const N: usize> FromBytes for [T; N] where T: FromBytes {}
+
+impl<'a, T> Parsable<'a, [u8]> for &'a T
+where
+	T: FromBytes,
+{
+	#[inline]
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		T::ref_from_prefix(source).ok_or((Error::NotEnoughBytes, source))
+	}
+}
+
+// The context is the number of values in the slice
+impl<'a, T> Parsable<'a, [u8], usize> for &'a [T]
+where
+	T: FromBytes,
+{
+	#[inline]
+	fn read(source: &'a [u8], context: usize) -> PResultBytes<'a, Self> {
+		T::slice_from_prefix(source, context).ok_or((Error::NotEnoughBytes, source))
+	}
+}
+
+// Slices are written in place whatever the context, so that a slice read with its length as the
+// context writes back with the same one
+impl<T, Ctx> Deparsable<Ctx> for [T]
+where
+	T: FromBytes,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> {
+		w.write_all(T::slice_as_bytes(self))
+	}
+}
+
+/// Types which are parsed from `[u8]` by copying their bytes into place and then, when the bytes are
+/// not in the native order, swapping them. Their `Parsable` and `Deparsable` impls use the helpers
+/// below for `read_array`, `read_vec` and `write_slice`
+///
+/// # Safety
+///
+/// Implementors must have no padding and every bit pattern of the right size must be a valid value
+pub unsafe trait Bulk: Copy + 'static {
+	// Whether `swap` does nothing, so that values can be copied without looking at them
+	const NATIVE: bool;
+
+	// Converts between the value copied out of the bytes and the value they hold. This is its own
+	// inverse, so it is also used when writing
+	fn swap(self) -> Self;
+}
+
+// Safety: `Bulk` has the same requirements as `FromBytes`, except for the alignment
+unsafe impl<T, const N: usize> Bulk for [T; N]
+where
+	T: Bulk,
+{
+	const NATIVE: bool = T::NATIVE;
+
+	#[inline]
+	fn swap(self) -> Self { self.map(T::swap) }
+}
+
+#[inline]
+fn copy_bulk<T>(source: &[u8], out: &mut [MaybeUninit<T>]) -> Option<usize>
+where
+	T: Bulk,
+{
+	let len = size_of::<T>().checked_mul(out.len())?;
+	if source.len() < len {
+		return None;
+	}
+	// Safety: the source has `len` bytes and the output has room for them, and any bytes are a
+	// valid `T`
+	unsafe { std::ptr::copy_nonoverlapping(source.as_ptr(), out.as_mut_ptr().cast::<u8>(), len) };
+	if !T::NATIVE {
+		out.iter_mut().for_each(|element| {
+			// Safety: every element was just initialised
+			let value = unsafe { element.assume_init() };
+			element.write(value.swap());
+		});
+	}
+	Some(len)
+}
+
+#[inline]
+pub fn read_bulk_array<T, const N: usize>(source: &[u8]) -> PResultBytes<'_, [T; N]>
+where
+	T: Bulk,
+{
+	let mut array: [MaybeUninit<T>; N] = [const { MaybeUninit::uninit() }; N];
+	let len = copy_bulk(source, &mut array).ok_or((Error::NotEnoughBytes, source))?;
+	// Safety: every element was initialised by the copy
+	let array = unsafe { (&raw const array).cast::<[T; N]>().read() };
+	Ok((array, &source[len..]))
+}
+
+// Without a count, as many whole values as are left are read, which is none of a type taking up no
+// bytes rather than any number of them
+#[inline]
+pub fn read_bulk_vec<T>(source: &[u8], count: Option<usize>) -> PResultBytes<'_, Vec<T>>
+where
+	T: Bulk,
+{
+	let count = count.unwrap_or_else(|| source.len().checked_div(size_of::<T>()).unwrap_or(0));
+	if size_of::<T>()
+		.checked_mul(count)
+		.is_none_or(|len| len > source.len())
+	{
+		return Err((Error::NotEnoughBytes, source));
+	}
+	limits::reserve::<T>(count).map_err(|e| (e, source))?;
+	let mut v = Vec::with_capacity(count);
+	let len = copy_bulk(source, &mut v.spare_capacity_mut()[..count])
+		.ok_or((Error::NotEnoughBytes, source))?;
+	// Safety: the first `count` elements were initialised by the copy
+	unsafe { v.set_len(count) };
+	Ok((v, &source[len..]))
+}
+
+#[inline]
+pub fn write_bulk<T>(slice: &[T], w: &mut impl std::io::Write) -> std::io::Result<()>
+where
+	T: Bulk,
+{
+	fn bytes<T: Bulk>(slice: &[T]) -> &[u8] {
+		// Safety: there is no padding, so every byte is initialised
+		unsafe { std::slice::from_raw_parts(slice.as_ptr().cast::<u8>(), size_of_val(slice)) }
+	}
+
+	if T::NATIVE {
+		return w.write_all(bytes(slice));
+	}
+	let mut buffer = Vec::with_capacity(slice.len().min(4096));
+	for chunk in slice.chunks(4096) {
+		buffer.clear();
+		buffer.extend(chunk.iter().map(|element| element.swap()));
+		w.write_all(bytes(&buffer))?;
+	}
+	Ok(())
+}
+
+// Types which always take up `SIZE` bytes of a `[u8]` source
+pub trait FixedSize {
+	const SIZE: usize;
+}
+
+macro_rules! impl_fixed_size {
+	($( $ty:ty = $size:literal )*) => {
+		$(
+			impl FixedSize for $ty {
+				const SIZE: usize = $size;
+			}
+		)*
+	};
+}
+
+impl_fixed_size!(
+	() = 0 u8 = 1 i8 = 1 u16 = 2 i16 = 2 u32 = 4 i32 = 4 u64 = 8 i64 = 8 u128 = 16 i128 = 16
+	f32 = 4 f64 = 8 bool = 1 char = 4 NonZeroU8 = 1 NonZeroI8 = 1 NonZeroU16 = 2 NonZeroI16 = 2
+	NonZeroU32 = 4 NonZeroI32 = 4 NonZeroU64 = 8 NonZeroI64 = 8 NonZeroU128 = 16
+	NonZeroI128 = 16 Ipv4Addr = 4 Ipv6Addr = 16 Duration = 12
+);
+
+impl<T, const N: usize> FixedSize for [T; N]
+where
+	T: FixedSize,
+{
+	const SIZE: usize = T::SIZE * N;
+}
+
+impl<T> FixedSize for PhantomData<T> {
+	const SIZE: usize = 0;
+}
+
+// Overrides the `Parsable<[u8]>` methods for a `Bulk` type
+macro_rules! bulk_read {
+	() => {
+		#[inline]
+		fn read_array<const N: usize>(
+			source: &[u8],
+			_context: (),
+		) -> $crate::PResultBytes<'_, [Self; N]> {
+			$crate::pod::read_bulk_array(source)
+		}
+
+		#[inline]
+		fn read_vec(
+			source: &[u8],
+			_context: (),
+			count: Option<usize>,
+		) -> $crate::PResultBytes<'_, Vec<Self>> {
+			$crate::pod::read_bulk_vec(source, count)
+		}
+	};
+}
+
+// Overrides the `Deparsable` methods for a `Bulk` type
+macro_rules! bulk_write {
+	() => {
+		#[inline]
+		fn write_slice(
+			slice: &[Self],
+			w: &mut impl std::io::Write,
+			_context: (),
+		) -> std::io::Result<()> {
+			$crate::pod::write_bulk(slice, w)
+		}
+	};
+}
+
+pub(crate) use {bulk_read, bulk_write};
@@ -1,5 +1,16 @@
 use crate::{
-	error::Error, helpers::try_split_array, Deparsable, PResult, PResultBytes, PResultStr, Parsable,
+	error::Error,
+	helpers::try_split_array,
+	pod::{bulk_read, bulk_write, Bulk},
+	Deparsable, PResult, PResultBytes, PResultStr, Parsable,
+};
+use std::{
+	net::{Ipv4Addr, Ipv6Addr},
+	num::{
+		NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+		NonZeroU32, NonZeroU64, NonZeroU8,
+	},
+	time::Duration,
 };
 
 impl<'a, Src> Parsable<'a, Src> for ()
@@ -7,52 +18,49 @@ where
 	Src: ?Sized,
 {
 	#[inline]
-	fn read(source: &'a Src, _context: ()) -> PResult<Self, Src> { Ok(((), source)) }
+	fn read(source: &'a Src, _context: ()) -> PResult<'a, Self, Src> { Ok(((), source)) }
 }
 
 impl Deparsable for () {
 	#[inline]
-	fn write(&mut self, _w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
-		Ok(())
-	}
+	fn write(&self, _w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> { Ok(()) }
 }
 
 impl<'a> Parsable<'a, [u8]> for &'a [u8] {
 	#[inline]
-	fn read(source: &'a [u8], _context: ()) -> PResultBytes<Self> { Ok((source, &[])) }
-}
-
-impl Deparsable for &[u8] {
-	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
-		w.write_all(self)
-	}
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> { Ok((source, &[])) }
 }
 
 impl<'a> Parsable<'a, str> for &'a str {
 	#[inline]
-	fn read(source: &'a str, _context: ()) -> PResultStr<Self> { Ok((source, "")) }
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> { Ok((source, "")) }
 }
 
-impl Deparsable for &str {
+impl Deparsable for str {
 	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
 		w.write_all(self.as_bytes())
 	}
 }
 
-impl<'a, const N: usize> Parsable<'a, [u8]> for &'a [u8; N] {
+impl<'a> Parsable<'a, str> for String {
+	#[inline]
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> { Ok((source.to_string(), "")) }
+}
+
+impl<'a> Parsable<'a, [u8]> for String {
 	#[inline]
-	fn read(source: &'a [u8], _context: ()) -> PResultBytes<Self> {
-		let (output, source) = try_split_array(source).ok_or((Error::NotEnoughBytes, source))?;
-		Ok((output, source))
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		let string =
+			std::str::from_utf8(source).map_err(|_| (Error::InvalidEncoding("utf-8"), source))?;
+		Ok((string.to_string(), &[]))
 	}
 }
 
-impl<const N: usize> Deparsable for &[u8; N] {
+impl Deparsable for String {
 	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
-		w.write_all(self.as_ref())
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(self.as_bytes())
 	}
 }
 
@@ -60,19 +68,31 @@ macro_rules! impl_prim {
 	($ty:ident $size:literal) => {
 		impl Parsable<'_, [u8]> for $ty {
 			#[inline]
-			fn read(source: &[u8], _context: ()) -> PResultBytes<Self> {
+			fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
 				let (head, source) =
 					try_split_array::<_, $size>(source).ok_or((Error::NotEnoughBytes, source))?;
 				let prim = $ty::from_ne_bytes(*head);
 				Ok((prim, source))
 			}
+
+			bulk_read!();
 		}
 
 		impl Deparsable for $ty {
 			#[inline]
-			fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+			fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
 				w.write_all(&self.to_ne_bytes())
 			}
+
+			bulk_write!();
+		}
+
+		// Safety: primitives have no padding and any bytes are a valid value
+		unsafe impl Bulk for $ty {
+			const NATIVE: bool = true;
+
+			#[inline]
+			fn swap(self) -> Self { self }
 		}
 	};
 }
@@ -87,4 +107,117 @@ macro_rules! impl_prims {
 	};
 }
 
-impl_prims!(u8 1 i8 1 u16 2 i16 2 u32 4 i32 4 u64 8 i64 8 f32 4 f64 8);
+impl_prims!(u8 1 i8 1 u16 2 i16 2 u32 4 i32 4 u64 8 i64 8 u128 16 i128 16 f32 4 f64 8);
+
+// Only 0 and 1 are accepted, so that every value read writes back to the same byte
+impl Parsable<'_, [u8]> for bool {
+	#[inline]
+	fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
+		let (byte, remainder) = u8::read(source, ())?;
+		match byte {
+			0 => Ok((false, remainder)),
+			1 => Ok((true, remainder)),
+			_ => Err((Error::InvalidInput, source)),
+		}
+	}
+}
+
+impl Deparsable for bool {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		(*self as u8).write(w, ())
+	}
+}
+
+impl Parsable<'_, [u8]> for char {
+	#[inline]
+	fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
+		let (code, remainder) = u32::read(source, ())?;
+		let c = char::from_u32(code).ok_or((Error::InvalidInput, source))?;
+		Ok((c, remainder))
+	}
+}
+
+impl Deparsable for char {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		(*self as u32).write(w, ())
+	}
+}
+
+macro_rules! impl_non_zero {
+	($( $ty:ident $prim:ident )*) => {
+		$(
+			impl Parsable<'_, [u8]> for $ty {
+				#[inline]
+				fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
+					let (prim, remainder) = $prim::read(source, ())?;
+					let non_zero = $ty::new(prim).ok_or((Error::InvalidInput, source))?;
+					Ok((non_zero, remainder))
+				}
+			}
+
+			impl Deparsable for $ty {
+				#[inline]
+				fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+					self.get().write(w, ())
+				}
+			}
+		)*
+	};
+}
+
+impl_non_zero!(
+	NonZeroU8 u8 NonZeroI8 i8 NonZeroU16 u16 NonZeroI16 i16 NonZeroU32 u32 NonZeroI32 i32
+	NonZeroU64 u64 NonZeroI64 i64 NonZeroU128 u128 NonZeroI128 i128
+);
+
+// Addresses are in network order, as they are everywhere else
+impl Parsable<'_, [u8]> for Ipv4Addr {
+	#[inline]
+	fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
+		let (octets, source) = <[u8; 4]>::read(source, ())?;
+		Ok((Ipv4Addr::from(octets), source))
+	}
+}
+
+impl Deparsable for Ipv4Addr {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(&self.octets())
+	}
+}
+
+impl Parsable<'_, [u8]> for Ipv6Addr {
+	#[inline]
+	fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
+		let (octets, source) = <[u8; 16]>::read(source, ())?;
+		Ok((Ipv6Addr::from(octets), source))
+	}
+}
+
+impl Deparsable for Ipv6Addr {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(&self.octets())
+	}
+}
+
+// Seconds as a `u64` followed by nanoseconds as a `u32`, which must be less than a second
+impl Parsable<'_, [u8]> for Duration {
+	#[inline]
+	fn read(source: &[u8], _context: ()) -> PResultBytes<'_, Self> {
+		let ((secs, nanos), remainder) = <(u64, u32)>::read(source, ())?;
+		if nanos >= 1_000_000_000 {
+			return Err((Error::InvalidInput, source));
+		}
+		Ok((Duration::new(secs, nanos), remainder))
+	}
+}
+
+impl Deparsable for Duration {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		(self.as_secs(), self.subsec_nanos()).write(w, ())
+	}
+}
@@ -0,0 +1,486 @@
+mod c;
+mod kaitai;
+mod markdown;
+
+pub use c::to_c_header;
+pub use kaitai::to_kaitai;
+pub use markdown::to_markdown;
+
+use std::{
+	collections::{BTreeMap, HashMap, VecDeque},
+	marker::PhantomData,
+	net::{Ipv4Addr, Ipv6Addr},
+	num::{
+		NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+		NonZeroU32, NonZeroU64, NonZeroU8,
+	},
+};
+
+// A description of how a type is laid out in its input, implemented by the derives, for documenting
+// formats from the types which parse them
+pub trait Schema {
+	fn schema() -> TypeSchema;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+	Little,
+	Big,
+	Native,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Number {
+	Unsigned,
+	Signed,
+	Float,
+	Bool,
+	Char,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeSchema {
+	pub name: String,
+	// Known when every value takes up the same number of bytes
+	pub size: Option<usize>,
+	pub layout: Layout,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Layout {
+	Primitive {
+		number: Number,
+		size: usize,
+		endian: Endian,
+	},
+	// Without a length, the count is given by the context or runs to the end of the input
+	Array {
+		element: Box<TypeSchema>,
+		len: Option<usize>,
+	},
+	// Present when it can be parsed
+	Optional(Box<TypeSchema>),
+	// Present when the context of the field holding it is true
+	Conditional(Box<TypeSchema>),
+	Struct(StructSchema),
+	Enum(EnumSchema),
+	// Types which do not describe themselves
+	Opaque,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructSchema {
+	pub tag: Option<&'static str>,
+	pub fields: Vec<FieldSchema>,
+	pub asserts: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumSchema {
+	pub tag: Option<&'static str>,
+	pub discriminant: Discriminant,
+	pub variants: Vec<VariantSchema>,
+	pub asserts: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discriminant {
+	// Each variant is tried in turn
+	None,
+	Read(Box<TypeSchema>),
+	// An expression of the context
+	FromContext(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSchema {
+	pub name: &'static str,
+	// The value or pattern of the discriminant which selects the variant
+	pub discriminant: Option<&'static str>,
+	pub tag: Option<&'static str>,
+	pub fields: Vec<FieldSchema>,
+	pub asserts: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSchema {
+	pub name: &'static str,
+	pub ty: TypeSchema,
+	pub source: FieldSource,
+	pub tag: Option<&'static str>,
+	// The expression the field is parsed with, when it is given one
+	pub context: Option<&'static str>,
+	// Checks on the value, such as `== 4` or `matches 1 | 2`
+	pub asserts: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldSource {
+	Read,
+	// Read in front of the next field without being kept
+	Temporary,
+	// A copy of the discriminant of the enum
+	Discriminant,
+	// Not read, but given by an expression
+	Computed(&'static str),
+}
+
+impl FieldSchema {
+	// Whether the field takes up bytes of the input
+	pub fn is_read(&self) -> bool {
+		matches!(self.source, FieldSource::Read | FieldSource::Temporary)
+	}
+}
+
+impl TypeSchema {
+	pub fn opaque(name: impl Into<String>) -> Self {
+		TypeSchema {
+			name: name.into(),
+			size: None,
+			layout: Layout::Opaque,
+		}
+	}
+
+	pub fn primitive(name: impl Into<String>, number: Number, size: usize) -> Self {
+		TypeSchema {
+			name: name.into(),
+			size: Some(size),
+			layout: Layout::Primitive {
+				number,
+				size,
+				endian: Endian::Native,
+			},
+		}
+	}
+
+	pub fn array(element: TypeSchema, len: Option<usize>) -> Self {
+		let name = match len {
+			Some(len) => format!("[{}; {}]", element.name, len),
+			None => format!("[{}]", element.name),
+		};
+		TypeSchema {
+			name,
+			size: element.size.zip(len).map(|(size, len)| size * len),
+			layout: Layout::Array {
+				element: Box::new(element),
+				len,
+			},
+		}
+	}
+
+	pub fn optional(inner: TypeSchema) -> Self {
+		TypeSchema {
+			name: format!("Option<{}>", inner.name),
+			size: None,
+			layout: Layout::Optional(Box::new(inner)),
+		}
+	}
+
+	// Wrappers such as `Cond` which read their value only when told to
+	pub fn conditional(wrapper: &str, inner: TypeSchema) -> Self {
+		TypeSchema {
+			name: format!("{}<{}>", wrapper, inner.name),
+			size: None,
+			layout: Layout::Conditional(Box::new(inner)),
+		}
+	}
+
+	pub fn structure(name: impl Into<String>, schema: StructSchema) -> Self {
+		let tag_len = schema.tag.map_or(0, str::len);
+		TypeSchema {
+			name: name.into(),
+			size: fields_size(&schema.fields).map(|size| tag_len + size),
+			layout: Layout::Struct(schema),
+		}
+	}
+
+	// The size is fixed when every variant has the same size
+	pub fn enumeration(name: impl Into<String>, schema: EnumSchema) -> Self {
+		let discriminant = match &schema.discriminant {
+			Discriminant::Read(ty) => ty.size,
+			_ => Some(0),
+		};
+		let mut sizes = schema.variants.iter().map(|variant| {
+			let tag_len = variant.tag.map_or(0, str::len);
+			fields_size(&variant.fields).map(|size| tag_len + size)
+		});
+		let first = sizes.next().flatten();
+		let size = match sizes.all(|size| size == first) {
+			true => first
+				.zip(discriminant)
+				.map(|(size, dis)| schema.tag.map_or(0, str::len) + dis + size),
+			false => None,
+		};
+		TypeSchema {
+			name: name.into(),
+			size,
+			layout: Layout::Enum(schema),
+		}
+	}
+
+	// Endian wrappers set the byte order of the primitives they hold
+	pub fn with_endian(mut self, wrapper: &str, endian: Endian) -> Self {
+		self.name = format!("{}<{}>", wrapper, self.name);
+		if let Layout::Primitive { endian: e, .. } = &mut self.layout {
+			*e = endian;
+		}
+		self
+	}
+
+	// Every struct and enum within this one, each after the types it holds, and this one last
+	pub fn named_types(&self) -> Vec<&TypeSchema> {
+		fn visit<'s>(schema: &'s TypeSchema, found: &mut Vec<&'s TypeSchema>) {
+			let fields: Box<dyn Iterator<Item = &FieldSchema>> = match &schema.layout {
+				Layout::Array { element, .. }
+				| Layout::Optional(element)
+				| Layout::Conditional(element) => return visit(element, found),
+				Layout::Struct(s) => Box::new(s.fields.iter()),
+				Layout::Enum(e) => {
+					if let Discriminant::Read(ty) = &e.discriminant {
+						visit(ty, found);
+					}
+					Box::new(e.variants.iter().flat_map(|v| v.fields.iter()))
+				}
+				Layout::Primitive { .. } | Layout::Opaque => return,
+			};
+			fields.for_each(|field| visit(&field.ty, found));
+			if !found.iter().any(|other| other.name == schema.name) {
+				found.push(schema);
+			}
+		}
+
+		let mut found = Vec::new();
+		visit(self, &mut found);
+		found
+	}
+}
+
+// The name of `T` without the paths of the types in it, such as `Vec<u8>` rather than
+// `alloc::vec::Vec<u8>`, for naming generic types by their arguments
+pub fn type_name<T: ?Sized>() -> String {
+	let mut out = String::new();
+	let mut path_start = 0;
+	let mut rest = std::any::type_name::<T>();
+	while let Some(c) = rest.chars().next() {
+		match rest.strip_prefix("::") {
+			Some(after) => {
+				out.truncate(path_start);
+				rest = after;
+				continue;
+			}
+			None if c.is_alphanumeric() || c == '_' => out.push(c),
+			None => {
+				out.push(c);
+				path_start = out.len();
+			}
+		}
+		rest = &rest[c.len_utf8()..];
+	}
+	out
+}
+
+fn fields_size(fields: &[FieldSchema]) -> Option<usize> {
+	fields
+		.iter()
+		.map(|field| {
+			let tag_len = field.tag.map_or(0, str::len);
+			match field.is_read() {
+				true => field.ty.size.map(|size| tag_len + size),
+				false => Some(tag_len),
+			}
+		})
+		.sum()
+}
+
+// Lets the derives describe field types which may not implement `Schema`. Calling `schema` on a
+// `&Probe` picks `ViaSchema` when it applies, and otherwise auto-refs to `ViaOpaque`
+pub struct Probe<T: ?Sized>(PhantomData<fn() -> T>, &'static str);
+
+impl<T: ?Sized> Probe<T> {
+	pub fn new(name: &'static str) -> Self { Probe(PhantomData, name) }
+}
+
+pub trait ViaSchema {
+	fn schema(&self) -> TypeSchema;
+}
+
+impl<T> ViaSchema for Probe<T>
+where
+	T: Schema + ?Sized,
+{
+	fn schema(&self) -> TypeSchema { T::schema() }
+}
+
+pub trait ViaOpaque {
+	fn schema(&self) -> TypeSchema;
+}
+
+impl<T: ?Sized> ViaOpaque for &Probe<T> {
+	fn schema(&self) -> TypeSchema { TypeSchema::opaque(self.1) }
+}
+
+macro_rules! impl_schema {
+	($( $ty:ty = $number:ident $size:literal )*) => {
+		$(
+			impl Schema for $ty {
+				fn schema() -> TypeSchema {
+					TypeSchema::primitive(stringify!($ty), Number::$number, $size)
+				}
+			}
+		)*
+	};
+}
+
+impl_schema!(
+	u8 = Unsigned 1 u16 = Unsigned 2 u32 = Unsigned 4 u64 = Unsigned 8 u128 = Unsigned 16
+	i8 = Signed 1 i16 = Signed 2 i32 = Signed 4 i64 = Signed 8 i128 = Signed 16
+	f32 = Float 4 f64 = Float 8 bool = Bool 1 char = Char 4
+	NonZeroU8 = Unsigned 1 NonZeroU16 = Unsigned 2 NonZeroU32 = Unsigned 4 NonZeroU64 = Unsigned 8
+	NonZeroU128 = Unsigned 16 NonZeroI8 = Signed 1 NonZeroI16 = Signed 2 NonZeroI32 = Signed 4
+	NonZeroI64 = Signed 8 NonZeroI128 = Signed 16
+);
+
+// Addresses are octets in network order
+impl Schema for Ipv4Addr {
+	fn schema() -> TypeSchema {
+		let mut schema = TypeSchema::array(u8::schema(), Some(4));
+		schema.name = "Ipv4Addr".to_string();
+		schema
+	}
+}
+
+impl Schema for Ipv6Addr {
+	fn schema() -> TypeSchema {
+		let mut schema = TypeSchema::array(u8::schema(), Some(16));
+		schema.name = "Ipv6Addr".to_string();
+		schema
+	}
+}
+
+impl<T, const N: usize> Schema for [T; N]
+where
+	T: Schema,
+{
+	fn schema() -> TypeSchema { TypeSchema::array(T::schema(), Some(N)) }
+}
+
+macro_rules! impl_schema_seq {
+	($( $ty:ty )*) => {
+		$(
+			impl<T> Schema for $ty
+			where
+				T: Schema,
+			{
+				fn schema() -> TypeSchema { TypeSchema::array(T::schema(), None) }
+			}
+		)*
+	};
+}
+
+impl_schema_seq!([T] Vec<T> Box<[T]> VecDeque<T>);
+
+// Maps are a sequence of key value pairs
+impl<K, V> Schema for BTreeMap<K, V>
+where
+	(K, V): Schema,
+{
+	fn schema() -> TypeSchema { TypeSchema::array(<(K, V)>::schema(), None) }
+}
+
+impl<K, V, S> Schema for HashMap<K, V, S>
+where
+	(K, V): Schema,
+{
+	fn schema() -> TypeSchema { TypeSchema::array(<(K, V)>::schema(), None) }
+}
+
+impl<T> Schema for Option<T>
+where
+	T: Schema,
+{
+	fn schema() -> TypeSchema { TypeSchema::optional(T::schema()) }
+}
+
+// References and boxes are laid out as what they point to
+impl<T> Schema for &T
+where
+	T: Schema + ?Sized,
+{
+	fn schema() -> TypeSchema { T::schema() }
+}
+
+impl<T> Schema for Box<T>
+where
+	T: Schema,
+{
+	fn schema() -> TypeSchema { T::schema() }
+}
+
+impl<T: ?Sized> Schema for PhantomData<T> {
+	fn schema() -> TypeSchema {
+		TypeSchema::structure(
+			"PhantomData",
+			StructSchema {
+				tag: None,
+				fields: Vec::new(),
+				asserts: Vec::new(),
+			},
+		)
+	}
+}
+
+// Tuples are structs with fields named by position
+pub(crate) fn tuple_schema(types: Vec<TypeSchema>) -> TypeSchema {
+	const NAMES: [&str; 16] = [
+		"0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+	];
+	let name = types
+		.iter()
+		.map(|ty| ty.name.as_str())
+		.collect::<Vec<_>>()
+		.join(", ");
+	let fields = types
+		.into_iter()
+		.zip(NAMES)
+		.map(|(ty, name)| FieldSchema {
+			name,
+			ty,
+			source: FieldSource::Read,
+			tag: None,
+			context: None,
+			asserts: Vec::new(),
+		})
+		.collect();
+	TypeSchema::structure(
+		format!("({})", name),
+		StructSchema {
+			tag: None,
+			fields,
+			asserts: Vec::new(),
+		},
+	)
+}
+
+// Turns a Rust type name into an identifier for the exporters, such as `LazyHeader` into
+// `lazy_header`
+pub(crate) fn snake_case(name: &str) -> String {
+	let mut out = String::new();
+	for (i, c) in name.chars().enumerate() {
+		match c {
+			c if c.is_ascii_uppercase() => {
+				if i != 0 && !out.ends_with('_') {
+					out.push('_');
+				}
+				out.push(c.to_ascii_lowercase());
+			}
+			c if c.is_ascii_alphanumeric() => out.push(c),
+			_ if !out.is_empty() && !out.ends_with('_') => out.push('_'),
+			_ => (),
+		}
+	}
+	let out = out.trim_end_matches('_').to_string();
+	match out.starts_with(|c: char| c.is_ascii_digit()) || out.is_empty() {
+		true => format!("field_{}", out),
+		false => out,
+	}
+}
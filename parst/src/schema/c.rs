@@ -0,0 +1,190 @@
+use super::{
+	snake_case, Discriminant, Endian, FieldSchema, FieldSource, Layout, Number, TypeSchema,
+};
+use std::fmt::Write;
+
+// Writes a C header with a packed struct for `schema` and each struct and enum within it. Fields the
+// C struct can not hold, such as ones after a value whose size varies, are left as comments. An enum
+// becomes its discriminant followed by a union of its variants
+pub fn to_c_header(schema: &TypeSchema) -> String {
+	let guard = format!("{}_H", snake_case(&schema.name).to_uppercase());
+	let mut out = String::new();
+	writeln!(out, "#ifndef {}\n#define {}\n", guard, guard).unwrap();
+	writeln!(out, "#include <stdint.h>\n").unwrap();
+	writeln!(out, "#pragma pack(push, 1)\n").unwrap();
+
+	for ty in schema.named_types() {
+		match &ty.layout {
+			Layout::Struct(s) => structure(&mut out, &c_name(&ty.name), s.tag, &s.fields, None),
+			Layout::Enum(e) => {
+				let name = c_name(&ty.name);
+				let mut members = Vec::new();
+				for variant in &e.variants {
+					let variant_name = format!("{}_{}", name, variant.name);
+					if variant.tag.is_some() || variant.fields.iter().any(FieldSchema::is_read) {
+						structure(&mut out, &variant_name, variant.tag, &variant.fields, None);
+						members.push(format!("struct {} {};", variant_name, variant.name));
+					}
+				}
+
+				let discriminant = match &e.discriminant {
+					Discriminant::Read(ty) => declare(ty, "discriminant", false),
+					Discriminant::FromContext(e) => {
+						Err(format!("discriminant: {}, from the context", e))
+					}
+					Discriminant::None => {
+						Err("no discriminant, the variants are tried in turn".into())
+					}
+				};
+				let body = (!members.is_empty()).then_some(members);
+				structure(&mut out, &name, e.tag, &[], Some((discriminant, body)));
+			}
+			_ => (),
+		}
+	}
+
+	writeln!(out, "#pragma pack(pop)\n\n#endif").unwrap();
+	out
+}
+
+type EnumParts = (Result<String, String>, Option<Vec<String>>);
+
+fn structure(
+	out: &mut String,
+	name: &str,
+	tag: Option<&str>,
+	fields: &[FieldSchema],
+	enum_parts: Option<EnumParts>,
+) {
+	writeln!(out, "struct {} {{", name).unwrap();
+	let mut tags = 0;
+	let mut tag_member = |out: &mut String, tag: &str| {
+		writeln!(out, "\tchar tag_{}[{}]; /* {:?} */", tags, tag.len(), tag).unwrap();
+		tags += 1;
+	};
+	if let Some(tag) = tag {
+		tag_member(out, tag);
+	}
+
+	// Once a field has no fixed size, the ones after it no longer have a fixed place
+	let last_read = fields.iter().rposition(FieldSchema::is_read);
+	let mut placed = true;
+	for (index, field) in fields.iter().enumerate() {
+		if let Some(tag) = field.tag {
+			match placed {
+				true => tag_member(out, tag),
+				false => writeln!(out, "\t/* {:?} */", tag).unwrap(),
+			}
+		}
+		let name = c_name(field.name);
+		match &field.source {
+			FieldSource::Discriminant => {
+				writeln!(out, "\t/* {}: the discriminant, not stored */", name).unwrap();
+				continue;
+			}
+			FieldSource::Computed(e) => {
+				writeln!(out, "\t/* {}: {}, not stored */", name, e).unwrap();
+				continue;
+			}
+			FieldSource::Read | FieldSource::Temporary => (),
+		}
+
+		let declaration = match placed {
+			true => declare(&field.ty, &name, Some(index) == last_read),
+			false => Err(format!(
+				"{}: {}, after a value whose size varies",
+				name, field.ty.name
+			)),
+		};
+		match declaration {
+			Ok(declaration) => writeln!(out, "\t{}", declaration).unwrap(),
+			Err(comment) => {
+				writeln!(out, "\t/* {} */", comment).unwrap();
+				placed = false;
+			}
+		}
+	}
+
+	if let Some((discriminant, body)) = enum_parts {
+		match discriminant {
+			Ok(declaration) => writeln!(out, "\t{}", declaration).unwrap(),
+			Err(comment) => writeln!(out, "\t/* {} */", comment).unwrap(),
+		}
+		if let Some(members) = body {
+			writeln!(out, "\tunion {{").unwrap();
+			for member in members {
+				writeln!(out, "\t\t{}", member).unwrap();
+			}
+			writeln!(out, "\t}} body;").unwrap();
+		}
+	}
+	writeln!(out, "}};\n").unwrap();
+}
+
+// A member declaration, or a comment describing a value which can not be one. Only the last member
+// may be an array without a length
+fn declare(ty: &TypeSchema, name: &str, last: bool) -> Result<String, String> {
+	match &ty.layout {
+		Layout::Primitive {
+			number,
+			size,
+			endian,
+		} => {
+			let endian = match (endian, size) {
+				(_, 1) | (Endian::Native, _) => String::new(),
+				(Endian::Little, _) => " /* little endian */".to_string(),
+				(Endian::Big, _) => " /* big endian */".to_string(),
+			};
+			Ok(match primitive(*number, *size) {
+				Some(c_type) => format!("{} {};{}", c_type, name, endian),
+				None => format!("uint8_t {}[{}];{}", name, size, endian),
+			})
+		}
+		Layout::Array { element, len } => match (len, last, element.size.is_some()) {
+			(Some(len), _, _) => declare(element, &format!("{}[{}]", name, len), false),
+			(None, true, true) => declare(element, &format!("{}[]", name), false),
+			(None, _, _) => Err(format!("{}: {}, whose length varies", name, ty.name)),
+		},
+		Layout::Struct(_) | Layout::Enum(_) => match ty.size.is_some() || last {
+			true => Ok(format!("struct {} {};", c_name(&ty.name), name)),
+			false => Err(format!(
+				"{}: struct {}, whose size varies",
+				name,
+				c_name(&ty.name)
+			)),
+		},
+		Layout::Optional(_) => Err(format!("{}: {}, present when it parses", name, ty.name)),
+		Layout::Conditional(_) => Err(format!(
+			"{}: {}, present when its context is true",
+			name, ty.name
+		)),
+		Layout::Opaque => Err(format!("{}: {}", name, ty.name)),
+	}
+}
+
+fn primitive(number: Number, size: usize) -> Option<&'static str> {
+	Some(match (number, size) {
+		(Number::Unsigned | Number::Bool, 1) => "uint8_t",
+		(Number::Unsigned, 2) => "uint16_t",
+		(Number::Unsigned | Number::Char, 4) => "uint32_t",
+		(Number::Unsigned, 8) => "uint64_t",
+		(Number::Signed, 1) => "int8_t",
+		(Number::Signed, 2) => "int16_t",
+		(Number::Signed, 4) => "int32_t",
+		(Number::Signed, 8) => "int64_t",
+		(Number::Float, 4) => "float",
+		(Number::Float, 8) => "double",
+		_ => return None,
+	})
+}
+
+// Keeps Rust names where they are already C identifiers, such as `Header`, and otherwise falls back
+// to snake case, such as `u8_u16` for `(u8, u16)`
+fn c_name(name: &str) -> String {
+	let is_ident = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+		&& !name.starts_with(|c: char| c.is_ascii_digit());
+	match is_ident {
+		true => name.to_string(),
+		false => snake_case(name),
+	}
+}
@@ -0,0 +1,258 @@
+use super::{
+	snake_case, Discriminant, Endian, FieldSchema, FieldSource, Layout, Number, TypeSchema,
+};
+use std::fmt::Write;
+
+// Writes a Kaitai Struct `.ksy` description of `schema`. Native endian values are described as
+// little endian, and types which do not describe themselves are left as bytes to the end of their
+// input. Contexts and computed fields are copied over as written in Rust, and may need adjusting
+pub fn to_kaitai(schema: &TypeSchema) -> String {
+	let mut out = Ksy::default();
+	out.line(0, "meta:");
+	out.line(1, &format!("id: {}", snake_case(&schema.name)));
+
+	let types = schema.named_types();
+	match &schema.layout {
+		Layout::Struct(_) | Layout::Enum(_) => out.body(0, schema),
+		_ => {
+			out.line(0, "seq:");
+			out.line(1, "- id: value");
+			out.attrs(2, &schema.clone(), None);
+		}
+	}
+
+	let nested = types
+		.iter()
+		.filter(|ty| ty.name != schema.name)
+		.collect::<Vec<_>>();
+	let variants = types.iter().any(|ty| matches!(ty.layout, Layout::Enum(_)));
+	if !nested.is_empty() || variants {
+		out.line(0, "types:");
+		for ty in types {
+			if ty.name != schema.name {
+				out.line(1, &format!("{}:", snake_case(&ty.name)));
+				out.body(2, ty);
+			}
+			out.variant_types(1, ty);
+		}
+	}
+
+	out.0
+}
+
+#[derive(Default)]
+struct Ksy(String);
+
+impl Ksy {
+	fn line(&mut self, indent: usize, text: &str) {
+		writeln!(self.0, "{:width$}{}", "", text, width = indent * 2).unwrap();
+	}
+
+	fn contents(&mut self, indent: usize, tag: &str) {
+		let bytes = tag
+			.bytes()
+			.map(|b| format!("0x{:02x}", b))
+			.collect::<Vec<_>>();
+		self.line(indent, &format!("- contents: [{}]", bytes.join(", ")));
+	}
+
+	// The `seq` and `instances` of a struct, or of an enum choosing between its variants
+	fn body(&mut self, indent: usize, schema: &TypeSchema) {
+		match &schema.layout {
+			Layout::Struct(s) => self.fields(indent, s.tag, &s.fields),
+			Layout::Enum(e) => {
+				self.line(indent, "seq:");
+				if let Some(tag) = e.tag {
+					self.contents(indent + 1, tag);
+				}
+				let switch_on = match &e.discriminant {
+					Discriminant::Read(ty) => {
+						self.line(indent + 1, "- id: discriminant");
+						self.attrs(indent + 2, ty, None);
+						Some("discriminant")
+					}
+					Discriminant::FromContext(e) => Some(*e),
+					Discriminant::None => None,
+				};
+				self.line(indent + 1, "- id: body");
+				match switch_on {
+					Some(switch_on) => {
+						self.line(indent + 2, "type:");
+						self.line(indent + 3, &format!("switch-on: {}", switch_on));
+						self.line(indent + 3, "cases:");
+						for variant in &e.variants {
+							let ty = variant_type(&schema.name, variant.name);
+							for case in variant.discriminant.unwrap_or("_").split('|') {
+								self.line(indent + 4, &format!("{}: {}", case.trim(), ty));
+							}
+						}
+					}
+					None => {
+						let names = e.variants.iter().map(|v| v.name).collect::<Vec<_>>();
+						let doc = format!("the first of {} which parses", names.join(", "));
+						self.line(indent + 2, "size-eos: true");
+						self.line(indent + 2, &format!("doc: {:?}", doc));
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	fn variant_types(&mut self, indent: usize, schema: &TypeSchema) {
+		if let Layout::Enum(e) = &schema.layout {
+			for variant in &e.variants {
+				self.line(
+					indent,
+					&format!("{}:", variant_type(&schema.name, variant.name)),
+				);
+				self.fields(indent + 1, variant.tag, &variant.fields);
+			}
+		}
+	}
+
+	fn fields(&mut self, indent: usize, tag: Option<&str>, fields: &[FieldSchema]) {
+		match tag.is_some()
+			|| fields
+				.iter()
+				.any(|field| field.tag.is_some() || field.is_read())
+		{
+			true => self.line(indent, "seq:"),
+			false => self.line(indent, "seq: []"),
+		}
+		if let Some(tag) = tag {
+			self.contents(indent + 1, tag);
+		}
+		for field in fields {
+			if let Some(tag) = field.tag {
+				self.contents(indent + 1, tag);
+			}
+			if field.is_read() {
+				self.line(indent + 1, &format!("- id: {}", snake_case(field.name)));
+				self.attrs(indent + 2, &field.ty, Some(field));
+			}
+		}
+
+		let computed = fields.iter().filter(|field| !field.is_read());
+		let mut computed = computed.peekable();
+		if computed.peek().is_some() {
+			self.line(indent, "instances:");
+			for field in computed {
+				let value = match field.source {
+					FieldSource::Computed(e) => e,
+					_ => "_parent.discriminant",
+				};
+				self.line(indent + 1, &format!("{}:", snake_case(field.name)));
+				self.line(indent + 2, &format!("value: {}", value));
+			}
+		}
+	}
+
+	// How a value of type `ty` is read, along with the length and checks from the field holding it
+	fn attrs(&mut self, indent: usize, ty: &TypeSchema, field: Option<&FieldSchema>) {
+		let mut docs = Vec::new();
+		let context = field.and_then(|field| field.context);
+		match &ty.layout {
+			Layout::Array { element, len } => {
+				let count = match (len, context) {
+					(Some(len), _) => Some(len.to_string()),
+					(None, Some(context)) => Some(context.to_string()),
+					(None, None) => None,
+				};
+				match (is_byte(element), count) {
+					(true, Some(count)) => self.line(indent, &format!("size: {}", count)),
+					(true, None) => self.line(indent, "size-eos: true"),
+					(false, count) => {
+						self.type_attr(indent, element, &mut docs);
+						match count {
+							Some(count) => {
+								self.line(indent, "repeat: expr");
+								self.line(indent, &format!("repeat-expr: {}", count));
+							}
+							None => self.line(indent, "repeat: eos"),
+						}
+					}
+				}
+			}
+			Layout::Optional(inner) => {
+				self.type_attr(indent, inner, &mut docs);
+				docs.push("present when it parses".to_string());
+			}
+			Layout::Conditional(inner) => {
+				self.type_attr(indent, inner, &mut docs);
+				match context {
+					Some(context) => self.line(indent, &format!("if: {}", context)),
+					None => docs.push("present when its context is true".to_string()),
+				}
+			}
+			_ => self.type_attr(indent, ty, &mut docs),
+		}
+
+		if let Some(field) = field {
+			for assert in &field.asserts {
+				match assert.strip_prefix("== ") {
+					Some(value) => self.line(indent, &format!("valid: {}", value)),
+					None => docs.push(format!("must be {}", assert)),
+				}
+			}
+			if field.source == FieldSource::Temporary {
+				docs.push("temporary".to_string());
+			}
+		}
+		if !docs.is_empty() {
+			self.line(indent, &format!("doc: {:?}", docs.join("; ")));
+		}
+	}
+
+	fn type_attr(&mut self, indent: usize, ty: &TypeSchema, docs: &mut Vec<String>) {
+		match &ty.layout {
+			Layout::Primitive {
+				number,
+				size,
+				endian,
+			} => self.line(
+				indent,
+				&format!("type: {}", primitive(*number, *size, *endian)),
+			),
+			Layout::Struct(_) | Layout::Enum(_) => {
+				self.line(indent, &format!("type: {}", snake_case(&ty.name)))
+			}
+			Layout::Array { .. }
+			| Layout::Optional(_)
+			| Layout::Conditional(_)
+			| Layout::Opaque => {
+				self.line(indent, "size-eos: true");
+				docs.push(ty.name.clone());
+			}
+		}
+	}
+}
+
+fn is_byte(ty: &TypeSchema) -> bool {
+	matches!(
+		ty.layout,
+		Layout::Primitive {
+			number: Number::Unsigned,
+			size: 1,
+			..
+		}
+	)
+}
+
+fn variant_type(name: &str, variant: &str) -> String {
+	format!("{}_{}", snake_case(name), snake_case(variant))
+}
+
+fn primitive(number: Number, size: usize, endian: Endian) -> String {
+	let prefix = match number {
+		Number::Unsigned | Number::Bool | Number::Char => "u",
+		Number::Signed => "s",
+		Number::Float => "f",
+	};
+	let suffix = match (size, endian) {
+		(1, _) => "",
+		(_, Endian::Big) => "be",
+		(_, Endian::Little | Endian::Native) => "le",
+	};
+	format!("{}{}{}", prefix, size, suffix)
+}
@@ -0,0 +1,151 @@
+use super::{Discriminant, FieldSchema, FieldSource, Layout, TypeSchema};
+use std::fmt::Write;
+
+// Writes a Markdown section for `schema` and each struct and enum within it, with a table of the
+// fields of each and their offsets while those are fixed
+pub fn to_markdown(schema: &TypeSchema) -> String {
+	let mut out = String::new();
+	let mut types = schema.named_types();
+	types.reverse();
+	if types.is_empty() {
+		writeln!(out, "## {}\n\n{}", schema.name, describe(schema)).unwrap();
+	}
+
+	for ty in types {
+		writeln!(out, "## {}\n", ty.name).unwrap();
+		writeln!(out, "Size: {}\n", size(ty.size)).unwrap();
+		match &ty.layout {
+			Layout::Struct(s) => {
+				if let Some(tag) = s.tag {
+					writeln!(out, "Starts with `{}`\n", escape(tag)).unwrap();
+				}
+				fields(&mut out, &s.fields);
+				asserts(&mut out, &s.asserts);
+			}
+			Layout::Enum(e) => {
+				if let Some(tag) = e.tag {
+					writeln!(out, "Starts with `{}`\n", escape(tag)).unwrap();
+				}
+				let discriminant = match &e.discriminant {
+					Discriminant::Read(ty) => format!("a `{}` read first", escape(&ty.name)),
+					Discriminant::FromContext(e) => format!("`{}` from the context", escape(e)),
+					Discriminant::None => "none, each variant is tried in turn".to_string(),
+				};
+				writeln!(out, "Discriminant: {}\n", discriminant).unwrap();
+
+				writeln!(out, "| Discriminant | Variant |\n| --- | --- |").unwrap();
+				for variant in &e.variants {
+					let discriminant = variant.discriminant.map_or(String::new(), code);
+					writeln!(out, "| {} | {} |", discriminant, variant.name).unwrap();
+				}
+				writeln!(out).unwrap();
+
+				for variant in &e.variants {
+					writeln!(out, "### {}::{}\n", ty.name, variant.name).unwrap();
+					if let Some(tag) = variant.tag {
+						writeln!(out, "Starts with `{}`\n", escape(tag)).unwrap();
+					}
+					fields(&mut out, &variant.fields);
+					asserts(&mut out, &variant.asserts);
+				}
+				asserts(&mut out, &e.asserts);
+			}
+			_ => (),
+		}
+	}
+	out
+}
+
+fn fields(out: &mut String, fields: &[FieldSchema]) {
+	if fields.is_empty() {
+		writeln!(out, "No fields\n").unwrap();
+		return;
+	}
+
+	writeln!(out, "| Offset | Field | Type | Size | Notes |").unwrap();
+	writeln!(out, "| --- | --- | --- | --- | --- |").unwrap();
+	let mut offset = Some(0);
+	for field in fields {
+		if let Some(tag) = field.tag {
+			writeln!(
+				out,
+				"| {} | | `{}` | {} | tag |",
+				size(offset),
+				escape(tag),
+				tag.len()
+			)
+			.unwrap();
+			offset = offset.map(|offset| offset + tag.len());
+		}
+
+		let mut notes = Vec::new();
+		match field.source {
+			FieldSource::Read => (),
+			FieldSource::Temporary => notes.push("temporary".to_string()),
+			FieldSource::Discriminant => notes.push("the discriminant, not read".to_string()),
+			FieldSource::Computed(e) => notes.push(format!("{}, not read", code(e))),
+		}
+		match (&field.ty.layout, field.context) {
+			(Layout::Optional(_), _) => notes.push("present when it parses".to_string()),
+			(Layout::Conditional(_), Some(context)) => {
+				notes.push(format!("present when {}", code(context)))
+			}
+			(Layout::Conditional(_), None) => {
+				notes.push("present when its context is true".to_string())
+			}
+			(_, Some(context)) => notes.push(format!("context {}", code(context))),
+			(_, None) => (),
+		}
+		notes.extend(
+			field
+				.asserts
+				.iter()
+				.map(|assert| format!("must be {}", code(assert))),
+		);
+
+		let field_size = match field.is_read() {
+			true => field.ty.size,
+			false => Some(0),
+		};
+		writeln!(
+			out,
+			"| {} | {} | {} | {} | {} |",
+			size(offset),
+			field.name,
+			code(&field.ty.name),
+			size(field_size),
+			notes.join(", ")
+		)
+		.unwrap();
+		offset = offset.zip(field_size).map(|(offset, size)| offset + size);
+	}
+	writeln!(out).unwrap();
+}
+
+fn asserts(out: &mut String, asserts: &[&str]) {
+	for assert in asserts {
+		writeln!(out, "- Asserts {}", code(assert)).unwrap();
+	}
+	if !asserts.is_empty() {
+		writeln!(out).unwrap();
+	}
+}
+
+fn describe(schema: &TypeSchema) -> String {
+	format!("`{}`, size {}", escape(&schema.name), size(schema.size))
+}
+
+fn size(size: Option<usize>) -> String { size.map_or("variable".to_string(), |s| s.to_string()) }
+
+fn code(text: &str) -> String { format!("`{}`", escape(text)) }
+
+// Pipes would end a table cell, and control characters a line
+fn escape(text: &str) -> String {
+	text.chars()
+		.map(|c| match c {
+			'|' => "\\|".to_string(),
+			c if c.is_control() => c.escape_default().to_string(),
+			c => c.to_string(),
+		})
+		.collect()
+}
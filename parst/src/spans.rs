@@ -0,0 +1,93 @@
+use crate::{iter::Source, owned::ToStatic, Deparsable, PResult, Parsable};
+use std::ops::Range;
+
+// Byte ranges are kept as distances from the end of the input while parsing, since a parser only
+// ever sees the rest of it, and are turned into offsets from the start once the length of the whole
+// input is known
+
+// A value along with where it was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+	pub value: T,
+	start_from_end: usize,
+	end_from_end: usize,
+}
+
+impl<T> Spanned<T> {
+	// The range of `input`, the whole of what was parsed, which the value was read from
+	pub fn span<Src>(&self, input: &Src) -> Range<usize>
+	where
+		Src: Source + ?Sized,
+	{
+		input.len() - self.start_from_end..input.len() - self.end_from_end
+	}
+}
+
+impl<'a, Src, Ctx, T> Parsable<'a, Src, Ctx> for Spanned<T>
+where
+	Src: Source + ?Sized,
+	T: Parsable<'a, Src, Ctx>,
+{
+	#[inline]
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let (value, remainder) = T::read(source, context)?;
+		let spanned = Spanned {
+			value,
+			start_from_end: source.len(),
+			end_from_end: remainder.len(),
+		};
+		Ok((spanned, remainder))
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for Spanned<T>
+where
+	T: Deparsable<Ctx>,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		self.value.write(w, context)
+	}
+}
+
+impl<T> ToStatic for Spanned<T>
+where
+	T: ToStatic,
+{
+	type Static = Spanned<T::Static>;
+
+	fn to_static(&self) -> Self::Static {
+		Spanned {
+			value: self.value.to_static(),
+			start_from_end: self.start_from_end,
+			end_from_end: self.end_from_end,
+		}
+	}
+}
+
+// Types with a companion type holding the range each of their fields was read from, generated by
+// `#[parst(spans)]`
+pub trait HasSpans {
+	type Spans;
+}
+
+pub trait ReadSpans<'a, Src, Ctx = ()>: HasSpans + Sized
+where
+	Src: ?Sized,
+{
+	// `origin` is the length of the whole input, which the ranges are offsets into
+	fn read_with_spans(
+		source: &'a Src,
+		context: Ctx,
+		origin: usize,
+	) -> PResult<'a, (Self, Self::Spans), Src>;
+}
+
+// Parses a `T` along with the range of `source` each of its fields was read from
+pub fn read_spans<'a, T, Src, Ctx>(source: &'a Src, context: Ctx) -> PResult<'a, (T, T::Spans), Src>
+where
+	Src: Source + ?Sized,
+	T: ReadSpans<'a, Src, Ctx>,
+{
+	T::read_with_spans(source, context, source.len())
+}
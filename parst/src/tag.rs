@@ -0,0 +1,69 @@
+use crate::{error::Error, Deparsable, PResult};
+
+// Sources which can be checked for a literal at their start
+pub trait Tag {
+	fn strip_tag(&self, tag: &str) -> Option<&Self>;
+
+	// Whether the whole source is the start of the tag, so that more of it might still complete it
+	#[inline]
+	fn is_tag_prefix(&self, _tag: &str) -> bool { false }
+}
+
+impl Tag for str {
+	#[inline]
+	fn strip_tag(&self, tag: &str) -> Option<&Self> { self.strip_prefix(tag) }
+
+	#[inline]
+	fn is_tag_prefix(&self, tag: &str) -> bool { self.len() < tag.len() && tag.starts_with(self) }
+}
+
+impl Tag for [u8] {
+	#[inline]
+	fn strip_tag(&self, tag: &str) -> Option<&Self> { self.strip_prefix(tag.as_bytes()) }
+
+	#[inline]
+	fn is_tag_prefix(&self, tag: &str) -> bool {
+		self.len() < tag.len() && tag.as_bytes().starts_with(self)
+	}
+}
+
+#[inline]
+pub fn read_tag<'a, Src>(source: &'a Src, tag: &'static str) -> PResult<'a, (), Src>
+where
+	Src: Tag + ?Sized,
+{
+	match source.strip_tag(tag) {
+		Some(source) => Ok(((), source)),
+		None if source.is_tag_prefix(tag) => Err((Error::NotEnoughBytes, source)),
+		None => Err((Error::Expected(tag), source)),
+	}
+}
+
+#[inline]
+pub fn write_tag(w: &mut impl std::io::Write, tag: &str) -> std::io::Result<()> {
+	w.write_all(tag.as_bytes())
+}
+
+// Tags for slices of any other element type, such as tokens, are sequences of elements
+#[inline]
+pub fn read_elements<'a, T>(source: &'a [T], tag: &[T]) -> PResult<'a, (), [T]>
+where
+	T: PartialEq,
+{
+	match source.strip_prefix(tag) {
+		Some(source) => Ok(((), source)),
+		None if source.len() < tag.len() && tag.starts_with(source) => {
+			Err((Error::NotEnoughBytes, source))
+		}
+		None => Err((Error::InvalidInput, source)),
+	}
+}
+
+#[inline]
+pub fn write_elements<T>(w: &mut impl std::io::Write, tag: &[T]) -> std::io::Result<()>
+where
+	T: Deparsable,
+{
+	tag.iter()
+		.try_for_each(|element| element.write(&mut *w, ()))
+}
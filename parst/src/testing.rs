@@ -0,0 +1,196 @@
+// Checks that what a type writes reads back as the same value. Values to check come from
+// `arbitrary`, so the checks also serve as the bodies of fuzz targets
+
+pub use arbitrary;
+
+use crate::{owned::OwnedBytes, Deparsable, Parsable};
+use arbitrary::{Arbitrary, Unstructured};
+use std::fmt::Debug;
+
+// Writes the value, then reads it back and checks that it is the same and used every byte written.
+// Types which borrow from their source can be checked through the owned form that
+// `#[derive(ToStatic)]` gives them. Returns the bytes written
+#[track_caller]
+pub fn assert_roundtrip<T, Ctx>(value: &T, context: Ctx) -> Vec<u8>
+where
+	T: for<'a> Parsable<'a, [u8], Ctx> + Deparsable<Ctx> + PartialEq + Debug,
+	Ctx: Copy,
+{
+	let mut bytes = Vec::new();
+	if let Err(e) = value.write(&mut bytes, context) {
+		panic!("could not write {:?}: {}", value, e);
+	}
+
+	match T::read(&bytes, context) {
+		Ok((read, rest)) => {
+			assert!(
+				rest.is_empty(),
+				"{} of the bytes written for {:?} were not read back: {:02x?}",
+				rest.len(),
+				value,
+				bytes
+			);
+			assert!(
+				read == *value,
+				"wrote {:?} as {:02x?} but read back {:?}",
+				value,
+				bytes,
+				read
+			);
+		}
+		Err((e, rest)) => panic!(
+			"could not read back {:?} from {:02x?}: {} at offset {}",
+			value,
+			bytes,
+			e,
+			bytes.len() - rest.len()
+		),
+	}
+	bytes
+}
+
+// Reads a value, then writes it and checks that the bytes are the ones it was read from. This holds
+// for formats with only one encoding of each value. Returns the value and the bytes left unread
+#[track_caller]
+pub fn assert_bytes_roundtrip<'a, T, Ctx>(bytes: &'a [u8], context: Ctx) -> (T, &'a [u8])
+where
+	T: Parsable<'a, [u8], Ctx> + Deparsable<Ctx> + Debug,
+	Ctx: Copy,
+{
+	let (value, rest) = match T::read(bytes, context) {
+		Ok(read) => read,
+		Err((e, rest)) => panic!(
+			"could not read from {:02x?}: {} at offset {}",
+			bytes,
+			e,
+			bytes.len() - rest.len()
+		),
+	};
+	let read = &bytes[..bytes.len() - rest.len()];
+
+	let mut written = Vec::new();
+	if let Err(e) = value.write(&mut written, context) {
+		panic!("could not write {:?}: {}", value, e);
+	}
+	assert!(
+		written == read,
+		"read {:?} from {:02x?} but wrote it as {:02x?}",
+		value,
+		read,
+		written
+	);
+	(value, rest)
+}
+
+// For fuzz targets: builds a value out of the data and checks that it roundtrips. Returns false
+// when the data does not give a value, such as when it does not meet the constraints of the type
+#[track_caller]
+pub fn check_arbitrary<T, Ctx>(data: &[u8], context: Ctx) -> bool
+where
+	T: for<'a> Arbitrary<'a>
+		+ for<'a> Parsable<'a, [u8], Ctx>
+		+ Deparsable<Ctx>
+		+ PartialEq
+		+ Debug,
+	Ctx: Copy,
+{
+	match T::arbitrary(&mut Unstructured::new(data)) {
+		Ok(value) => {
+			assert_roundtrip(&value, context);
+			true
+		}
+		Err(_) => false,
+	}
+}
+
+// For fuzz targets: checks that whatever can be read from the data is written back the same.
+// Returns false when nothing can be read
+#[track_caller]
+pub fn check_bytes<'a, T, Ctx>(data: &'a [u8], context: Ctx) -> bool
+where
+	T: Parsable<'a, [u8], Ctx> + Deparsable<Ctx> + Debug,
+	Ctx: Copy,
+{
+	match T::read(data, context) {
+		Ok(_) => {
+			assert_bytes_roundtrip::<T, Ctx>(data, context);
+			true
+		}
+		Err(_) => false,
+	}
+}
+
+// Checks values built from `cases` deterministic pseudo random inputs of growing length, for use in
+// ordinary tests. Returns how many of them gave a value, which should not be zero
+#[track_caller]
+pub fn roundtrip_random<T, Ctx>(context: Ctx, cases: usize) -> usize
+where
+	T: for<'a> Arbitrary<'a>
+		+ for<'a> Parsable<'a, [u8], Ctx>
+		+ Deparsable<Ctx>
+		+ PartialEq
+		+ Debug,
+	Ctx: Copy,
+{
+	let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+	let mut data = Vec::new();
+	(0..cases)
+		.filter(|case| {
+			data.clear();
+			data.extend((0..8 + case * 4).map(|_| {
+				// xorshift64
+				state ^= state << 13;
+				state ^= state >> 7;
+				state ^= state << 17;
+				state as u8
+			}));
+			check_arbitrary::<T, Ctx>(&data, context)
+		})
+		.count()
+}
+
+impl<'a> Arbitrary<'a> for OwnedBytes {
+	fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> { Ok(Self(u.arbitrary()?)) }
+
+	fn arbitrary_take_rest(u: Unstructured<'a>) -> arbitrary::Result<Self> {
+		Ok(Self(u.take_rest().to_vec()))
+	}
+}
+
+#[cfg(feature = "endian")]
+mod endian {
+	use crate::endian::{PackedBE, PackedLE, Primitive, BE, LE};
+	use arbitrary::{Arbitrary, Unstructured};
+
+	impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for LE<T> {
+		fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+			Ok(Self(u.arbitrary()?))
+		}
+
+		fn size_hint(depth: usize) -> (usize, Option<usize>) { T::size_hint(depth) }
+	}
+
+	impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for BE<T> {
+		fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+			Ok(Self(u.arbitrary()?))
+		}
+
+		fn size_hint(depth: usize) -> (usize, Option<usize>) { T::size_hint(depth) }
+	}
+
+	impl<'a, T: Primitive + Arbitrary<'a>> Arbitrary<'a> for PackedLE<T> {
+		fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+			Ok(Self::new(u.arbitrary()?))
+		}
+
+		fn size_hint(depth: usize) -> (usize, Option<usize>) { T::size_hint(depth) }
+	}
+
+	impl<'a, T: Primitive + Arbitrary<'a>> Arbitrary<'a> for PackedBE<T> {
+		fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+			Ok(Self::new(u.arbitrary()?))
+		}
+
+		fn size_hint(depth: usize) -> (usize, Option<usize>) { T::size_hint(depth) }
+	}
+}
@@ -0,0 +1,285 @@
+use crate::error::Error;
+use std::{
+	cell::RefCell,
+	fmt::{self, Debug, Write},
+	rc::Rc,
+};
+
+// Positions are given as how many bytes of the input were left, since a parser only ever sees the
+// rest of it. For sources of other elements than bytes, that is the size of those left
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'e> {
+	Enter {
+		ty: &'static str,
+		remaining: usize,
+	},
+	Field {
+		name: &'static str,
+		remaining: usize,
+		consumed: usize,
+		value: &'e dyn Debug,
+	},
+	FieldFailed {
+		name: &'static str,
+		remaining: usize,
+		failed_at: usize,
+		error: Error,
+	},
+	// On failure `remaining` is where the failure happened
+	Exit {
+		ty: &'static str,
+		remaining: usize,
+		result: Result<(), Error>,
+	},
+}
+
+pub trait Sink {
+	fn event(&mut self, event: &Event<'_>);
+}
+
+impl<F> Sink for F
+where
+	F: FnMut(&Event<'_>),
+{
+	fn event(&mut self, event: &Event<'_>) { self(event) }
+}
+
+thread_local! {
+	static SINK: RefCell<Option<Rc<RefCell<dyn Sink>>>> = const { RefCell::new(None) };
+}
+
+// Puts back the sink which was there before, even when unwinding
+struct Restore(Option<Rc<RefCell<dyn Sink>>>);
+
+impl Drop for Restore {
+	fn drop(&mut self) { SINK.with(|s| *s.borrow_mut() = self.0.take()) }
+}
+
+// Runs `f` with every event from the derived impls on this thread going to `sink`, and hands the
+// sink back afterwards
+pub fn with_sink<S, R>(sink: S, f: impl FnOnce() -> R) -> (R, S)
+where
+	S: Sink + 'static,
+{
+	let sink = Rc::new(RefCell::new(sink));
+	let previous = SINK.with(|s| s.borrow_mut().replace(sink.clone()));
+	let restore = Restore(previous);
+	let result = f();
+	drop(restore);
+
+	let sink = Rc::try_unwrap(sink)
+		.ok()
+		.expect("sink is still installed")
+		.into_inner();
+	(result, sink)
+}
+
+// Events raised while the sink is already handling one, such as by a `Debug` impl which parses, are
+// dropped rather than handed to it again
+pub fn emit(event: Event<'_>) {
+	let Some(sink) = SINK.with(|s| s.borrow().clone()) else {
+		return;
+	};
+	let Ok(mut handler) = sink.try_borrow_mut() else {
+		return;
+	};
+	handler.event(&event);
+}
+
+// Called by the derived impls
+
+// Any source has a size, so tracing puts no bound on the sources of generic impls
+pub fn remaining<Src: ?Sized>(source: &Src) -> usize { size_of_val(source) }
+
+pub fn enter(ty: &'static str, remaining: usize) { emit(Event::Enter { ty, remaining }) }
+
+pub fn field(name: &'static str, remaining: usize, consumed: usize, value: &dyn Debug) {
+	emit(Event::Field {
+		name,
+		remaining,
+		consumed,
+		value,
+	})
+}
+
+pub fn field_failed(name: &'static str, remaining: usize, failed_at: usize, error: Error) {
+	emit(Event::FieldFailed {
+		name,
+		remaining,
+		failed_at,
+		error,
+	})
+}
+
+pub fn exit(ty: &'static str, remaining: usize, result: Result<(), Error>) {
+	emit(Event::Exit {
+		ty,
+		remaining,
+		result,
+	})
+}
+
+// Field values are shown with their `Debug` impl when they have one. Calling `trace_value` on a
+// `&Wrap` picks `ViaDebug` when it applies, and otherwise auto-refs to `ViaOpaque`
+pub struct Wrap<'a, T>(pub &'a T);
+
+pub trait ViaDebug {
+	fn trace_value(&self) -> &dyn Debug;
+}
+
+impl<T> ViaDebug for Wrap<'_, T>
+where
+	T: Debug,
+{
+	fn trace_value(&self) -> &dyn Debug { self.0 }
+}
+
+pub trait ViaOpaque {
+	fn trace_value(&self) -> &dyn Debug;
+}
+
+impl<T> ViaOpaque for &Wrap<'_, T> {
+	fn trace_value(&self) -> &dyn Debug { &Opaque }
+}
+
+struct Opaque;
+
+impl Debug for Opaque {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("..") }
+}
+
+struct Row {
+	depth: usize,
+	start: usize,
+	end: Option<usize>,
+	label: String,
+	named: bool,
+	failure: Option<Error>,
+}
+
+// Collects events into an annotated hexdump, with each type and field next to the bytes it was read
+// from, nested fields indented beneath the field holding them, and failures marked where they
+// happened
+#[derive(Default)]
+pub struct Hexdump {
+	rows: Vec<Row>,
+	open: Vec<usize>,
+}
+
+const ROW_BYTES: usize = 16;
+
+impl Hexdump {
+	// A field read by a derived impl has already got a row from entering its type, which is named
+	// rather than adding another
+	fn name_last(&mut self, name: &str, remaining: usize) -> bool {
+		let depth = self.open.len();
+		match self.rows.iter_mut().rev().find(|row| row.depth <= depth) {
+			Some(row) if row.depth == depth && !row.named && row.start == remaining => {
+				row.label = format!("{}: {}", name, row.label);
+				row.named = true;
+				true
+			}
+			_ => false,
+		}
+	}
+
+	// `input` is the whole of what was parsed. Given anything shorter, rows are clamped to it
+	// rather than panicking
+	pub fn render(&self, input: &[u8]) -> String {
+		let mut out = String::new();
+		for row in &self.rows {
+			let start = input.len().saturating_sub(row.start);
+			let end = row
+				.end
+				.map_or(start, |end| input.len().saturating_sub(end))
+				.max(start);
+
+			let mut bytes = String::new();
+			let shown = &input[start..end.min(start + ROW_BYTES)];
+			for b in shown {
+				write!(bytes, "{:02x} ", b).unwrap();
+			}
+			if end - start > ROW_BYTES {
+				bytes.replace_range(bytes.len() - 3.., "..");
+			}
+
+			write!(
+				out,
+				"{:08x}  {:<width$} {:indent$}{}",
+				start,
+				bytes,
+				"",
+				row.label,
+				width = ROW_BYTES * 3,
+				indent = row.depth * 2
+			)
+			.unwrap();
+			if let Some(failure) = row.failure {
+				write!(out, "  <-- {}", failure).unwrap();
+			}
+			out.push('\n');
+		}
+		out
+	}
+}
+
+impl Sink for Hexdump {
+	fn event(&mut self, event: &Event<'_>) {
+		match *event {
+			Event::Enter { ty, remaining } => {
+				self.open.push(self.rows.len());
+				self.rows.push(Row {
+					depth: self.open.len() - 1,
+					start: remaining,
+					end: None,
+					label: ty.to_string(),
+					named: false,
+					failure: None,
+				});
+			}
+			Event::Exit {
+				remaining, result, ..
+			} => {
+				if let Some(index) = self.open.pop() {
+					let row = &mut self.rows[index];
+					row.end = Some(remaining);
+					row.failure = result.err();
+				}
+			}
+			Event::Field {
+				name,
+				remaining,
+				consumed,
+				value,
+			} => {
+				if !self.name_last(name, remaining) {
+					self.rows.push(Row {
+						depth: self.open.len(),
+						start: remaining,
+						end: Some(remaining - consumed),
+						label: format!("{}: {:?}", name, value),
+						named: true,
+						failure: None,
+					});
+				}
+			}
+			Event::FieldFailed {
+				name,
+				remaining,
+				failed_at,
+				error,
+			} => {
+				if !self.name_last(name, remaining) {
+					self.rows.push(Row {
+						depth: self.open.len(),
+						start: remaining,
+						end: Some(failed_at),
+						label: name.to_string(),
+						named: true,
+						failure: Some(error),
+					});
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "testing")]
+
+use parst::{
+	testing::{
+		arbitrary::{Arbitrary, Unstructured},
+		assert_roundtrip, roundtrip_random,
+	},
+	Arbitrary, Deparsable, Parsable,
+};
+
+#[derive(Debug, PartialEq, Parsable, Deparsable, Arbitrary)]
+#[parst(src = "[u8]", dis = "u16")]
+enum Message {
+	#[parst(dis = "1")]
+	Ping,
+	#[parst(dis_pat = "1000..=1002")]
+	Reserved(#[parst(dis)] u16),
+	#[parst(dis_pat = "40000..")]
+	High {
+		#[parst(dis)]
+		kind: u16,
+		#[parst(matches = "-5..=5")]
+		level: i8,
+		#[parst(matches = "..10")]
+		count: u8,
+	},
+}
+
+// Told apart by their tags rather than a discriminant
+#[derive(Debug, PartialEq, Parsable, Deparsable, Arbitrary)]
+#[parst(src = "[u8]")]
+enum Tagged {
+	#[parst(tag = "A")]
+	A(u8),
+	#[parst(tag = "B")]
+	B(u16),
+}
+
+#[test]
+fn ranges_generated_directly() {
+	// Every input gives a value, where drawing a whole u16 would rarely land in the ranges
+	assert_eq!(roundtrip_random::<Message, _>((), 200), 200);
+	for seed in 0..=255u8 {
+		let data = [seed, seed.wrapping_mul(7), seed ^ 0x5a, 1, 2, 3];
+		match Message::arbitrary(&mut Unstructured::new(&data)).unwrap() {
+			Message::Ping => (),
+			Message::Reserved(dis) => assert!((1000..=1002).contains(&dis)),
+			Message::High { kind, level, count } => {
+				assert!(kind >= 40000);
+				assert!((-5..=5).contains(&level));
+				assert!(count < 10);
+			}
+		}
+	}
+}
+
+#[test]
+fn tagged_variants_roundtrip() {
+	assert_eq!(roundtrip_random::<Tagged, _>((), 100), 100);
+	assert_roundtrip(&Tagged::B(0x4142), ());
+}
@@ -0,0 +1,37 @@
+use parst::{endian::BE, Deparsable, FromBytes, Parsable};
+
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes)]
+#[repr(C)]
+struct Empty {}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", lifetime = "'a")]
+struct Block<'a> {
+	len: u8,
+	#[parst(ctx = "len as usize")]
+	words: &'a [u8],
+	rest: Vec<BE<u16>>,
+}
+
+#[test]
+fn bulk_paths_roundtrip() {
+	let bytes = [2, 7, 8, 0, 1, 0, 2, 9];
+	let (block, rest) = Block::read(&bytes, ()).unwrap();
+	assert_eq!(rest, [9]);
+	assert_eq!(block.words, [7, 8]);
+	assert_eq!(block.rest, [BE(1), BE(2)]);
+
+	let mut written = Vec::new();
+	block.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes[..7]);
+}
+
+#[test]
+fn zero_sized_elements_without_count() {
+	let bytes = [1, 2, 3];
+	assert_eq!(Vec::<Empty>::read(&bytes, ()), Ok((vec![], &bytes[..])));
+	assert_eq!(
+		<[Empty; 2]>::read(&bytes, ()),
+		Ok(([Empty {}, Empty {}], &bytes[..]))
+	);
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "bytes")]
+
+use parst::{
+	bytes::{self, Bytes, BytesMut},
+	Deparsable, Parsable,
+};
+use std::io;
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]")]
+struct Frame {
+	len: u8,
+	#[parst(ctx = "len as usize")]
+	body: Bytes,
+	rest: Bytes,
+}
+
+fn within(inner: &[u8], outer: &[u8]) -> bool {
+	let outer = outer.as_ptr_range();
+	outer.start <= inner.as_ptr() && inner.as_ptr_range().end <= outer.end
+}
+
+#[test]
+fn read_shares_the_buffer() {
+	let buffer = Bytes::from_static(b"\x02abcdef");
+	let (frame, remainder) = bytes::read::<Frame, _>(&buffer, ()).unwrap();
+	assert_eq!(frame.body, &b"ab"[..]);
+	assert_eq!(frame.rest, &b"cdef"[..]);
+	assert_eq!(frame.body.as_ptr(), buffer[1..].as_ptr());
+	assert_eq!(frame.rest.as_ptr(), buffer[3..].as_ptr());
+	assert!(remainder.is_empty());
+}
+
+#[test]
+fn read_from_a_slice_copies() {
+	let buffer = b"\x02abcdef".to_vec();
+	let (frame, _) = Frame::read(&buffer, ()).unwrap();
+	assert_eq!(frame.body, &b"ab"[..]);
+	assert!(!within(&frame.body, &buffer));
+	assert!(!within(&frame.rest, &buffer));
+}
+
+// Fails after the bytes before it are written
+struct Unwritable;
+
+impl Deparsable for Unwritable {
+	fn write(&self, _w: &mut impl io::Write, _context: ()) -> io::Result<()> {
+		Err(io::ErrorKind::InvalidInput.into())
+	}
+}
+
+#[test]
+fn write_appends_or_leaves_buffer() {
+	let mut dst = BytesMut::from(&b"head"[..]);
+	bytes::write(&Bytes::from_static(b"tail"), &mut dst, ()).unwrap();
+	assert_eq!(&dst[..], b"headtail");
+
+	let value = (Bytes::from_static(b"more"), Unwritable);
+	assert!(bytes::write(&value, &mut dst, ()).is_err());
+	assert_eq!(&dst[..], b"headtail");
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "codec")]
+
+use parst::{
+	bytes::{Bytes, BytesMut},
+	codec::ParstCodec,
+	error::Error,
+	Deparsable, Parsable,
+};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+// Told apart by their tags, so a frame cut short fails every variant
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+enum Message {
+	#[parst(tag = "A")]
+	A(u32),
+	#[parst(tag = "B")]
+	B(u16),
+}
+
+fn parst_error(error: &io::Error) -> Option<Error> {
+	error.get_ref()?.downcast_ref::<Error>().copied()
+}
+
+#[test]
+fn split_frame_waits_for_the_rest() {
+	let mut codec = ParstCodec::<Message>::new();
+	let mut src = BytesMut::from(&b"A\x01\x02"[..]);
+	assert_eq!(codec.decode(&mut src).unwrap(), None);
+	assert_eq!(&src[..], b"A\x01\x02");
+
+	src.extend_from_slice(b"\x00\x00");
+	assert_eq!(codec.decode(&mut src).unwrap(), Some(Message::A(0x0201)));
+	assert!(src.is_empty());
+}
+
+#[test]
+fn several_frames_in_one_buffer() {
+	let mut codec = ParstCodec::<Message>::new();
+	let mut src = BytesMut::from(&b"B\x01\x00A\x02\x00\x00\x00B\x03"[..]);
+	assert_eq!(codec.decode(&mut src).unwrap(), Some(Message::B(1)));
+	assert_eq!(codec.decode(&mut src).unwrap(), Some(Message::A(2)));
+	assert_eq!(codec.decode(&mut src).unwrap(), None);
+	assert_eq!(&src[..], b"B\x03");
+}
+
+#[test]
+fn frame_over_max_frame() {
+	let mut codec = ParstCodec::<Message>::new().with_max_frame(3);
+	let mut src = BytesMut::from(&b"A\x01\x02"[..]);
+	let error = codec.decode(&mut src).unwrap_err();
+	assert_eq!(parst_error(&error), Some(Error::LimitExceeded));
+
+	let mut dst = BytesMut::from(&b"B\x01\x00"[..]);
+	let error = codec.encode(Message::A(1), &mut dst).unwrap_err();
+	assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+	assert_eq!(&dst[..], b"B\x01\x00");
+}
+
+#[test]
+fn invalid_frame_rejected() {
+	let mut codec = ParstCodec::<Message>::new();
+	let mut src = BytesMut::from(&b"C\x01"[..]);
+	let error = codec.decode(&mut src).unwrap_err();
+	assert_eq!(parst_error(&error), Some(Error::InvalidInput));
+}
+
+#[test]
+fn decode_eof_with_partial_frame() {
+	let mut codec = ParstCodec::<Message>::new();
+	let mut src = BytesMut::from(&b"B\x01\x00A\x02"[..]);
+	assert_eq!(codec.decode_eof(&mut src).unwrap(), Some(Message::B(1)));
+	let error = codec.decode_eof(&mut src).unwrap_err();
+	assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+	let mut src = BytesMut::new();
+	assert_eq!(codec.decode_eof(&mut src).unwrap(), None);
+}
+
+#[test]
+fn encoded_frames_decode() {
+	let mut codec = ParstCodec::<Message>::new();
+	let mut buffer = BytesMut::new();
+	codec.encode(Message::A(7), &mut buffer).unwrap();
+	codec.encode(&Message::B(8), &mut buffer).unwrap();
+	assert_eq!(codec.decode(&mut buffer).unwrap(), Some(Message::A(7)));
+	assert_eq!(codec.decode(&mut buffer).unwrap(), Some(Message::B(8)));
+	assert!(buffer.is_empty());
+}
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]")]
+struct Blob {
+	len: u8,
+	#[parst(ctx = "len as usize")]
+	body: Bytes,
+}
+
+#[test]
+fn decoded_frames_share_the_buffer() {
+	let mut codec = ParstCodec::<Blob>::new();
+	let mut src = BytesMut::from(&b"\x03abc\x01d"[..]);
+	let start = src.as_ptr();
+	let blob = codec.decode(&mut src).unwrap().unwrap();
+	assert_eq!(blob.body, &b"abc"[..]);
+	assert_eq!(blob.body.as_ptr(), start.wrapping_add(1));
+
+	let blob = codec.decode(&mut src).unwrap().unwrap();
+	assert_eq!(blob.body, &b"d"[..]);
+	assert_eq!(blob.body.as_ptr(), start.wrapping_add(5));
+}
@@ -0,0 +1,94 @@
+use parst::{error::Error, Deparsable, Parsable};
+
+// Bytes taking up as many as the context gives, which has to match when writing
+#[derive(Debug, PartialEq)]
+struct Chunk(Vec<u8>);
+
+impl<'a> Parsable<'a, [u8], usize> for Chunk {
+	fn read(source: &'a [u8], len: usize) -> parst::PResultBytes<'a, Self> {
+		let (bytes, source) = source
+			.split_at_checked(len)
+			.ok_or((Error::NotEnoughBytes, source))?;
+		Ok((Chunk(bytes.to_vec()), source))
+	}
+}
+
+impl Deparsable<usize> for Chunk {
+	fn write(&self, w: &mut impl std::io::Write, len: usize) -> std::io::Result<()> {
+		if self.0.len() != len {
+			return Err(Error::InvalidInput.into());
+		}
+		w.write_all(&self.0)
+	}
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", ctx = "len: usize")]
+struct Pair {
+	#[parst(ctx_inherit)]
+	first: Chunk,
+	#[parst(ctx_inherit)]
+	second: Chunk,
+	tail: u8,
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+struct Message {
+	len: u8,
+	#[parst(ctx = "len as usize")]
+	pair: Pair,
+}
+
+// Without a `ctx` attribute the context is generic, and inheriting it needs the field to take it
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+struct Wrapper {
+	#[parst(ctx_inherit)]
+	chunk: Chunk,
+}
+
+fn written<Ctx>(value: &impl Deparsable<Ctx>, context: Ctx) -> std::io::Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	value.write(&mut bytes, context)?;
+	Ok(bytes)
+}
+
+#[test]
+fn ctx_inherit_passes_context_to_fields() {
+	let bytes = [1, 2, 3, 4, 9];
+	let pair = Pair {
+		first: Chunk(vec![1, 2]),
+		second: Chunk(vec![3, 4]),
+		tail: 9,
+	};
+	assert_eq!(Pair::read(&bytes, 2), Ok((pair, &[][..])));
+	let (pair, rest) = Pair::read(&bytes, 1).unwrap();
+	assert_eq!(
+		(pair.first, pair.second, pair.tail),
+		(Chunk(vec![1]), Chunk(vec![2]), 3)
+	);
+	assert_eq!(rest, [4, 9]);
+
+	let pair = Pair::read(&bytes, 2).unwrap().0;
+	assert_eq!(written(&pair, 2).unwrap(), bytes);
+	assert!(written(&pair, 3).is_err());
+}
+
+#[test]
+fn ctx_inherit_through_parent() {
+	let bytes = [3, 1, 2, 3, 4, 5, 6, 7];
+	let (message, rest) = Message::read(&bytes, ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(message.pair.second, Chunk(vec![4, 5, 6]));
+	assert_eq!(written(&message, ()).unwrap(), bytes);
+}
+
+#[test]
+fn ctx_inherit_generic_context() {
+	let (wrapper, rest) = Wrapper::read(&[1, 2, 3], 2).unwrap();
+	assert_eq!(wrapper.chunk, Chunk(vec![1, 2]));
+	assert_eq!(rest, [3]);
+	assert_eq!(written(&wrapper, 2).unwrap(), [1, 2]);
+	assert!(written(&wrapper, 1).is_err());
+}
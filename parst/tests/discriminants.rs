@@ -0,0 +1,189 @@
+use parst::{error::Error, Deparsable, PResultBytes, Parsable};
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", dis = "u8")]
+enum Utf8Byte {
+	#[parst(dis_pat = "0..=0x7f")]
+	Ascii(#[parst(dis)] u8),
+	#[parst(dis_pat = "0x80..=0xbf")]
+	Continuation(#[parst(dis)] u8),
+	#[parst(dis_pat = "0xc0 | 0xe0 | 0xf0")]
+	Lead(#[parst(dis)] u8, u8),
+}
+
+fn write(value: &Utf8Byte) -> std::io::Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	value.write(&mut bytes, ())?;
+	Ok(bytes)
+}
+
+#[test]
+fn dis_pat_selects_variant() {
+	assert_eq!(
+		Utf8Byte::read(&[0x41], ()),
+		Ok((Utf8Byte::Ascii(0x41), &[][..]))
+	);
+	assert_eq!(
+		Utf8Byte::read(&[0x85], ()),
+		Ok((Utf8Byte::Continuation(0x85), &[][..]))
+	);
+	assert_eq!(
+		Utf8Byte::read(&[0xe0, 7], ()),
+		Ok((Utf8Byte::Lead(0xe0, 7), &[][..]))
+	);
+}
+
+#[test]
+fn dis_field_written_as_discriminant() {
+	assert_eq!(write(&Utf8Byte::Continuation(0x90)).unwrap(), [0x90]);
+	assert_eq!(write(&Utf8Byte::Lead(0xf0, 1)).unwrap(), [0xf0, 1]);
+}
+
+#[test]
+fn dis_field_outside_pattern_rejected() {
+	let error = write(&Utf8Byte::Ascii(0x90)).unwrap_err();
+	assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+	assert!(write(&Utf8Byte::Lead(0xc1, 0)).is_err());
+}
+
+// A discriminant taking up as many bytes as its context gives, most significant first
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Wide(u32);
+
+impl<'a> Parsable<'a, [u8], usize> for Wide {
+	fn read(source: &'a [u8], width: usize) -> PResultBytes<'a, Self> {
+		let (bytes, source) = source
+			.split_at_checked(width)
+			.ok_or((Error::NotEnoughBytes, source))?;
+		let value = bytes.iter().fold(0, |value, &b| value << 8 | u32::from(b));
+		Ok((Wide(value), source))
+	}
+}
+
+impl Deparsable<usize> for Wide {
+	fn write(&self, w: &mut impl std::io::Write, width: usize) -> std::io::Result<()> {
+		w.write_all(&self.0.to_be_bytes()[4 - width..])
+	}
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", dis = "Wide", dis_ctx = "2")]
+enum FixedWidth {
+	#[parst(dis = "Wide(1)")]
+	One(u8),
+	#[parst(dis = "Wide(0x0100)")]
+	Big,
+}
+
+// The enum's own context is passed on to the discriminant
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", ctx = "width: usize", dis = "Wide", dis_ctx)]
+enum GivenWidth {
+	#[parst(dis = "Wide(1)")]
+	One(u8),
+	#[parst(dis = "Wide(2)")]
+	Two,
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", ctx = "kind: u8", dis_from_ctx = "kind")]
+enum Body {
+	#[parst(dis = "1")]
+	Byte(u8),
+	#[parst(dis = "2")]
+	Pair(u8, u8),
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+struct Message {
+	kind: u8,
+	#[parst(ctx = "kind")]
+	body: Body,
+}
+
+fn written<Ctx>(value: &impl Deparsable<Ctx>, context: Ctx) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	value.write(&mut bytes, context).unwrap();
+	bytes
+}
+
+#[test]
+fn dis_ctx_read_and_write() {
+	assert_eq!(
+		FixedWidth::read(&[0, 1, 7], ()),
+		Ok((FixedWidth::One(7), &[][..]))
+	);
+	assert_eq!(
+		FixedWidth::read(&[1, 0], ()),
+		Ok((FixedWidth::Big, &[][..]))
+	);
+	assert_eq!(
+		FixedWidth::read(&[0], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(written(&FixedWidth::One(7), ()), [0, 1, 7]);
+	assert_eq!(written(&FixedWidth::Big, ()), [1, 0]);
+
+	assert_eq!(
+		GivenWidth::read(&[1, 9], 1),
+		Ok((GivenWidth::One(9), &[][..]))
+	);
+	assert_eq!(
+		GivenWidth::read(&[0, 0, 0, 2], 4),
+		Ok((GivenWidth::Two, &[][..]))
+	);
+	assert_eq!(written(&GivenWidth::One(9), 3), [0, 0, 1, 9]);
+	assert_eq!(written(&GivenWidth::Two, 1), [2]);
+}
+
+#[test]
+fn dis_from_ctx_read_and_write() {
+	assert_eq!(Body::read(&[5, 6], 1), Ok((Body::Byte(5), &[6][..])));
+	assert_eq!(Body::read(&[5, 6], 2), Ok((Body::Pair(5, 6), &[][..])));
+	assert!(Body::read(&[5, 6], 3).is_err());
+
+	// The discriminant is the parent's to write, so none of it is written by the enum
+	assert_eq!(written(&Body::Byte(5), 1), [5]);
+	assert_eq!(written(&Body::Pair(5, 6), 2), [5, 6]);
+
+	let message = Message {
+		kind: 2,
+		body: Body::Pair(5, 6),
+	};
+	assert_eq!(written(&message, ()), [2, 5, 6]);
+	assert_eq!(Message::read(&[2, 5, 6], ()), Ok((message, &[][..])));
+}
+
+// Without a discriminant each variant is tried in turn
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]")]
+enum Untagged {
+	Pair(#[parst(matches = "0")] u8, u8),
+	Word(#[parst(matches = "1")] u8, u16),
+}
+
+#[test]
+fn no_discriminant_cut_short() {
+	assert_eq!(
+		Untagged::read(&[0, 5], ()),
+		Ok((Untagged::Pair(0, 5), &[][..]))
+	);
+	assert!(matches!(
+		Untagged::read(&[1, 5, 6], ()),
+		Ok((Untagged::Word(1, _), []))
+	));
+
+	// Any variant running out of bytes means more input might still read
+	for bytes in [&[][..], &[0], &[1, 5]] {
+		assert_eq!(
+			Untagged::read(bytes, ()),
+			Err((Error::NotEnoughBytes, bytes))
+		);
+	}
+	// And when none does, no more input would help
+	assert_eq!(
+		Untagged::read(&[2, 5, 6], ()).unwrap_err().0,
+		Error::InvalidInput
+	);
+}
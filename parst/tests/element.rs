@@ -0,0 +1,120 @@
+use parst::{
+	element::{Any, Literal, Not, Peek, Satisfy, Take},
+	error::Error,
+	limits::{with_limits, Limits},
+	Deparsable, Parsable,
+};
+use std::borrow::Cow;
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]", max_depth = "2")]
+struct Tree {
+	#[parst(assert_eq = "1")]
+	open: u8,
+	children: Vec<Tree>,
+}
+
+#[test]
+fn any_and_take() {
+	let source = [1u16, 2, 3];
+	assert_eq!(Any::read(&source[..], ()), Ok((Any(1), &source[1..])));
+	assert_eq!(
+		Take::<_, 2>::read(&source[..], ()),
+		Ok((Take([1, 2]), &source[2..]))
+	);
+	assert_eq!(
+		Take::<u16, 4>::read(&source[..], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(
+		Any::<u16>::read(&[][..], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
+
+#[test]
+fn satisfy() {
+	let source = [2u8, 1];
+	let even = |element: &u8| element.is_multiple_of(2);
+	assert_eq!(
+		Satisfy::read(&source[..], even),
+		Ok((Satisfy(2), &source[1..]))
+	);
+	assert_eq!(
+		Satisfy::read(&source[1..], even),
+		Err((Error::InvalidInput, &source[1..]))
+	);
+	assert_eq!(
+		Satisfy::read(&source[2..], even),
+		Err((Error::NotEnoughBytes, &source[2..]))
+	);
+
+	// The predicate is not checked when writing
+	let mut written = Vec::new();
+	Satisfy(3u8).write(&mut written, even).unwrap();
+	assert_eq!(written, [3]);
+}
+
+#[test]
+fn literal() {
+	let source = [1u8, 2, 3];
+	let (literal, rest) = Literal::read(&source[..], &[1, 2]).unwrap();
+	assert!(matches!(literal.0, Cow::Borrowed([1, 2])));
+	assert_eq!(rest, [3]);
+
+	// A source which is the start of the literal is cut short, anything else does not match
+	assert_eq!(
+		Literal::read(&source[..], &[1, 2, 3, 4]).unwrap_err(),
+		(Error::NotEnoughBytes, &source[..])
+	);
+	assert_eq!(
+		Literal::read(&source[..2], &[1, 3, 5]).unwrap_err(),
+		(Error::InvalidInput, &source[..2])
+	);
+	assert_eq!(
+		Literal::read(&source[..], &[2]).unwrap_err(),
+		(Error::InvalidInput, &source[..])
+	);
+	assert_eq!(Literal::read(&source[..], &[]).unwrap().1, source);
+}
+
+#[test]
+fn peek_consumes_nothing() {
+	let source = [7u8, 8];
+	assert_eq!(
+		Peek::<u8>::read(&source[..], ()),
+		Ok((Peek(7), &source[..]))
+	);
+	assert_eq!(
+		Peek::<u16>::read(&source[1..], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+
+	let mut written = Vec::new();
+	Peek(7u8).write(&mut written, ()).unwrap();
+	assert!(written.is_empty());
+}
+
+#[test]
+fn not() {
+	let source = [1u8];
+	assert_eq!(Not::<u16>::read(&source[..], ()).unwrap().1, source);
+	assert_eq!(
+		Not::<u8>::read(&source[..], ()).unwrap_err(),
+		(Error::InvalidInput, &source[..])
+	);
+}
+
+#[test]
+fn not_keeps_limit_errors() {
+	let bytes = [0; 32];
+	let limits = Limits::UNLIMITED.with_max_bytes(8);
+	let error = with_limits(limits, || Not::<Vec<u16>>::read(&bytes[..], ())).unwrap_err();
+	assert_eq!(error.0, Error::LimitExceeded);
+
+	assert!(Not::<Tree>::read(&[0][..], ()).is_ok());
+	assert_eq!(
+		Not::<Tree>::read(&[1, 1, 1][..], ()).unwrap_err().0,
+		Error::RecursionLimit
+	);
+}
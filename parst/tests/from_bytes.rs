@@ -0,0 +1,34 @@
+use parst::{
+	endian::{PackedBE, PackedLE},
+	pod::FromBytes,
+	Deparsable, FromBytes, Parsable,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes)]
+#[repr(C)]
+struct Header {
+	kind: u8,
+	len: PackedBE<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes)]
+#[repr(C)]
+struct Record {
+	header: Header,
+	value: PackedLE<u32>,
+}
+
+#[test]
+fn derived_from_bytes_in_place() {
+	let bytes = [1, 0, 4, 0x78, 0x56, 0x34, 0x12];
+	let (record, rest) = Record::read(&bytes, ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(record.header.kind, 1);
+	assert_eq!(record.header.len.get(), 4);
+	assert_eq!(record.value.get(), 0x12345678);
+	assert_eq!(record.as_bytes(), bytes);
+
+	let mut written = Vec::new();
+	[record, record].write(&mut written, ()).unwrap();
+	assert_eq!(written, [bytes, bytes].concat());
+}
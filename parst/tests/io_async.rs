@@ -0,0 +1,182 @@
+#![cfg(feature = "async")]
+
+use parst::{
+	error::Error,
+	io_async::{read_buffered, read_fixed, read_prefixed, write, write_prefixed},
+	Deparsable, Parsable,
+};
+use std::{future::Future, io, time::Duration};
+use tokio::{
+	io::{duplex, AsyncWriteExt, BufReader},
+	task::yield_now,
+	time::timeout,
+};
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", fixed_size)]
+struct Header {
+	kind: u8,
+	len: u16,
+}
+
+// Told apart by their tags, so a message cut short fails every variant
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+enum Message {
+	#[parst(tag = "A")]
+	A(u32),
+	#[parst(tag = "B")]
+	B(u16),
+}
+
+// Each read must finish while the writer is still open, rather than wait for more
+async fn promptly<T>(read: impl Future<Output = T>) -> T {
+	timeout(Duration::from_secs(5), read)
+		.await
+		.expect("read waited for more than was needed")
+}
+
+fn parst_error(error: &io::Error) -> Option<Error> {
+	error.get_ref()?.downcast_ref::<Error>().copied()
+}
+
+#[tokio::test]
+async fn read_fixed_exact_message() {
+	let (mut client, mut server) = duplex(64);
+	client.write_all(&[7, 1, 0, 9]).await.unwrap();
+	let header = promptly(read_fixed::<Header, _, _>(&mut server, ()))
+		.await
+		.unwrap();
+	assert_eq!(header, Header { kind: 7, len: 1 });
+}
+
+#[tokio::test]
+async fn read_fixed_split_message() {
+	let (mut client, mut server) = duplex(64);
+	let send = async {
+		client.write_all(&[7]).await.unwrap();
+		yield_now().await;
+		client.write_all(&[2, 0]).await.unwrap();
+	};
+	let (header, ()) = tokio::join!(read_fixed::<Header, _, _>(&mut server, ()), send);
+	assert_eq!(header.unwrap(), Header { kind: 7, len: 2 });
+}
+
+#[tokio::test]
+async fn read_prefixed_messages() {
+	let (mut client, mut server) = duplex(64);
+	client.write_all(&[3, 7, 1, 0]).await.unwrap();
+	let header = promptly(read_prefixed::<u8, Header, _, _>(&mut server, 16, ()))
+		.await
+		.unwrap();
+	assert_eq!(header, Header { kind: 7, len: 1 });
+
+	// The length is checked before the rest arrives
+	client.write_all(&[200]).await.unwrap();
+	let error = promptly(read_prefixed::<u8, Header, _, _>(&mut server, 16, ()))
+		.await
+		.unwrap_err();
+	assert_eq!(parst_error(&error), Some(Error::LimitExceeded));
+
+	drop(client);
+	let error = read_prefixed::<u8, Header, _, _>(&mut server, 16, ())
+		.await
+		.unwrap_err();
+	assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn read_buffered_exact_message() {
+	let (mut client, server) = duplex(64);
+	let mut server = BufReader::new(server);
+	client.write_all(b"B\x05\x00").await.unwrap();
+	let message = promptly(read_buffered::<Message, _, _>(&mut server, 16, ()))
+		.await
+		.unwrap();
+	assert_eq!(message, Message::B(5));
+
+	// Bytes past the value stay in the reader for the next one
+	client
+		.write_all(b"A\x01\x00\x00\x00B\x02\x00")
+		.await
+		.unwrap();
+	let message = promptly(read_buffered::<Message, _, _>(&mut server, 16, ()))
+		.await
+		.unwrap();
+	assert_eq!(message, Message::A(1));
+	let message = promptly(read_buffered::<Message, _, _>(&mut server, 16, ()))
+		.await
+		.unwrap();
+	assert_eq!(message, Message::B(2));
+}
+
+#[tokio::test]
+async fn read_buffered_split_message() {
+	let (mut client, server) = duplex(64);
+	let mut server = BufReader::new(server);
+	let send = async {
+		client.write_all(b"A\x01").await.unwrap();
+		yield_now().await;
+		client.write_all(b"\x00\x00\x00").await.unwrap();
+	};
+	let (message, ()) = tokio::join!(read_buffered::<Message, _, _>(&mut server, 16, ()), send);
+	assert_eq!(message.unwrap(), Message::A(1));
+}
+
+#[tokio::test]
+async fn read_buffered_oversize_and_invalid() {
+	let (mut client, server) = duplex(64);
+	let mut server = BufReader::new(server);
+	client.write_all(b"A\x01").await.unwrap();
+	let error = promptly(read_buffered::<Message, _, _>(&mut server, 2, ()))
+		.await
+		.unwrap_err();
+	assert_eq!(parst_error(&error), Some(Error::LimitExceeded));
+
+	let (mut client, server) = duplex(64);
+	let mut server = BufReader::new(server);
+	client.write_all(b"C\x01").await.unwrap();
+	let error = promptly(read_buffered::<Message, _, _>(&mut server, 16, ()))
+		.await
+		.unwrap_err();
+	assert_eq!(parst_error(&error), Some(Error::InvalidInput));
+
+	// A tag cut short by the end of the stream
+	let (mut client, server) = duplex(64);
+	let mut server = BufReader::new(server);
+	client.write_all(b"A\x01").await.unwrap();
+	drop(client);
+	let error = read_buffered::<Message, _, _>(&mut server, 16, ())
+		.await
+		.unwrap_err();
+	assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn writes_read_back() {
+	let (mut client, mut server) = duplex(64);
+	write(&mut client, &Header { kind: 1, len: 2 }, ())
+		.await
+		.unwrap();
+	write_prefixed::<u8, _, _, _>(&mut client, &Message::B(3), ())
+		.await
+		.unwrap();
+
+	let header = promptly(read_fixed::<Header, _, _>(&mut server, ()))
+		.await
+		.unwrap();
+	assert_eq!(header, Header { kind: 1, len: 2 });
+	let message = promptly(read_prefixed::<u8, Message, _, _>(&mut server, 16, ()))
+		.await
+		.unwrap();
+	assert_eq!(message, Message::B(3));
+}
+
+#[tokio::test]
+async fn write_prefixed_too_long() {
+	let (mut client, _server) = duplex(1024);
+	let error = write_prefixed::<u8, _, _, _>(&mut client, &[0u8; 300][..], ())
+		.await
+		.unwrap_err();
+	assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
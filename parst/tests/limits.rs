@@ -0,0 +1,91 @@
+use parst::{
+	error::Error,
+	limits::{with_limits, Limits},
+	Parsable,
+};
+use std::collections::{BTreeMap, HashMap};
+
+// Nests through a sequence without a count, so that running out of depth happens inside one
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]", max_depth = "4")]
+struct Tree {
+	#[parst(assert_eq = "1")]
+	open: u8,
+	children: Vec<Tree>,
+}
+
+fn nested(depth: usize) -> Vec<u8> { vec![1; depth] }
+
+fn small<R>(f: impl FnOnce() -> R) -> R { with_limits(Limits::UNLIMITED.with_max_bytes(8), f) }
+
+#[test]
+fn sequences_without_count_keep_recursion_limit() {
+	// The innermost tree still tries to read a child of its own, which ends its children
+	let bytes = nested(4);
+	let (tree, rest) = Tree::read(&bytes[..], ()).unwrap();
+	assert!(rest.is_empty());
+	let mut depth = 1;
+	let mut inner = &tree;
+	while let Some(child) = inner.children.first() {
+		depth += 1;
+		inner = child;
+	}
+	assert_eq!(depth, 4);
+
+	assert_eq!(
+		Tree::read(&nested(5)[..], ()).unwrap_err().0,
+		Error::RecursionLimit
+	);
+	assert_eq!(
+		Tree::read(&nested(64)[..], ()).unwrap_err().0,
+		Error::RecursionLimit
+	);
+}
+
+#[test]
+fn recursion_limit_from_limits() {
+	let limits = Limits::UNLIMITED.with_max_depth(2);
+	let read = |depth| {
+		with_limits(limits, || {
+			Tree::read(&nested(depth)[..], ())
+				.map(|_| ())
+				.map_err(|(e, _)| e)
+		})
+	};
+	assert_eq!(read(2), Ok(()));
+	assert_eq!(read(3), Err(Error::RecursionLimit));
+}
+
+#[test]
+fn sequences_without_count_keep_limit_exceeded() {
+	let bytes = [0; 32];
+	let error = small(|| <Vec<Vec<u16>>>::read(&bytes[..], ())).unwrap_err();
+	assert_eq!(error.0, Error::LimitExceeded);
+}
+
+#[test]
+fn maps_keep_limit_exceeded() {
+	let bytes = [0; 32];
+	let error = small(|| <BTreeMap<u8, Vec<u16>>>::read(&bytes[..], ())).unwrap_err();
+	assert_eq!(error.0, Error::LimitExceeded);
+	let error = small(|| <HashMap<u8, Vec<u16>>>::read(&bytes[..], ())).unwrap_err();
+	assert_eq!(error.0, Error::LimitExceeded);
+}
+
+#[test]
+fn options_keep_limits() {
+	let bytes = [0; 32];
+	let error = small(|| <Option<Vec<u16>>>::read(&bytes[..], ())).unwrap_err();
+	assert_eq!(error.0, Error::LimitExceeded);
+	assert!(matches!(
+		<Option<Tree>>::read(&nested(4)[..], ()),
+		Ok((Some(_), []))
+	));
+	assert_eq!(
+		<Option<Tree>>::read(&nested(5)[..], ()).unwrap_err().0,
+		Error::RecursionLimit
+	);
+
+	// Other errors still leave the value out
+	assert_eq!(<Option<u16>>::read(&[1][..], ()), Ok((None, &[1][..])));
+}
@@ -0,0 +1,56 @@
+use parst::{owned::ToStatic, Deparsable, Parsable, ToStatic};
+use std::fmt::Debug;
+
+#[derive(Debug, Clone, PartialEq, Parsable, Deparsable, ToStatic)]
+#[parst(
+	src = "[u8]",
+	lifetime = "'a",
+	owned,
+	owned_derive = "Debug, PartialEq"
+)]
+struct Message<'a> {
+	kind: u8,
+	body: &'a [u8],
+}
+
+// Only written, with bounds in a where clause
+#[derive(Deparsable, ToStatic)]
+#[parst(owned = "Written")]
+struct Write<'a, T>
+where
+	T: Deparsable + Debug + 'a,
+{
+	value: T,
+	rest: &'a [u8],
+}
+
+#[test]
+fn owned_counterpart_reads_and_writes() {
+	let bytes = [2, 7, 8];
+	let (message, _) = Message::read(&bytes, ()).unwrap();
+	let owned = message.to_owned_copy();
+	assert_eq!(MessageOwned::read(&bytes, ()).unwrap().0, owned);
+
+	let mut written = Vec::new();
+	owned.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+
+	// The inherent conversion leaves `ToOwned` to `Clone`
+	let cloned: Message = message.to_owned();
+	assert_eq!(cloned, message);
+}
+
+#[test]
+fn owned_counterpart_of_deparsable_only() {
+	let write = Write {
+		value: 1u8,
+		rest: &[2, 3],
+	};
+	let owned: Written<u8> = write.to_static();
+	assert_eq!(owned.value, 1);
+	assert_eq!(*owned.rest, [2, 3]);
+
+	let mut written = Vec::new();
+	owned.write(&mut written, ()).unwrap();
+	assert_eq!(written, [1, 2, 3]);
+}
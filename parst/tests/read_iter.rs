@@ -0,0 +1,161 @@
+use parst::{
+	error::Error,
+	iter::{iter, read_iter, RecordError},
+	Deparsable, Parsable,
+};
+use std::io::{Cursor, Read};
+
+// Bytes taking up exactly the number the context gives
+#[derive(Debug, PartialEq)]
+struct Body(Vec<u8>);
+
+impl<'a> Parsable<'a, [u8], usize> for Body {
+	fn read(source: &'a [u8], len: usize) -> parst::PResultBytes<'a, Self> {
+		let (bytes, source) = source
+			.split_at_checked(len)
+			.ok_or((Error::NotEnoughBytes, source))?;
+		Ok((Body(bytes.to_vec()), source))
+	}
+}
+
+impl Deparsable<usize> for Body {
+	fn write(&self, w: &mut impl std::io::Write, _len: usize) -> std::io::Result<()> {
+		w.write_all(&self.0)
+	}
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+struct Record {
+	#[parst(matches = "1..")]
+	len: u8,
+	#[parst(ctx = "len as usize")]
+	body: Body,
+}
+
+fn records(bodies: &[&[u8]]) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	for body in bodies {
+		bytes.push(body.len() as u8);
+		bytes.extend_from_slice(body);
+	}
+	bytes
+}
+
+#[test]
+fn small_max_record() {
+	let bytes = records(&[&[1, 2, 3], &[4], &[5, 6]]);
+	let read = read_iter::<Record, _, _>(Cursor::new(bytes), ())
+		.with_max_record(4)
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(read.len(), 3);
+	assert_eq!(read[2].body, Body(vec![5, 6]));
+}
+
+#[test]
+fn record_longer_than_max_record() {
+	let bytes = records(&[&[1], &[2; 10]]);
+	let mut iter = read_iter::<Record, _, _>(Cursor::new(bytes), ()).with_max_record(4);
+	assert!(iter.next().unwrap().is_ok());
+	let error = iter.next().unwrap().unwrap_err();
+	let error = error.get_ref().unwrap().downcast_ref::<RecordError>();
+	assert_eq!(
+		error,
+		Some(&RecordError {
+			offset: 2,
+			error: Error::NotEnoughBytes
+		})
+	);
+	assert!(iter.next().is_none());
+}
+
+#[test]
+fn failed_record_offset() {
+	let mut bytes = records(&[&[1, 2], &[3]]);
+	bytes.extend([0, 9]);
+	let mut iter = read_iter::<Record, _, _>(Cursor::new(bytes), ());
+	assert!(iter.next().unwrap().is_ok());
+	assert!(iter.next().unwrap().is_ok());
+	let error = iter.next().unwrap().unwrap_err();
+	assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+	let error = error
+		.into_inner()
+		.unwrap()
+		.downcast::<RecordError>()
+		.unwrap();
+	assert_eq!(error.offset, 5);
+	assert!(matches!(error.error, Error::AssertionFailed(_)));
+}
+
+// Fails every read, so that reading past the first bytes shows
+struct Broken;
+
+impl Read for Broken {
+	fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+		Err(std::io::Error::other("read past the failed record"))
+	}
+}
+
+#[test]
+fn failed_record_not_refilled() {
+	let mut bytes = records(&[&[1]]);
+	bytes.extend([0, 9]);
+	let mut iter = read_iter::<Record, _, _>(Cursor::new(bytes).chain(Broken), ());
+	assert!(iter.next().unwrap().is_ok());
+	let error = iter.next().unwrap().unwrap_err();
+	let error = error.get_ref().unwrap().downcast_ref::<RecordError>();
+	assert!(matches!(
+		error,
+		Some(RecordError {
+			offset: 2,
+			error: Error::AssertionFailed(_)
+		})
+	));
+	assert!(iter.next().is_none());
+}
+
+#[test]
+fn slice_records() {
+	let bytes = records(&[&[1, 2], &[3], &[4, 5, 6]]);
+	let mut records = iter::<Record, _, _>(&bytes[..], ());
+	assert_eq!(
+		records.next(),
+		Some(Ok(Record {
+			len: 2,
+			body: Body(vec![1, 2])
+		}))
+	);
+	assert_eq!(records.offset(), 3);
+	assert_eq!(records.by_ref().count(), 2);
+	assert_eq!(records.offset(), bytes.len());
+	assert!(records.remainder().is_empty());
+	assert_eq!(records.next(), None);
+}
+
+#[test]
+fn slice_records_trailing_partial() {
+	let mut bytes = records(&[&[1, 2]]);
+	bytes.extend([3, 4]);
+	let mut records = iter::<Record, _, _>(&bytes[..], ());
+	assert!(records.next().unwrap().is_ok());
+	assert_eq!(records.next(), Some(Err(Error::NotEnoughBytes)));
+	assert_eq!(records.offset(), 3);
+	assert_eq!(records.remainder(), [3, 4]);
+	assert_eq!(records.next(), None);
+}
+
+#[test]
+fn slice_records_failed_mid_stream() {
+	let mut bytes = records(&[&[1]]);
+	bytes.extend([0, 9]);
+	bytes.extend(records(&[&[2]]));
+	let mut records = iter::<Record, _, _>(&bytes[..], ());
+	assert!(records.next().unwrap().is_ok());
+	assert!(matches!(
+		records.next(),
+		Some(Err(Error::AssertionFailed(_)))
+	));
+	assert_eq!(records.offset(), 2);
+	assert_eq!(records.next(), None);
+}
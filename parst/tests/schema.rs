@@ -0,0 +1,148 @@
+use parst::{
+	error::Error,
+	schema::{to_c_header, to_kaitai, to_markdown, Layout, Schema},
+	Parsable,
+};
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]", tag = "HD", validate = "check_header")]
+struct Header {
+	#[parst(assert_eq = "1")]
+	version: u8,
+	length: u16,
+	kind: Kind,
+	point: Point<u8>,
+	wide: Point<u16>,
+}
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]", dis = "u8")]
+enum Kind {
+	#[parst(dis = "1")]
+	Empty,
+	#[parst(dis = "2")]
+	Sized(u32),
+}
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]")]
+struct Point<T>
+where
+	T: for<'a> Parsable<'a, [u8]>,
+{
+	x: T,
+	y: T,
+}
+
+fn check_header(header: &Header) -> Result<(), Error> {
+	match header.length > 0 {
+		true => Ok(()),
+		false => Err(Error::InvalidInput),
+	}
+}
+
+// Reads without describing itself
+#[derive(Debug, PartialEq)]
+struct Opaque;
+
+impl<'a> Parsable<'a, [u8]> for Opaque {
+	fn read(source: &'a [u8], _context: ()) -> parst::PResult<'a, Self, [u8]> {
+		Ok((Opaque, source))
+	}
+}
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]")]
+struct Holder {
+	point: Point<Opaque>,
+}
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]", validate = "check_choice")]
+enum Choice {
+	A(u8),
+	B(u16),
+}
+
+fn check_choice(_choice: &Choice) -> Result<(), Error> { Ok(()) }
+
+#[test]
+fn generic_types_named_by_arguments() {
+	let schema = Header::schema();
+	let names = schema
+		.named_types()
+		.iter()
+		.map(|ty| ty.name.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(names, ["Kind", "Point<u8>", "Point<u16>", "Header"]);
+	assert_eq!(Point::<u16>::schema().size, Some(4));
+}
+
+#[test]
+fn generic_fields_without_schema_opaque() {
+	let schema = Holder::schema();
+	let Layout::Struct(s) = &schema.layout else {
+		panic!("not a struct");
+	};
+	assert_eq!(s.fields[0].ty.layout, Layout::Opaque);
+	assert_eq!(s.fields[0].ty.name, "Point<Opaque>");
+}
+
+#[test]
+fn validate_recorded_as_asserts() {
+	let Layout::Struct(s) = Header::schema().layout else {
+		panic!("not a struct");
+	};
+	assert_eq!(s.asserts, ["check_header(&self)"]);
+
+	let Layout::Enum(e) = Choice::schema().layout else {
+		panic!("not an enum");
+	};
+	assert_eq!(e.asserts, ["check_choice(&self)"]);
+	assert!(to_markdown(&Choice::schema()).contains("- Asserts `check_choice(&self)`"));
+}
+
+#[test]
+fn kaitai_export() {
+	let ksy = to_kaitai(&Header::schema());
+	assert!(ksy.starts_with("meta:\n  id: header\nseq:\n  - contents: [0x48, 0x44]\n"));
+	assert!(ksy.contains("  - id: version\n    type: u1\n    valid: 1\n"));
+	assert!(ksy.contains("  - id: length\n    type: u2le\n"));
+	assert!(ksy.contains("        type:\n          switch-on: discriminant\n"));
+	assert!(ksy.contains("            1: kind_empty\n            2: kind_sized\n"));
+	assert!(ksy.contains("    type: point_u8\n"));
+	assert!(ksy.contains("  point_u16:\n    seq:\n      - id: x\n        type: u2le\n"));
+}
+
+#[test]
+fn markdown_export() {
+	let markdown = to_markdown(&Header::schema());
+	assert!(markdown.starts_with("## Header\n\nSize: variable\n\nStarts with `HD`\n"));
+	assert!(markdown.contains("| 0 | version | `u8` | 1 | must be `== 1` |\n"));
+	assert!(markdown.contains("| 3 | kind | `Kind` | variable |  |\n"));
+	assert!(markdown.contains("| variable | wide | `Point<u16>` | 4 |  |\n"));
+	assert!(markdown.contains("## Point<u8>\n\nSize: 2\n"));
+	assert!(markdown.contains("| `1` | Empty |\n| `2` | Sized |\n"));
+}
+
+#[test]
+fn c_export() {
+	let header = to_c_header(&Point::<u16>::schema());
+	assert!(header.starts_with("#ifndef POINT_U16_H\n#define POINT_U16_H\n"));
+	assert!(header.contains("struct point_u16 {\n\tuint16_t x;\n\tuint16_t y;\n};\n"));
+
+	let header = to_c_header(&Header::schema());
+	assert!(header.contains("\tunion {\n\t\tstruct Kind_Sized Sized;\n\t} body;\n"));
+	assert!(header.contains("\tchar tag_0[2]; /* \"HD\" */\n\tuint8_t version;\n"));
+	assert!(header.contains("\t/* kind: struct Kind, whose size varies */\n"));
+	assert!(header.ends_with("#pragma pack(pop)\n\n#endif\n"));
+}
+
+#[test]
+fn type_names_without_paths() {
+	assert_eq!(parst::schema::type_name::<Vec<u8>>(), "Vec<u8>");
+	assert_eq!(
+		parst::schema::type_name::<Option<(String, &[u16])>>(),
+		"Option<(String, &[u16])>"
+	);
+}
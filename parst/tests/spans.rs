@@ -0,0 +1,53 @@
+use parst::{
+	spans::{read_spans, HasSpans},
+	Parsable,
+};
+
+#[derive(Debug, Default, PartialEq, Parsable)]
+#[parst(src = "[u8]", dis = "u8", spans)]
+enum Shape {
+	#[default]
+	#[parst(dis_pat = "0")]
+	Point,
+	#[parst(dis_pat = "1")]
+	Line { len: u8 },
+}
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]", spans)]
+struct Drawing {
+	#[parst(spans)]
+	first: Shape,
+	#[parst(skip, spans)]
+	second: Shape,
+}
+
+#[test]
+fn enum_spans() {
+	let bytes = [1, 5];
+	let (shape, spans) = read_spans::<Shape, _, _>(&bytes[..], ()).unwrap().0;
+	assert_eq!(shape, Shape::Line { len: 5 });
+	assert_eq!(
+		spans,
+		ShapeSpans::Line {
+			span: 0..2,
+			len: 1..2
+		}
+	);
+}
+
+#[test]
+fn skipped_enum_field_has_empty_spans() {
+	let bytes = [1, 5];
+	let (drawing, spans) = read_spans::<Drawing, _, _>(&bytes[..], ()).unwrap().0;
+	assert_eq!(drawing.second, Shape::Point);
+	assert_eq!(
+		spans.first,
+		ShapeSpans::Line {
+			span: 0..2,
+			len: 1..2
+		}
+	);
+	assert_eq!(spans.second, <Shape as HasSpans>::Spans::default());
+	assert_eq!(spans.second, ShapeSpans::Point { span: 0..0 });
+}
@@ -0,0 +1,134 @@
+use parst::{error::Error, Deparsable, Parsable};
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt::Debug,
+	net::{Ipv4Addr, Ipv6Addr},
+	num::{NonZeroI32, NonZeroU16, NonZeroU8},
+	ops::{Range, RangeInclusive},
+	time::Duration,
+};
+
+#[track_caller]
+fn roundtrip<T>(value: T, bytes: &[u8])
+where
+	T: for<'a> Parsable<'a, [u8]> + Deparsable + PartialEq + Debug,
+{
+	let mut written = Vec::new();
+	value.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+	assert_eq!(T::read(bytes, ()), Ok((value, &[][..])));
+}
+
+#[track_caller]
+fn rejected<T>(bytes: &[u8])
+where
+	T: for<'a> Parsable<'a, [u8]> + Debug,
+{
+	assert_eq!(
+		T::read(bytes, ()).unwrap_err(),
+		(Error::InvalidInput, bytes)
+	);
+}
+
+#[test]
+fn bool_only_zero_or_one() {
+	roundtrip(false, &[0]);
+	roundtrip(true, &[1]);
+	rejected::<bool>(&[2]);
+	rejected::<bool>(&[0xff]);
+}
+
+#[test]
+fn non_zero_rejects_zero() {
+	roundtrip(NonZeroU8::new(7).unwrap(), &[7]);
+	roundtrip(NonZeroU16::new(0x0102).unwrap(), &0x0102u16.to_ne_bytes());
+	roundtrip(NonZeroI32::new(-1).unwrap(), &[0xff; 4]);
+	rejected::<NonZeroU8>(&[0]);
+	rejected::<NonZeroU16>(&[0, 0]);
+	rejected::<NonZeroI32>(&[0; 4]);
+}
+
+#[test]
+fn char_must_be_a_scalar_value() {
+	roundtrip('a', &('a' as u32).to_ne_bytes());
+	roundtrip('\u{10ffff}', &0x10ffffu32.to_ne_bytes());
+	// Surrogates and anything past the last code point
+	rejected::<char>(&0xd800u32.to_ne_bytes());
+	rejected::<char>(&0x110000u32.to_ne_bytes());
+}
+
+#[test]
+fn maps_reject_repeated_keys() {
+	let map = BTreeMap::from([(1u8, 10u8), (2, 20)]);
+	roundtrip(map, &[1, 10, 2, 20]);
+
+	let repeated = [1, 10, 1, 11];
+	assert_eq!(
+		BTreeMap::<u8, u8>::read(&repeated, ()).unwrap_err(),
+		(Error::InvalidInput, &repeated[2..])
+	);
+	assert_eq!(
+		HashMap::<u8, u8>::read(&repeated, ()).unwrap_err(),
+		(Error::InvalidInput, &repeated[2..])
+	);
+}
+
+#[test]
+fn btree_maps_reject_keys_out_of_order() {
+	let unsorted = [2, 20, 1, 10];
+	assert_eq!(
+		BTreeMap::<u8, u8>::read(&unsorted, ()).unwrap_err(),
+		(Error::InvalidInput, &unsorted[2..])
+	);
+}
+
+#[test]
+fn hash_maps_keep_their_entries() {
+	let map = (0..16u8)
+		.map(|key| (key, key * 3))
+		.collect::<HashMap<_, _>>();
+	let mut bytes = Vec::new();
+	map.write(&mut bytes, ()).unwrap();
+	assert_eq!(bytes.len(), 32);
+	let (read, rest) = HashMap::<u8, u8>::read(&bytes, ()).unwrap();
+	assert_eq!(read, map);
+	assert!(rest.is_empty());
+
+	// Any order of the entries reads as the same map
+	let reversed = bytes.chunks(2).rev().flatten().copied().collect::<Vec<_>>();
+	assert_eq!(HashMap::<u8, u8>::read(&reversed, ()).unwrap().0, map);
+}
+
+#[test]
+fn addresses_in_network_order() {
+	roundtrip(Ipv4Addr::new(192, 168, 0, 1), &[192, 168, 0, 1]);
+	let address = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+	roundtrip(address, &address.octets());
+	assert_eq!(
+		Ipv4Addr::read(&[1, 2, 3], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
+
+#[test]
+fn duration_nanoseconds_under_a_second() {
+	let mut bytes = 5u64.to_ne_bytes().to_vec();
+	bytes.extend(999_999_999u32.to_ne_bytes());
+	roundtrip(Duration::new(5, 999_999_999), &bytes);
+
+	let mut bytes = 5u64.to_ne_bytes().to_vec();
+	bytes.extend(1_000_000_000u32.to_ne_bytes());
+	rejected::<Duration>(&bytes);
+}
+
+#[test]
+fn ranges_as_their_bounds() {
+	roundtrip(Range { start: 1u8, end: 4 }, &[1, 4]);
+	roundtrip(RangeInclusive::new(2u8, 3), &[2, 3]);
+	// Empty ranges are values like any other
+	roundtrip(Range { start: 5u8, end: 2 }, &[5, 2]);
+	assert_eq!(
+		Range::<u8>::read(&[1], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
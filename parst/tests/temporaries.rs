@@ -0,0 +1,65 @@
+use parst::{error::Error, Deparsable, Parsable};
+
+// Bytes taking up exactly the number the context gives
+#[derive(Debug, PartialEq)]
+struct Bytes(Vec<u8>);
+
+impl<'a> Parsable<'a, [u8], usize> for Bytes {
+	fn read(source: &'a [u8], len: usize) -> parst::PResultBytes<'a, Self> {
+		let (bytes, source) = source
+			.split_at_checked(len)
+			.ok_or((Error::NotEnoughBytes, source))?;
+		Ok((Bytes(bytes.to_vec()), source))
+	}
+}
+
+impl Deparsable<usize> for Bytes {
+	fn write(&self, w: &mut impl std::io::Write, len: usize) -> std::io::Result<()> {
+		if self.0.len() != len {
+			return Err(Error::InvalidInput.into());
+		}
+		w.write_all(&self.0)
+	}
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+struct Packet {
+	width: u8,
+	#[parst(temp = "len: u8", calc = "body.0.len() as u8")]
+	#[parst(ctx = "len as usize")]
+	body: Bytes,
+	#[parst(
+		temp = "padding: Bytes",
+		calc = "Bytes(vec![0; *width as usize])",
+		temp_ctx = "width as usize"
+	)]
+	end: u8,
+}
+
+#[test]
+fn temporaries_roundtrip() {
+	let bytes = [2, 3, 7, 8, 9, 0, 0, 0xff];
+	let (packet, rest) = Packet::read(&bytes, ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(
+		packet,
+		Packet {
+			width: 2,
+			body: Bytes(vec![7, 8, 9]),
+			end: 0xff
+		}
+	);
+
+	let mut written = Vec::new();
+	packet.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+}
+
+#[test]
+fn temporary_context_limits_read() {
+	assert_eq!(
+		Packet::read(&[4, 0, 0, 0], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
@@ -0,0 +1,104 @@
+use parst::{element::Any, error::Error, pod::FixedSize, Deparsable, Parsable};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+	LParen,
+	RParen,
+	Comma,
+	Minus,
+	Num(u8),
+}
+
+// Tokens are written back as the text they were lexed from
+impl Deparsable for Token {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		match self {
+			Token::LParen => w.write_all(b"("),
+			Token::RParen => w.write_all(b")"),
+			Token::Comma => w.write_all(b","),
+			Token::Minus => w.write_all(b"-"),
+			Token::Num(n) => write!(w, "{}", n),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[Token]")]
+enum Value {
+	#[parst(tag_elements = "[Token::Minus]")]
+	Negative(Any<Token>),
+	Positive(Any<Token>),
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[Token]", tag_elements = "[Token::LParen]")]
+struct Pair {
+	first: Value,
+	#[parst(tag_elements = "[Token::Comma]")]
+	second: Value,
+	#[parst(tag_elements = "[Token::RParen]")]
+	end: (),
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", tag = "ID", fixed_size)]
+struct Id(#[parst(tag = ":")] u16);
+
+#[test]
+fn element_tags_read_and_write() {
+	let tokens = [
+		Token::LParen,
+		Token::Minus,
+		Token::Num(1),
+		Token::Comma,
+		Token::Num(2),
+		Token::RParen,
+	];
+	let (pair, rest) = Pair::read(&tokens, ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(
+		pair,
+		Pair {
+			first: Value::Negative(Any(Token::Num(1))),
+			second: Value::Positive(Any(Token::Num(2))),
+			end: (),
+		}
+	);
+
+	let mut written = Vec::new();
+	pair.write(&mut written, ()).unwrap();
+	assert_eq!(written, b"(-1,2)");
+}
+
+#[test]
+fn element_tags_rejected_or_cut_short() {
+	let tokens = [Token::RParen];
+	assert_eq!(
+		Pair::read(&tokens, ()).unwrap_err(),
+		(Error::InvalidInput, &tokens[..])
+	);
+	let tokens = [Token::LParen, Token::Num(1), Token::Comma, Token::Num(2)];
+	assert_eq!(
+		Pair::read(&tokens, ()).unwrap_err(),
+		(Error::NotEnoughBytes, &[][..])
+	);
+}
+
+#[test]
+fn text_tags_count_towards_fixed_size() {
+	assert_eq!(Id::SIZE, 5);
+	assert_eq!(
+		Id::read(b"ID:\x01\x00", ()),
+		Ok((Id(u16::from_ne_bytes([1, 0])), &[][..]))
+	);
+}
+
+#[test]
+fn text_tags_rejected_or_cut_short() {
+	assert_eq!(Id::read(b"I", ()), Err((Error::NotEnoughBytes, &b"I"[..])));
+	assert_eq!(Id::read(b"ID", ()), Err((Error::NotEnoughBytes, &[][..])));
+	assert_eq!(
+		Id::read(b"IX:", ()),
+		Err((Error::Expected("ID"), &b"IX:"[..]))
+	);
+}
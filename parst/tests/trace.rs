@@ -0,0 +1,92 @@
+#![cfg(feature = "trace")]
+
+use parst::{
+	spans::read_spans,
+	trace::{with_sink, Event, Hexdump, Sink},
+	PResult, Parsable,
+};
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]", spans)]
+struct Point {
+	x: u8,
+	y: u8,
+}
+
+// A source which is not an `iter::Source`
+#[derive(Debug)]
+struct Tape;
+
+// Reads nothing out of any source
+#[derive(Debug, PartialEq)]
+struct Mark;
+
+impl<'a, Src: ?Sized> Parsable<'a, Src> for Mark {
+	fn read(source: &'a Src, _context: ()) -> PResult<'a, Self, Src> { Ok((Mark, source)) }
+}
+
+// Tracing puts no bound on a generic source
+#[derive(Debug, PartialEq, Parsable)]
+struct Marks {
+	first: Mark,
+	second: Mark,
+}
+
+// The names of the fields traced
+#[derive(Default)]
+struct Fields(Vec<&'static str>);
+
+impl Sink for Fields {
+	fn event(&mut self, event: &Event<'_>) {
+		if let Event::Field { name, .. } = event {
+			self.0.push(name);
+		}
+	}
+}
+
+// Parses while handling each type it enters, raising events of its own
+struct Reentrant(usize);
+
+impl Sink for Reentrant {
+	fn event(&mut self, event: &Event<'_>) {
+		if let Event::Enter { .. } = event {
+			self.0 += 1;
+			Point::read(&[3, 4], ()).unwrap();
+		}
+	}
+}
+
+#[test]
+fn fields_traced_with_and_without_spans() {
+	let bytes = [1, 2];
+	let (_, fields) = with_sink(Fields::default(), || Point::read(&bytes, ()).unwrap());
+	assert_eq!(fields.0, ["x", "y"]);
+
+	let (_, fields) = with_sink(Fields::default(), || {
+		read_spans::<Point, _, _>(&bytes[..], ()).unwrap()
+	});
+	assert_eq!(fields.0, ["x", "y"]);
+}
+
+#[test]
+fn generic_sources_traced() {
+	let (read, fields) = with_sink(Fields::default(), || Marks::read(&Tape, ()).unwrap());
+	assert_eq!(read.0.second, Mark);
+	assert_eq!(fields.0, ["first", "second"]);
+}
+
+#[test]
+fn events_raised_by_the_sink_are_dropped() {
+	let (point, sink) = with_sink(Reentrant(0), || Point::read(&[1, 2], ()).unwrap());
+	assert_eq!(point.0, Point { x: 1, y: 2 });
+	assert_eq!(sink.0, 1);
+}
+
+#[test]
+fn render_clamps_to_shorter_input() {
+	let bytes = [1, 2];
+	let (_, hexdump) = with_sink(Hexdump::default(), || Point::read(&bytes, ()).unwrap());
+	assert!(hexdump.render(&bytes).contains("x: 1"));
+	assert!(hexdump.render(&bytes[..1]).contains("y: 2"));
+	assert!(hexdump.render(&[]).contains("Point"));
+}
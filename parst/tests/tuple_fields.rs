@@ -0,0 +1,47 @@
+use parst::{error::Error, Deparsable, Parsable};
+
+// Bytes taking up exactly the number the context ends with
+#[derive(Debug, PartialEq)]
+struct Run(Vec<u8>);
+
+impl<'a, Ctx> Parsable<'a, [u8], (Ctx, u8)> for Run {
+	fn read(source: &'a [u8], (_, len): (Ctx, u8)) -> parst::PResultBytes<'a, Self> {
+		let (bytes, source) = source
+			.split_at_checked(len as usize)
+			.ok_or((Error::NotEnoughBytes, source))?;
+		Ok((Run(bytes.to_vec()), source))
+	}
+}
+
+impl<Ctx> Deparsable<(Ctx, u8)> for Run {
+	fn write(&self, w: &mut impl std::io::Write, (_, len): (Ctx, u8)) -> std::io::Result<()> {
+		if self.0.len() != len as usize {
+			return Err(Error::InvalidInput.into());
+		}
+		w.write_all(&self.0)
+	}
+}
+
+// Fields of tuple structs are `_0`, `_1`, ..., and still answer to `__field_0`, `__field_1`, ...
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", lifetime = "'a")]
+struct Lengths<'a>(
+	u8,
+	#[parst(ctx = "_0 as usize")] &'a [u8],
+	u8,
+	#[parst(ctx = "__field_2 as usize")] &'a [u8],
+	u8,
+	#[parst(with_field_context = "__field_4")] Run,
+);
+
+#[test]
+fn tuple_fields_as_context() {
+	let bytes = [2, 7, 8, 1, 9, 3, 4, 5, 6];
+	let (lengths, rest) = Lengths::read(&bytes, ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(lengths, Lengths(2, &[7, 8], 1, &[9], 3, Run(vec![4, 5, 6])));
+
+	let mut written = Vec::new();
+	lengths.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+}
@@ -0,0 +1,79 @@
+use parst::{error::Error, Deparsable, Parsable};
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", dis = "u8")]
+enum Message {
+	#[parst(dis = "1", assert = "x > &5")]
+	A { x: u8 },
+	#[parst(dis = "2")]
+	B(u16),
+	#[parst(dis_pat = "3..", dis = "3")]
+	Other,
+}
+
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]", assert = "start <= end")]
+struct Range {
+	start: u8,
+	end: u8,
+}
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]")]
+struct Header {
+	#[parst(matches = "1..=4")]
+	version: u8,
+	#[parst(assert_eq = "version.pow(2)")]
+	square: u8,
+	#[parst(assert_ne = "-(version as i8)")]
+	negated: i8,
+}
+
+#[test]
+fn variant_assert_failure_reported() {
+	assert_eq!(
+		Message::read(&[1, 3], ()),
+		Err((Error::AssertionFailed("x > &5"), &[][..]))
+	);
+	assert_eq!(
+		Message::read(&[1, 9], ()),
+		Ok((Message::A { x: 9 }, &[][..]))
+	);
+}
+
+#[test]
+fn selected_variant_not_enough_bytes() {
+	assert_eq!(
+		Message::read(&[2, 0], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(Message::read(&[3], ()), Ok((Message::Other, &[][..])));
+}
+
+#[test]
+fn struct_assert() {
+	assert!(Range::read(&[1, 2], ()).is_ok());
+	assert_eq!(
+		Range::read(&[2, 1], ()).unwrap_err().0,
+		Error::AssertionFailed("start <= end")
+	);
+
+	let mut bytes = Vec::new();
+	assert!(Range { start: 3, end: 1 }.write(&mut bytes, ()).is_err());
+	assert!(bytes.is_empty());
+}
+
+#[test]
+fn field_assert_messages_as_written() {
+	assert!(Header::read(&[3, 9, 0], ()).is_ok());
+	let message = |bytes: &[u8]| match Header::read(bytes, ()) {
+		Err((Error::AssertionFailed(message), _)) => message,
+		result => panic!("{:?}", result),
+	};
+	assert_eq!(message(&[5, 25, 0]), "version does not match 1..=4");
+	assert_eq!(message(&[3, 6, 0]), "square is not equal to version.pow(2)");
+	assert_eq!(
+		message(&[3, 9, 0xfd]),
+		"negated is equal to -(version as i8)"
+	);
+}
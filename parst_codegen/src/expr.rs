@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+// The earlier integer fields of a type by their Kaitai name, to the Rust expression for their value
+// as an `i64`
+pub type Scope = HashMap<String, String>;
+
+enum Token {
+	Int(i128),
+	Ident(String),
+	Op(String),
+	Other(String),
+}
+
+fn tokenize(expr: &str) -> Vec<Token> {
+	const OPS: [&str; 21] = [
+		"<<", ">>", "<=", ">=", "==", "!=", "::", "+", "-", "*", "/", "%", "&", "|", "^", "<", ">",
+		"(", ")", "?", ".",
+	];
+
+	let mut tokens = Vec::new();
+	let mut rest = expr.trim_start();
+	while let Some(c) = rest.chars().next() {
+		let len = if c.is_ascii_digit() {
+			let len = rest
+				.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+				.unwrap_or(rest.len());
+			let text = rest[..len].replace('_', "");
+			let value = match text.get(..2) {
+				Some("0x") => i128::from_str_radix(&text[2..], 16),
+				Some("0b") => i128::from_str_radix(&text[2..], 2),
+				Some("0o") => i128::from_str_radix(&text[2..], 8),
+				_ => text.parse(),
+			};
+			tokens.push(match value {
+				Ok(value) => Token::Int(value),
+				Err(_) => Token::Other(rest[..len].to_string()),
+			});
+			len
+		} else if c.is_ascii_alphabetic() || c == '_' {
+			let len = rest
+				.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+				.unwrap_or(rest.len());
+			tokens.push(Token::Ident(rest[..len].to_string()));
+			len
+		} else if let Some(op) = OPS.iter().find(|op| rest.starts_with(*op)) {
+			tokens.push(Token::Op(op.to_string()));
+			op.len()
+		} else {
+			tokens.push(Token::Other(c.to_string()));
+			c.len_utf8()
+		};
+		rest = rest[len..].trim_start();
+	}
+	tokens
+}
+
+// Translates a Kaitai expression over integers and the earlier fields into Rust, or describes the
+// part of it which can not be
+pub fn translate(expr: &str, scope: &Scope) -> Result<String, String> {
+	let mut out = String::new();
+	// Whether the next token starts an operand, where `-` is negation
+	let mut operand = true;
+	for token in tokenize(expr) {
+		match token {
+			Token::Int(value) => {
+				out.push_str(&value.to_string());
+				operand = false;
+			}
+			Token::Ident(name) => match name.as_str() {
+				"and" => {
+					out.push_str(" && ");
+					operand = true;
+				}
+				"or" => {
+					out.push_str(" || ");
+					operand = true;
+				}
+				"not" => out.push('!'),
+				"true" | "false" => {
+					out.push_str(&name);
+					operand = false;
+				}
+				_ => match scope.get(&name) {
+					Some(value) => {
+						out.push_str(value);
+						operand = false;
+					}
+					None => {
+						return Err(format!(
+							"`{}` refers to `{}`, which is not an earlier integer field",
+							expr, name
+						))
+					}
+				},
+			},
+			Token::Op(op) => match op.as_str() {
+				"(" => {
+					out.push('(');
+					operand = true;
+				}
+				")" => {
+					out.push(')');
+					operand = false;
+				}
+				"-" if operand => out.push('-'),
+				"?" | "." | "::" => return Err(format!("`{}` in `{}`", op, expr)),
+				_ => {
+					out.push(' ');
+					out.push_str(&op);
+					out.push(' ');
+					operand = true;
+				}
+			},
+			Token::Other(other) => return Err(format!("`{}` in `{}`", other, expr)),
+		}
+	}
+	Ok(out)
+}
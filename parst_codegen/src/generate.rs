@@ -0,0 +1,717 @@
+use crate::{
+	expr::{translate, Scope},
+	Error, Unsupported,
+};
+use std::{collections::HashMap, fmt::Write};
+use yaml_rust2::{yaml::Hash, Yaml, YamlLoader};
+
+enum Ty {
+	// A number, written as its Rust type
+	Prim(String),
+	Bytes,
+	ByteArray(usize),
+	CStr,
+	User(String),
+	Array(Box<Ty>, usize),
+	Vec(Box<Ty>),
+	Counted(Box<Ty>),
+	Limited(Box<Ty>),
+	Cond(Box<Ty>),
+}
+
+struct Field {
+	name: String,
+	docs: Vec<String>,
+	ty: Ty,
+	// The contents of its `#[parst(...)]` attribute
+	attrs: Vec<String>,
+}
+
+struct Variant {
+	name: String,
+	// The `dis` or `dis_pat` attribute
+	dis: String,
+	ty: Ty,
+}
+
+enum Item {
+	Struct {
+		name: String,
+		docs: Vec<String>,
+		fields: Vec<Field>,
+	},
+	Enum {
+		name: String,
+		variants: Vec<Variant>,
+	},
+}
+
+impl Item {
+	fn name(&self) -> &str {
+		match self {
+			Item::Struct { name, .. } | Item::Enum { name, .. } => name,
+		}
+	}
+
+	fn types(&self) -> Vec<&Ty> {
+		match self {
+			Item::Struct { fields, .. } => fields.iter().map(|f| &f.ty).collect(),
+			Item::Enum { variants, .. } => variants.iter().map(|v| &v.ty).collect(),
+		}
+	}
+}
+
+#[derive(Default)]
+struct Generator {
+	endian: Option<&'static str>,
+	// Kaitai type names to Rust ones
+	names: HashMap<String, String>,
+	items: Vec<Item>,
+	unsupported: Vec<Unsupported>,
+}
+
+const RUST_KEYWORDS: [&str; 38] = [
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+	"false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+	"ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+	"unsafe", "use", "where", "while",
+];
+
+fn ident(name: &str) -> String {
+	match RUST_KEYWORDS.contains(&name) {
+		true => format!("r#{}", name),
+		false => name.to_string(),
+	}
+}
+
+fn camel_case(name: &str) -> String {
+	name.split('_')
+		.filter(|part| !part.is_empty())
+		.map(|part| {
+			let mut chars = part.chars();
+			chars.next().map_or(String::new(), |first| {
+				first.to_ascii_uppercase().to_string() + chars.as_str()
+			})
+		})
+		.collect()
+}
+
+fn as_expr(yaml: &Yaml) -> Option<String> {
+	match yaml {
+		Yaml::Integer(i) => Some(i.to_string()),
+		Yaml::String(s) | Yaml::Real(s) => Some(s.clone()),
+		Yaml::Boolean(b) => Some(b.to_string()),
+		_ => None,
+	}
+}
+
+fn key_str(key: &Yaml) -> String { as_expr(key).unwrap_or_else(|| format!("{:?}", key)) }
+
+fn docs(spec: &Hash) -> Vec<String> {
+	["doc", "doc-ref"]
+		.iter()
+		.filter_map(|key| spec.get(&Yaml::String(key.to_string())))
+		.filter_map(as_expr)
+		.flat_map(|doc| doc.lines().map(str::to_string).collect::<Vec<_>>())
+		.collect()
+}
+
+fn get<'y>(spec: &'y Hash, key: &str) -> Option<&'y Yaml> {
+	spec.get(&Yaml::String(key.to_string()))
+}
+
+impl Generator {
+	fn unsupported(&mut self, path: &str, construct: impl Into<String>) {
+		self.unsupported.push(Unsupported {
+			path: root(path),
+			construct: construct.into(),
+		});
+	}
+
+	fn check_keys(&mut self, path: &str, spec: &Hash, known: &[&str]) {
+		for key in spec.keys() {
+			let key = key_str(key);
+			if !known.contains(&key.as_str()) {
+				self.unsupported(path, format!("`{}`", key));
+			}
+		}
+	}
+
+	// Gives every type a Rust name before any are generated, so that they can refer to each other
+	fn collect_names(&mut self, path: &str, spec: &Hash) -> Result<(), Error> {
+		if let Some(types) = get(spec, "types") {
+			let types = types
+				.as_hash()
+				.ok_or_else(|| invalid(path, "`types` must be a map"))?;
+			for (name, ty) in types {
+				let name = key_str(name);
+				let path = format!("{}/types/{}", path, name);
+				if self.names.insert(name.clone(), camel_case(&name)).is_some() {
+					self.unsupported(&path, "a second type with the same name");
+				}
+				let ty = ty
+					.as_hash()
+					.ok_or_else(|| invalid(&path, "a type must be a map"))?;
+				self.collect_names(&path, ty)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn type_spec(&mut self, path: &str, name: String, spec: &Hash) -> Result<(), Error> {
+		self.check_keys(
+			path,
+			spec,
+			&["meta", "seq", "types", "doc", "doc-ref", "enums"],
+		);
+
+		let mut fields = Vec::new();
+		let mut scope = Scope::new();
+		if let Some(seq) = get(spec, "seq") {
+			let seq = seq
+				.as_vec()
+				.ok_or_else(|| invalid(path, "`seq` must be a list"))?;
+			for (index, attr) in seq.iter().enumerate() {
+				let attr = attr
+					.as_hash()
+					.ok_or_else(|| invalid(path, "each entry of `seq` must be a map"))?;
+				let id = get(attr, "id")
+					.and_then(as_expr)
+					.unwrap_or_else(|| format!("unnamed_{}", index));
+				let path = format!("{}/seq/{}", path, id);
+				let field = self.attribute(&path, &name, &id, attr, &scope)?;
+				if let Ty::Prim(prim) = &field.ty {
+					let value = match prim.contains('<') {
+						true => format!("({}.0 as i64)", ident(&id)),
+						false => format!("({} as i64)", ident(&id)),
+					};
+					if !prim.contains('f') {
+						scope.insert(id.clone(), value);
+					}
+				}
+				fields.push(field);
+			}
+		}
+
+		self.items.push(Item::Struct {
+			name,
+			docs: docs(spec),
+			fields,
+		});
+
+		if let Some(types) = get(spec, "types").and_then(Yaml::as_hash) {
+			for (name, ty) in types {
+				let name = key_str(name);
+				let path = format!("{}/types/{}", path, name);
+				if let Some(ty) = ty.as_hash() {
+					let rust_name = camel_case(&name);
+					self.type_spec(&path, rust_name, ty)?;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn attribute(
+		&mut self,
+		path: &str,
+		parent: &str,
+		id: &str,
+		attr: &Hash,
+		scope: &Scope,
+	) -> Result<Field, Error> {
+		self.check_keys(
+			path,
+			attr,
+			&[
+				"id",
+				"type",
+				"size",
+				"size-eos",
+				"repeat",
+				"repeat-expr",
+				"if",
+				"contents",
+				"enum",
+				"encoding",
+				"terminator",
+				"doc",
+				"doc-ref",
+				"valid",
+			],
+		);
+
+		let mut field_docs = docs(attr);
+		let mut context = None;
+		let mut attrs = Vec::new();
+		let expr = |this: &mut Self, key: &str, yaml: &Yaml| -> Option<String> {
+			let text = as_expr(yaml)?;
+			match translate(&text, scope) {
+				Ok(rust) => Some(rust),
+				Err(message) => {
+					this.unsupported(&format!("{}/{}", path, key), message);
+					None
+				}
+			}
+		};
+
+		let size = get(attr, "size");
+		let size_eos = get(attr, "size-eos").and_then(Yaml::as_bool) == Some(true);
+		let ty = get(attr, "type");
+		let encoding = get(attr, "encoding").and_then(as_expr);
+
+		let mut ty = if let Some(contents) = get(attr, "contents") {
+			let bytes = contents_bytes(contents).ok_or_else(|| {
+				invalid(
+					path,
+					"`contents` must be a string or a list of bytes and strings",
+				)
+			})?;
+			let list = bytes
+				.iter()
+				.map(|b| format!("0x{:02x}", b))
+				.collect::<Vec<_>>();
+			attrs.push(format!("assert_eq = \"[{}]\"", list.join(", ")));
+			Ty::ByteArray(bytes.len())
+		} else {
+			match ty {
+				None | Some(Yaml::String(_)) => {
+					let ty = ty.and_then(Yaml::as_str);
+					match (ty, size, size_eos) {
+						(Some("strz"), None, false) => self.cstr(path, &encoding),
+						(Some("str"), None, false) => {
+							match get(attr, "terminator").and_then(Yaml::as_i64) {
+								Some(0) => self.cstr(path, &encoding),
+								_ => {
+									self.unsupported(path, "a string without a size or terminator");
+									Ty::Bytes
+								}
+							}
+						}
+						(None | Some("str") | Some("strz"), Some(Yaml::Integer(n)), _) => {
+							if let Some(encoding) = &encoding {
+								field_docs.push(format!("Text in {}", encoding));
+							}
+							Ty::ByteArray(*n as usize)
+						}
+						(None | Some("str") | Some("strz"), Some(size), _) => {
+							if let Some(encoding) = &encoding {
+								field_docs.push(format!("Text in {}", encoding));
+							}
+							context = expr(self, "size", size).map(as_usize);
+							Ty::Bytes
+						}
+						(None | Some("str") | Some("strz"), None, true) => Ty::Bytes,
+						(None, None, false) => {
+							return Err(invalid(path, "needs a `type`, `size` or `contents`"))
+						}
+						(Some(name), size, size_eos) => {
+							let base = self.named_type(path, name)?;
+							match (size, size_eos) {
+								(Some(size), _) => {
+									context = expr(self, "size", size).map(as_usize);
+									Ty::Limited(Box::new(base))
+								}
+								(None, true) => {
+									self.unsupported(path, "`size-eos` on a type");
+									base
+								}
+								(None, false) => base,
+							}
+						}
+					}
+				}
+				Some(Yaml::Hash(switch)) => {
+					let enum_name = format!("{}{}", parent, camel_case(id));
+					let on = get(switch, "switch-on").ok_or_else(|| {
+						invalid(path, "a `type` map must have `switch-on` and `cases`")
+					})?;
+					context = expr(self, "type/switch-on", on);
+					self.switch(path, enum_name.clone(), switch)?;
+					Ty::User(enum_name)
+				}
+				Some(_) => return Err(invalid(path, "`type` must be a name or a switch")),
+			}
+		};
+
+		if let Some(valid) = get(attr, "valid") {
+			let valid = match valid {
+				Yaml::Hash(h) => get(h, "eq"),
+				other => Some(other),
+			};
+			match valid.and_then(|v| expr(self, "valid", v)) {
+				Some(value) => attrs.push(format!("assert_eq = \"{}\"", value)),
+				None => self.unsupported(path, "`valid` other than a value to equal"),
+			}
+		}
+		if let Some(name) = get(attr, "enum").and_then(as_expr) {
+			field_docs.push(format!("Values of the `{}` enum", name));
+		}
+
+		// Repeating and conditions give the context to their wrapper, so the value inside can not
+		// have one of its own
+		let wrap = |this: &mut Self,
+		            context: &mut Option<String>,
+		            key: &str,
+		            new_context: Option<String>,
+		            ty: Ty| {
+			if context.is_some() {
+				this.unsupported(
+					&format!("{}/{}", path, key),
+					format!("`{}` on a value which needs its own size or switch", key),
+				);
+				return ty;
+			}
+			*context = new_context;
+			ty
+		};
+		match get(attr, "repeat").and_then(as_expr).as_deref() {
+			None => (),
+			Some("eos") => ty = wrap(self, &mut context, "repeat", None, Ty::Vec(Box::new(ty))),
+			Some("expr") => match get(attr, "repeat-expr") {
+				Some(Yaml::Integer(n)) if context.is_none() => {
+					ty = Ty::Array(Box::new(ty), *n as usize)
+				}
+				Some(count) => {
+					let count = expr(self, "repeat-expr", count).map(as_usize);
+					ty = wrap(
+						self,
+						&mut context,
+						"repeat",
+						count,
+						Ty::Counted(Box::new(ty)),
+					);
+				}
+				None => return Err(invalid(path, "`repeat: expr` needs a `repeat-expr`")),
+			},
+			Some(other) => self.unsupported(path, format!("`repeat: {}`", other)),
+		}
+		if let Some(condition) = get(attr, "if") {
+			let condition = expr(self, "if", condition);
+			ty = wrap(self, &mut context, "if", condition, Ty::Cond(Box::new(ty)));
+		}
+
+		if let Some(context) = context {
+			attrs.insert(0, format!("ctx = \"{}\"", context));
+		}
+		Ok(Field {
+			name: ident(id),
+			docs: field_docs,
+			ty,
+			attrs,
+		})
+	}
+
+	fn cstr(&mut self, path: &str, encoding: &Option<String>) -> Ty {
+		match encoding.as_deref().map(str::to_ascii_uppercase).as_deref() {
+			None | Some("UTF-8") | Some("ASCII") => (),
+			Some(other) => self.unsupported(path, format!("NUL terminated {} strings", other)),
+		}
+		Ty::CStr
+	}
+
+	// A built in number type, or one of the types in the spec
+	fn named_type(&mut self, path: &str, name: &str) -> Result<Ty, Error> {
+		let (kind, rest) = name.split_at(1.min(name.len()));
+		let (width, suffix) = rest.split_at(
+			rest.find(|c: char| !c.is_ascii_digit())
+				.unwrap_or(rest.len()),
+		);
+		let rust = match (kind, width) {
+			("u", "1" | "2" | "4" | "8") => Some(format!("u{}", width.parse::<u32>().unwrap() * 8)),
+			("s", "1" | "2" | "4" | "8") => Some(format!("i{}", width.parse::<u32>().unwrap() * 8)),
+			("f", "4" | "8") => Some(format!("f{}", width.parse::<u32>().unwrap() * 8)),
+			("b", w) if !w.is_empty() && suffix.is_empty() => {
+				self.unsupported(path, format!("bit sized integer `{}`", name));
+				return Ok(Ty::Prim("u8".to_string()));
+			}
+			_ => None,
+		};
+
+		match rust {
+			Some(rust) => {
+				if width == "1" {
+					return Ok(Ty::Prim(rust));
+				}
+				let wrapper = match suffix {
+					"le" => "LE",
+					"be" => "BE",
+					"" => self
+						.endian
+						.ok_or_else(|| invalid(path, &format!("`{}` needs an endianness", name)))?,
+					_ => return Err(invalid(path, &format!("unknown type `{}`", name))),
+				};
+				Ok(Ty::Prim(format!("::parst::endian::{}<{}>", wrapper, rust)))
+			}
+			None => match self.names.get(name) {
+				Some(rust) => Ok(Ty::User(rust.clone())),
+				None => {
+					self.unsupported(
+						path,
+						format!("the type `{}`, which is not defined here", name),
+					);
+					Ok(Ty::Bytes)
+				}
+			},
+		}
+	}
+
+	// A switch becomes an enum chosen by the value switched on, given as its context
+	fn switch(&mut self, path: &str, name: String, switch: &Hash) -> Result<(), Error> {
+		let cases = get(switch, "cases")
+			.and_then(Yaml::as_hash)
+			.ok_or_else(|| invalid(path, "a switch must have `cases`"))?;
+
+		let mut variants: Vec<Variant> = Vec::new();
+		for (key, case) in cases {
+			let key = key_str(key);
+			let dis = match key.as_str() {
+				"_" => "dis_pat = \"_\"".to_string(),
+				_ => match translate(&key, &Scope::new()) {
+					Ok(value) => format!("dis = \"{}\"", value),
+					Err(_) => {
+						self.unsupported(
+							&format!("{}/type/cases/{}", path, key),
+							"a case which is not an integer",
+						);
+						continue;
+					}
+				},
+			};
+			let case = case
+				.as_str()
+				.ok_or_else(|| invalid(path, "each case must be a type name"))?;
+			let ty = self.named_type(&format!("{}/type/cases/{}", path, key), case)?;
+
+			let mut variant_name = camel_case(case);
+			if variants.iter().any(|v| v.name == variant_name) {
+				match key.as_str() {
+					"_" => variant_name.push_str("Other"),
+					key => variant_name.extend(key.chars().filter(char::is_ascii_alphanumeric)),
+				}
+			}
+			variants.push(Variant {
+				name: variant_name,
+				dis,
+				ty,
+			});
+		}
+
+		self.items.push(Item::Enum { name, variants });
+		Ok(())
+	}
+}
+
+fn root(path: &str) -> String {
+	match path {
+		"" => "/".to_string(),
+		path => path.to_string(),
+	}
+}
+
+// Kaitai works in 64 bit integers, which sizes and counts are converted from
+fn as_usize(expr: String) -> String {
+	let wrapped = expr.starts_with('(')
+		&& expr.ends_with(')')
+		&& expr[1..expr.len() - 1]
+			.chars()
+			.try_fold(0usize, |depth, c| match c {
+				'(' => Some(depth + 1),
+				')' => depth.checked_sub(1),
+				_ => Some(depth),
+			}) == Some(0);
+	match wrapped {
+		true => format!("{} as usize", expr),
+		false => format!("({}) as usize", expr),
+	}
+}
+
+fn invalid(path: &str, message: &str) -> Error {
+	Error::Invalid {
+		path: root(path),
+		message: message.to_string(),
+	}
+}
+
+fn contents_bytes(contents: &Yaml) -> Option<Vec<u8>> {
+	match contents {
+		Yaml::String(s) => Some(s.as_bytes().to_vec()),
+		Yaml::Array(items) => items.iter().try_fold(Vec::new(), |mut bytes, item| {
+			match item {
+				Yaml::Integer(i) => bytes.push(u8::try_from(*i).ok()?),
+				Yaml::String(s) => bytes.extend_from_slice(s.as_bytes()),
+				_ => return None,
+			}
+			Some(bytes)
+		}),
+		_ => None,
+	}
+}
+
+// Types which hold borrowed bytes, directly or through others, take the lifetime `'a`
+fn borrowing(items: &[Item]) -> HashMap<&str, bool> {
+	fn borrows(ty: &Ty, found: &HashMap<&str, bool>) -> bool {
+		match ty {
+			Ty::Bytes | Ty::CStr => true,
+			Ty::Prim(_) | Ty::ByteArray(_) => false,
+			Ty::User(name) => found.get(name.as_str()).copied().unwrap_or(false),
+			Ty::Array(ty, _) | Ty::Vec(ty) | Ty::Counted(ty) | Ty::Limited(ty) | Ty::Cond(ty) => {
+				borrows(ty, found)
+			}
+		}
+	}
+
+	let mut found = items
+		.iter()
+		.map(|item| (item.name(), false))
+		.collect::<HashMap<_, _>>();
+	loop {
+		let mut changed = false;
+		for item in items {
+			if !found[item.name()] && item.types().into_iter().any(|ty| borrows(ty, &found)) {
+				found.insert(item.name(), true);
+				changed = true;
+			}
+		}
+		if !changed {
+			return found;
+		}
+	}
+}
+
+fn render_type(ty: &Ty, borrowing: &HashMap<&str, bool>) -> String {
+	match ty {
+		Ty::Prim(prim) => prim.clone(),
+		Ty::Bytes => "&'a [u8]".to_string(),
+		Ty::ByteArray(n) => format!("[u8; {}]", n),
+		Ty::CStr => "::parst_extra::strings::NulTerminated<'a>".to_string(),
+		Ty::User(name) => match borrowing.get(name.as_str()) {
+			Some(true) => format!("{}<'a>", name),
+			_ => name.clone(),
+		},
+		Ty::Array(ty, n) => format!("[{}; {}]", render_type(ty, borrowing), n),
+		Ty::Vec(ty) => format!("Vec<{}>", render_type(ty, borrowing)),
+		Ty::Counted(ty) => format!(
+			"::parst_extra::var::Counted<{}>",
+			render_type(ty, borrowing)
+		),
+		Ty::Limited(ty) => format!(
+			"::parst_extra::var::Limited<{}>",
+			render_type(ty, borrowing)
+		),
+		Ty::Cond(ty) => format!("::parst_extra::var::Cond<{}>", render_type(ty, borrowing)),
+	}
+}
+
+fn render(items: &[Item], source: &str) -> String {
+	let borrowing = borrowing(items);
+	let mut out = format!("// Generated by parst_codegen from `{}`\n", source);
+
+	for item in items {
+		out.push('\n');
+		let (lifetime, params) = match borrowing[item.name()] {
+			true => ("lifetime = \"'a\", ", "<'a>"),
+			false => ("", ""),
+		};
+		match item {
+			Item::Struct { name, docs, fields } => {
+				for doc in docs {
+					writeln!(out, "/// {}", doc).unwrap();
+				}
+				writeln!(
+					out,
+					"#[derive(Debug, ::parst::Parsable, ::parst::Deparsable)]"
+				)
+				.unwrap();
+				writeln!(out, "#[parst({}src = \"[u8]\")]", lifetime).unwrap();
+				writeln!(out, "pub struct {}{} {{", name, params).unwrap();
+				for field in fields {
+					for doc in &field.docs {
+						writeln!(out, "\t/// {}", doc).unwrap();
+					}
+					if !field.attrs.is_empty() {
+						writeln!(out, "\t#[parst({})]", field.attrs.join(", ")).unwrap();
+					}
+					let ty = render_type(&field.ty, &borrowing);
+					writeln!(out, "\tpub {}: {},", field.name, ty).unwrap();
+				}
+				writeln!(out, "}}").unwrap();
+			}
+			Item::Enum { name, variants } => {
+				writeln!(
+					out,
+					"#[derive(Debug, ::parst::Parsable, ::parst::Deparsable)]"
+				)
+				.unwrap();
+				writeln!(
+					out,
+					"#[parst({}src = \"[u8]\", ctx = \"on: i64\", dis_from_ctx = \"on\")]",
+					lifetime
+				)
+				.unwrap();
+				writeln!(out, "pub enum {}{} {{", name, params).unwrap();
+				for variant in variants {
+					let ty = render_type(&variant.ty, &borrowing);
+					writeln!(out, "\t#[parst({})]", variant.dis).unwrap();
+					writeln!(out, "\t{}({}),", variant.name, ty).unwrap();
+				}
+				writeln!(out, "}}").unwrap();
+			}
+		}
+	}
+	out
+}
+
+pub fn generate(ksy: &str) -> Result<String, Error> {
+	let documents = YamlLoader::load_from_str(ksy)?;
+	let root = documents
+		.first()
+		.and_then(Yaml::as_hash)
+		.ok_or_else(|| invalid("", "the definition must be a map"))?;
+
+	let meta = get(root, "meta")
+		.and_then(Yaml::as_hash)
+		.ok_or_else(|| invalid("meta", "missing"))?;
+	let id = get(meta, "id")
+		.and_then(as_expr)
+		.ok_or_else(|| invalid("meta/id", "missing"))?;
+
+	let mut generator = Generator::default();
+	generator.check_keys(
+		"meta",
+		meta,
+		&[
+			"id",
+			"title",
+			"application",
+			"file-extension",
+			"xref",
+			"license",
+			"ks-version",
+			"ks-debug",
+			"ks-opaque-types",
+			"encoding",
+			"endian",
+			"tags",
+		],
+	);
+	generator.endian = match get(meta, "endian").map(|e| as_expr(e).unwrap_or_default()) {
+		None => None,
+		Some(e) if e == "le" => Some("LE"),
+		Some(e) if e == "be" => Some("BE"),
+		Some(_) => {
+			generator.unsupported("meta/endian", "an endianness chosen at run time");
+			None
+		}
+	};
+
+	let root_name = camel_case(&id);
+	generator.names.insert(id.clone(), root_name.clone());
+	generator.collect_names("", root)?;
+	generator.type_spec("", root_name, root)?;
+
+	match generator.unsupported.is_empty() {
+		true => Ok(render(&generator.items, &id)),
+		false => Err(Error::Unsupported(generator.unsupported)),
+	}
+}
@@ -0,0 +1,45 @@
+// Generates Rust types deriving `Parsable` and `Deparsable` from Kaitai Struct `.ksy` definitions.
+// The output uses `parst` and `parst_extra`, which the crate including it depends on
+
+mod expr;
+mod generate;
+
+pub use generate::generate;
+
+use std::{fmt, fs, io, path::Path};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+	// Where in the definition it is, such as `/types/header/seq/flags/if`
+	pub path: String,
+	pub construct: String,
+}
+
+impl fmt::Display for Unsupported {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.path, self.construct)
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("could not read or write: {0}")]
+	Io(#[from] io::Error),
+	#[error("invalid YAML: {0}")]
+	Yaml(#[from] yaml_rust2::ScanError),
+	#[error("{path}: {message}")]
+	Invalid { path: String, message: String },
+	#[error("unsupported:{}", .0.iter().map(|u| format!("\n  {}", u)).collect::<String>())]
+	Unsupported(Vec<Unsupported>),
+}
+
+// For build scripts, writes the types for the definition at `input` to `output`, and reruns when
+// the definition changes
+pub fn generate_file(input: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<(), Error> {
+	let input = input.as_ref();
+	println!("cargo:rerun-if-changed={}", input.display());
+	let code = generate(&fs::read_to_string(input)?)?;
+	fs::write(output, code)?;
+	Ok(())
+}
@@ -0,0 +1,37 @@
+use std::{env, fs, process::ExitCode};
+
+// parst_codegen <definition.ksy> [output.rs]
+fn main() -> ExitCode {
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	let (input, output) = match args.as_slice() {
+		[input] => (input, None),
+		[input, output] => (input, Some(output)),
+		_ => {
+			eprintln!("usage: parst_codegen <definition.ksy> [output.rs]");
+			return ExitCode::FAILURE;
+		}
+	};
+
+	let result = fs::read_to_string(input)
+		.map_err(parst_codegen::Error::from)
+		.and_then(|ksy| parst_codegen::generate(&ksy));
+	match result {
+		Ok(code) => match output {
+			Some(output) => match fs::write(output, code) {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(e) => {
+					eprintln!("{}: {}", output, e);
+					ExitCode::FAILURE
+				}
+			},
+			None => {
+				print!("{}", code);
+				ExitCode::SUCCESS
+			}
+		},
+		Err(e) => {
+			eprintln!("{}: {}", input, e);
+			ExitCode::FAILURE
+		}
+	}
+}
@@ -0,0 +1,120 @@
+use parst::{Deparsable, Parsable};
+use parst_codegen::{generate, Error, Unsupported};
+
+// The snapshot is compiled as well as compared, so that the output stays valid Rust
+#[allow(dead_code)]
+mod packet {
+	include!("snapshots/packet.rs");
+}
+
+#[test]
+fn matches_snapshot() {
+	let code = generate(include_str!("snapshots/packet.ksy")).unwrap();
+	assert_eq!(code, include_str!("snapshots/packet.rs"));
+}
+
+#[test]
+fn generated_types_read_and_write() {
+	let bytes = [
+		b'P', b'K', 1, 2, 0, 1, 0xaa, 0xbb, 0, 0, 0, 7, 1, 3, 0, 4, 0, 5, 0, 6, 0,
+	];
+	let (value, rest) = packet::Packet::read(&bytes, ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(value.body, [0xaa, 0xbb]);
+	assert_eq!(value.extra.0.map(|extra| extra.0), Some(7));
+	assert_eq!(value.points.0.len(), 1);
+	assert!(matches!(value.payload, packet::PacketPayload::Point(_)));
+
+	let mut written = Vec::new();
+	value.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+}
+
+fn unsupported(ksy: &str) -> Vec<String> {
+	match generate(ksy) {
+		Err(Error::Unsupported(unsupported)) => {
+			unsupported.iter().map(Unsupported::to_string).collect()
+		}
+		other => panic!(
+			"expected unsupported constructs, got {:?}",
+			other.map(|_| ())
+		),
+	}
+}
+
+#[test]
+fn unsupported_constructs_reported_with_paths() {
+	let ksy = "
+meta:
+  id: file
+  endian: le
+  imports: [other]
+seq:
+  - id: bits
+    type: b3
+  - id: name
+    type: str
+    encoding: UTF-8
+  - id: ref
+    type: other
+  - id: items
+    type: u1
+    repeat: until
+    repeat-until: _ == 0
+  - id: body
+    size: 4
+    process: zlib
+instances:
+  total:
+    value: 1
+";
+	assert_eq!(
+		unsupported(ksy),
+		[
+			"meta: `imports`",
+			"/: `instances`",
+			"/seq/bits: bit sized integer `b3`",
+			"/seq/name: a string without a size or terminator",
+			"/seq/ref: the type `other`, which is not defined here",
+			"/seq/items: `repeat-until`",
+			"/seq/items: `repeat: until`",
+			"/seq/body: `process`",
+		]
+	);
+}
+
+#[test]
+fn wrappers_sharing_a_context_unsupported() {
+	let ksy = "
+meta:
+  id: file
+seq:
+  - id: len
+    type: u1
+  - id: body
+    size: len
+    if: len > 2
+  - id: cases
+    type:
+      switch-on: len
+      cases:
+        1: u1
+        x: u1
+";
+	assert_eq!(
+		unsupported(ksy),
+		[
+			"/seq/body/if: `if` on a value which needs its own size or switch",
+			"/seq/cases/type/cases/x: a case which is not an integer",
+		]
+	);
+}
+
+#[test]
+fn invalid_definitions_rejected() {
+	let error = generate("meta:\n  id: file\nseq:\n  - id: value\n    type: u2\n").unwrap_err();
+	assert_eq!(error.to_string(), "/seq/value: `u2` needs an endianness");
+
+	let error = generate("seq: []\n").unwrap_err();
+	assert_eq!(error.to_string(), "meta: missing");
+}
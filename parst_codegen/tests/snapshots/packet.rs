@@ -0,0 +1,37 @@
+// Generated by parst_codegen from `packet`
+
+#[derive(Debug, ::parst::Parsable, ::parst::Deparsable)]
+#[parst(src = "[u8]", ctx = "on: i64", dis_from_ctx = "on")]
+pub enum PacketPayload {
+	#[parst(dis = "1")]
+	Point(Point),
+	#[parst(dis = "2")]
+	U2(::parst::endian::LE<u16>),
+}
+
+#[derive(Debug, ::parst::Parsable, ::parst::Deparsable)]
+#[parst(lifetime = "'a", src = "[u8]")]
+pub struct Packet<'a> {
+	#[parst(assert_eq = "[0x50, 0x4b]")]
+	pub magic: [u8; 2],
+	pub kind: u8,
+	pub len: ::parst::endian::LE<u16>,
+	pub flags: u8,
+	#[parst(ctx = "(len.0 as i64) as usize")]
+	pub body: &'a [u8],
+	#[parst(ctx = "(flags as i64) & 1 != 0")]
+	pub extra: ::parst_extra::var::Cond<::parst::endian::BE<u32>>,
+	pub count: u8,
+	#[parst(ctx = "(count as i64) as usize")]
+	pub points: ::parst_extra::var::Counted<Point>,
+	#[parst(ctx = "(kind as i64)")]
+	pub payload: PacketPayload,
+}
+
+/// A position
+#[derive(Debug, ::parst::Parsable, ::parst::Deparsable)]
+#[parst(src = "[u8]")]
+pub struct Point {
+	pub x: ::parst::endian::LE<i16>,
+	pub y: ::parst::endian::LE<i16>,
+}
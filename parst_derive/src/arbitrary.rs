@@ -0,0 +1,354 @@
+use crate::{
+	attributes::{
+		parse_field_attributes, parse_variant_attributes, Assertion, LocalContext,
+		VariantAttributes,
+	},
+	helpers::field_name,
+};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+	parse_quote, Data, DataEnum, DeriveInput, Expr, ExprLit, ExprUnary, Field, Fields,
+	GenericParam, Lifetime, Lit, Pat, Path, RangeLimits, Type, UnOp,
+};
+
+// Values which would not read back as themselves are rejected, which `arbitrary` treats as the
+// input not fitting the type
+fn reject() -> TokenStream {
+	quote! { return ::core::result::Result::Err(::parst::testing::arbitrary::Error::IncorrectFormat) }
+}
+
+// Implements `Arbitrary` generating each field in order, bound by value for the fields after it in
+// the same way as when parsing
+pub fn generate_arbitrary(input: &DeriveInput, ctx: &LocalContext) -> TokenStream {
+	let ident = &input.ident;
+
+	let mut generated = Vec::new();
+	let body = match &input.data {
+		Data::Struct(s) => {
+			let construct = gen_construct(
+				quote! { Self },
+				&s.fields,
+				&ctx.asserts,
+				false,
+				&mut generated,
+			);
+			quote! {
+				#construct
+				__value
+			}
+		}
+		Data::Enum(e) => gen_enum(e, ctx, &mut generated),
+		Data::Union(_) => panic!("Can not derive arbitrary for union"),
+	};
+	let validate = gen_validate(&ctx.validate);
+
+	// A type borrowing from its source borrows from the unstructured data instead
+	let mut generics = input.generics.clone();
+	let lifetime = match generics.lifetimes().next() {
+		Some(param) => param.lifetime.clone(),
+		None => {
+			let lifetime = Lifetime::new("'__u", proc_macro2::Span::call_site());
+			generics
+				.params
+				.insert(0, GenericParam::Lifetime(parse_quote! { #lifetime }));
+			lifetime
+		}
+	};
+	let predicates = &mut generics.make_where_clause().predicates;
+	for ty in generated {
+		predicates.push(parse_quote! { #ty: ::parst::testing::arbitrary::Arbitrary<#lifetime> });
+	}
+
+	let (impl_generics, _, where_clause) = generics.split_for_impl();
+	let (_, type_generics, _) = input.generics.split_for_impl();
+
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics ::parst::testing::arbitrary::Arbitrary<#lifetime> for #ident #type_generics #where_clause {
+			fn arbitrary(
+				__u: &mut ::parst::testing::arbitrary::Unstructured<#lifetime>,
+			) -> ::parst::testing::arbitrary::Result<Self> {
+				#![allow(non_snake_case)]
+				let __value = { #body };
+				#validate
+				::core::result::Result::Ok(__value)
+			}
+		}
+	}
+}
+
+// Integer range patterns, such as `3..` or `-5..=5`, are met by generating a value within them
+fn gen_in_range(name: &Ident, ty: &Type, pat: &Pat) -> Option<TokenStream> {
+	fn is_int(expr: &Expr) -> bool {
+		match expr {
+			Expr::Lit(ExprLit {
+				lit: Lit::Int(_), ..
+			}) => true,
+			Expr::Unary(ExprUnary {
+				op: UnOp::Neg(_),
+				expr,
+				..
+			}) => is_int(expr),
+			_ => false,
+		}
+	}
+
+	let Pat::Range(range) = pat else {
+		return None;
+	};
+	if !range.start.iter().chain(&range.end).all(|end| is_int(end)) {
+		return None;
+	}
+
+	let reject = reject();
+	let start = match &range.start {
+		Some(start) => quote! { #start },
+		None => quote! { <#ty>::MIN },
+	};
+	let end = match (&range.end, &range.limits) {
+		(Some(end), RangeLimits::Closed(_)) => quote! { #end },
+		(Some(end), RangeLimits::HalfOpen(_)) => quote! {
+			match <#ty>::checked_sub(#end, 1) {
+				::core::option::Option::Some(end) => end,
+				::core::option::Option::None => #reject,
+			}
+		},
+		(None, _) => quote! { <#ty>::MAX },
+	};
+	Some(quote! {
+		let (__start, __end): (#ty, #ty) = (#start, #end);
+		if __start > __end {
+			#reject;
+		}
+		let #name: #ty = __u.int_in_range(__start..=__end)?;
+	})
+}
+
+// Without a discriminant, parsing tries each variant in turn, so a value generated for a later
+// variant can read back as an earlier one whose fields happen to fit its bytes. Telling these apart
+// needs the value written and read, which the contexts being unknown rules out, so such enums are
+// generated as they are and may fail a round trip that parsing itself would never produce
+fn gen_enum(input: &DataEnum, ctx: &LocalContext, generated: &mut Vec<Type>) -> TokenStream {
+	let variants = input
+		.variants
+		.iter()
+		.map(|variant| (variant, parse_variant_attributes(&variant.attrs)))
+		.collect::<Vec<_>>();
+	let count = variants.len();
+
+	let arms = variants
+		.iter()
+		.enumerate()
+		.map(|(index, (variant, attributes))| {
+			let name = &variant.ident;
+
+			// The discriminant must pick this variant, and not one before it
+			let dis_field = variant
+				.fields
+				.iter()
+				.find(|field| parse_field_attributes(&field.attrs).dis);
+			let discriminant = match (&attributes.dis, &attributes.dis_pat) {
+				(Some(value), _) => Some(quote! {
+					#[allow(unused_variables)]
+					let __discriminant = { #value };
+				}),
+				(None, Some(pat)) => {
+					let ty = dis_field.map(|field| &field.ty).or(ctx.dis_type.as_ref());
+					ty.map(|ty| {
+						let discriminant = Ident::new("__discriminant", Span::call_site());
+						if let Some(tokens) = gen_in_range(&discriminant, ty, pat) {
+							return tokens;
+						}
+						generated.push(ty.clone());
+						let reject = reject();
+						quote! {
+							let __discriminant: #ty = ::parst::testing::arbitrary::Arbitrary::arbitrary(__u)?;
+							if !matches!(__discriminant, #pat) {
+								#reject;
+							}
+						}
+					})
+				}
+				(None, None) => None,
+			};
+			let shadowed = discriminant
+				.is_some()
+				.then(|| gen_shadowed(&variants[..index]));
+
+			let construct = gen_construct(
+				quote! { Self::#name },
+				&variant.fields,
+				&attributes.asserts,
+				discriminant.is_some(),
+				generated,
+			);
+			let validate = gen_validate(&attributes.validate);
+
+			quote! {
+				#index => {
+					#discriminant
+					#shadowed
+					#construct
+					#validate
+					__value
+				}
+			}
+		});
+
+	quote! {
+		match __u.choose_index(#count)? {
+			#( #arms )*
+			_ => unreachable!(),
+		}
+	}
+}
+
+fn gen_shadowed(earlier: &[(&syn::Variant, VariantAttributes)]) -> TokenStream {
+	let reject = reject();
+	let checks =
+		earlier.iter().map(
+			|(_, attributes)| match (&attributes.dis, &attributes.dis_pat) {
+				(_, Some(pat)) => quote! {
+					if matches!(__discriminant, #pat) {
+						#reject;
+					}
+				},
+				(Some(value), None) => quote! {
+					if __discriminant == { #value } {
+						#reject;
+					}
+				},
+				(None, None) => quote! {},
+			},
+		);
+	quote! { #( #checks )* }
+}
+
+// Binds each field, checks the assertions and then binds the value built from them as `__value`.
+// Inside a variant with a discriminant, a field marked `dis` takes its value
+fn gen_construct(
+	path: TokenStream,
+	fields: &Fields,
+	asserts: &[Assertion],
+	has_discriminant: bool,
+	generated: &mut Vec<Type>,
+) -> TokenStream {
+	let field_names = fields
+		.iter()
+		.enumerate()
+		.map(field_name)
+		.collect::<Vec<_>>();
+
+	let bindings = fields
+		.iter()
+		.zip(field_names.iter())
+		.map(|(field, name)| gen_field(field, name, has_discriminant, generated))
+		.collect::<Vec<_>>();
+	let asserts = gen_asserts(&field_names, asserts);
+
+	let pattern = match fields {
+		Fields::Named(_) => quote! { { #(#field_names),* } },
+		Fields::Unnamed(_) => quote! { ( #(#field_names),* ) },
+		Fields::Unit => quote! {},
+	};
+
+	quote! {
+		#( #bindings )*
+		#asserts
+		let __value = #path #pattern;
+	}
+}
+
+fn gen_field(
+	Field { attrs, ty, .. }: &Field,
+	name: &Ident,
+	has_discriminant: bool,
+	generated: &mut Vec<Type>,
+) -> TokenStream {
+	let field_attributes = parse_field_attributes(attrs);
+	let reject = reject();
+
+	if field_attributes.dis {
+		if !has_discriminant {
+			panic!("A dis field needs a discriminant given by its variant");
+		}
+		return quote! {
+			let #name: #ty = ::core::clone::Clone::clone(&__discriminant);
+		};
+	}
+	if let Some(e) = field_attributes.default {
+		return quote! { let #name: #ty = { #e }; };
+	}
+	if let Some(e) = field_attributes.assert_eq {
+		return quote! { let #name: #ty = #e; };
+	}
+
+	let mut tokens = Vec::new();
+	match field_attributes
+		.matches
+		.as_ref()
+		.map(|pat| (pat, gen_in_range(name, ty, pat)))
+	{
+		Some((_, Some(in_range))) => tokens.push(in_range),
+		matches => {
+			generated.push(ty.clone());
+			tokens.push(quote! {
+				let #name: #ty = ::parst::testing::arbitrary::Arbitrary::arbitrary(__u)?;
+			});
+			if let Some((pat, None)) = matches {
+				tokens.push(quote! {
+					if !matches!(#name, #pat) {
+						#reject;
+					}
+				});
+			}
+		}
+	}
+	if let Some(e) = field_attributes.assert_ne {
+		tokens.push(quote! {
+			if #name == #e {
+				#reject;
+			}
+		});
+	}
+	quote! { #( #tokens )* }
+}
+
+// Assertions see every field by reference, the same as when parsing
+fn gen_asserts(field_names: &[Ident], asserts: &[Assertion]) -> TokenStream {
+	if asserts.is_empty() {
+		return quote! {};
+	}
+
+	let reject = reject();
+	let checks = asserts.iter().map(|assertion| {
+		let expr = &assertion.expr;
+		quote! {
+			if !(#expr) {
+				#reject;
+			}
+		}
+	});
+
+	quote! {
+		{
+			#(
+				#[allow(unused_variables)]
+				let #field_names = &#field_names;
+			)*
+			#( #checks )*
+		}
+	}
+}
+
+fn gen_validate(validate: &[Path]) -> TokenStream {
+	let reject = reject();
+	quote! {
+		#(
+			if #validate(&__value).is_err() {
+				#reject;
+			}
+		)*
+	}
+}
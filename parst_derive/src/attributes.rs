@@ -1,15 +1,104 @@
-use quote::ToTokens;
+use crate::helpers::source_string;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-	parse_quote, Attribute, Expr, FnArg, GenericParam, Generics, Lifetime, LitStr, Pat, PatType,
-	Type,
+	parse_quote, punctuated::Punctuated, visit_mut::VisitMut, Attribute, Expr, ExprPath, FnArg,
+	GenericParam, Generics, Lifetime, LitStr, Pat, PatType, Path, Token, Type,
 };
 
+#[derive(Debug, Clone)]
+pub struct Assertion {
+	pub expr: Expr,
+	pub err: Option<LitStr>,
+}
+
+impl Assertion {
+	pub fn message(&self) -> LitStr {
+		self.err
+			.clone()
+			.unwrap_or_else(|| LitStr::new(&source_string(&self.expr, false), Span::call_site()))
+	}
+}
+
+// Tuple fields used to be bound as `__field_0`, `__field_1`, ..., which still name `_0`, `_1`, ...
+fn field_alias(ident: &Ident) -> Option<Ident> {
+	let index = ident
+		.to_string()
+		.strip_prefix("__field_")?
+		.parse::<usize>()
+		.ok()?;
+	Some(format_ident!("_{}", index, span = ident.span()))
+}
+
+struct FieldAliases;
+
+impl VisitMut for FieldAliases {
+	fn visit_expr_path_mut(&mut self, expr: &mut ExprPath) {
+		let alias = expr.path.get_ident().and_then(field_alias);
+		if let (Some(alias), None) = (alias, &expr.qself) {
+			expr.path = alias.into();
+		}
+	}
+}
+
+fn parse_expr(litstring: &LitStr) -> Expr {
+	let mut expr = litstring.parse::<Expr>().unwrap();
+	FieldAliases.visit_expr_mut(&mut expr);
+	expr
+}
+
+#[derive(Debug, Clone)]
+pub enum Tag {
+	// Text, for `str` and `[u8]` sources
+	Text(LitStr),
+	// A sequence of elements, for slices of any other element type such as tokens
+	Elements(Expr),
+}
+
+impl Tag {
+	fn parse(name: &str, litstring: LitStr) -> Self {
+		match name {
+			"tag" => Tag::Text(litstring),
+			_ => Tag::Elements(parse_expr(&litstring)),
+		}
+	}
+
+	pub fn text(&self) -> Option<String> {
+		match self {
+			Tag::Text(text) => Some(text.value()),
+			Tag::Elements(_) => None,
+		}
+	}
+
+	// The number of elements of the source the tag takes up
+	pub fn len(&self) -> TokenStream {
+		match self {
+			Tag::Text(text) => {
+				let len = text.value().len();
+				quote! { #len }
+			}
+			Tag::Elements(elements) => quote! { { #elements }.len() },
+		}
+	}
+}
+
 #[derive(Debug, Default)]
 pub struct OuterAttributes {
 	pub lifetime: Option<Lifetime>,
 	pub src: Option<Type>,
 	pub ctx: Option<PatType>,
 	pub dis: Option<Type>,
+	pub dis_ctx: InnerContext,
+	pub dis_from_ctx: Option<Expr>,
+	pub validate: Vec<Path>,
+	pub asserts: Vec<Assertion>,
+	pub owned: bool,
+	pub owned_name: Option<Ident>,
+	pub owned_derive: Vec<Path>,
+	pub tag: Option<Tag>,
+	pub fixed_size: bool,
+	pub spans: bool,
+	pub max_depth: Option<Expr>,
 }
 
 impl OuterAttributes {
@@ -51,6 +140,74 @@ impl OuterAttributes {
 								let value = litstring.parse::<Type>().unwrap();
 								outer_attributes.dis = Some(value);
 							}
+							"dis_ctx" => {
+								outer_attributes.dis_ctx = match meta.input.peek(Token![=]) {
+									true => {
+										let value = meta.value().unwrap();
+										let litstring = value.parse::<LitStr>().unwrap();
+										let value = parse_expr(&litstring);
+										InnerContext::Expr(value)
+									}
+									false => InnerContext::Inherit,
+								};
+							}
+							"dis_from_ctx" => {
+								let value = meta.value().unwrap();
+								let litstring = value.parse::<LitStr>().unwrap();
+								let value = parse_expr(&litstring);
+								outer_attributes.dis_from_ctx = Some(value);
+							}
+							"validate" => {
+								let value = meta.value().unwrap();
+								let litstring = value.parse::<LitStr>().unwrap();
+								let value = litstring.parse::<Path>().unwrap();
+								outer_attributes.validate.push(value);
+							}
+							"assert" => {
+								let value = meta.value().unwrap();
+								let litstring = value.parse::<LitStr>().unwrap();
+								let expr = parse_expr(&litstring);
+								outer_attributes.asserts.push(Assertion { expr, err: None });
+							}
+							"err" => {
+								let value = meta.value().unwrap();
+								let litstring = value.parse::<LitStr>().unwrap();
+								let assertion = outer_attributes
+									.asserts
+									.last_mut()
+									.expect("err must follow an assert");
+								assertion.err = Some(litstring);
+							}
+							"owned" => {
+								outer_attributes.owned = true;
+								if meta.input.peek(Token![=]) {
+									let value = meta.value().unwrap();
+									let litstring = value.parse::<LitStr>().unwrap();
+									let value = litstring.parse::<Ident>().unwrap();
+									outer_attributes.owned_name = Some(value);
+								}
+							}
+							"owned_derive" => {
+								let value = meta.value().unwrap();
+								let litstring = value.parse::<LitStr>().unwrap();
+								let value = litstring
+									.parse_with(Punctuated::<Path, Token![,]>::parse_terminated)
+									.unwrap();
+								outer_attributes.owned_derive.extend(value);
+							}
+							name @ ("tag" | "tag_elements") => {
+								let value = meta.value().unwrap();
+								let litstring = value.parse::<LitStr>().unwrap();
+								outer_attributes.tag = Some(Tag::parse(name, litstring));
+							}
+							"fixed_size" => outer_attributes.fixed_size = true,
+							"spans" => outer_attributes.spans = true,
+							"max_depth" => {
+								let value = meta.value().unwrap();
+								let litstring = value.parse::<LitStr>().unwrap();
+								let value = parse_expr(&litstring);
+								outer_attributes.max_depth = Some(value);
+							}
 							x => panic!("unknown attribute {}", x),
 						}
 					}
@@ -64,7 +221,7 @@ impl OuterAttributes {
 	}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LocalContext {
 	pub src_lifetime: Lifetime,
 	pub src_type: Type,
@@ -73,9 +230,43 @@ pub struct LocalContext {
 	pub ctx_type: Type,
 	pub ctx_is_generic: bool,
 	pub dis_type: Option<Type>,
+	pub dis_ctx: InnerContext,
+	pub dis_from_ctx: Option<Expr>,
+	pub validate: Vec<Path>,
+	pub asserts: Vec<Assertion>,
+	pub owned: bool,
+	pub owned_name: Option<Ident>,
+	pub owned_derive: Vec<Path>,
+	pub tag: Option<Tag>,
+	pub fixed_size: bool,
+	pub spans: bool,
+	pub max_depth: Option<Expr>,
 }
 
 impl LocalContext {
+	// Only set when the derived traits are also to be implemented for the owned counterpart
+	pub fn owned_ident(&self, ident: &Ident) -> Option<Ident> {
+		self.owned.then(|| self.owned_type_ident(ident))
+	}
+
+	pub fn owned_type_ident(&self, ident: &Ident) -> Ident {
+		self.owned_name
+			.clone()
+			.unwrap_or_else(|| format_ident!("{}Owned", ident))
+	}
+
+	// The owned counterpart has no lifetimes of its own, so it parses from any source lifetime
+	pub fn for_owned(&self) -> Self {
+		Self {
+			src_lifetime: parse_quote! { '__src },
+			..self.clone()
+		}
+	}
+
+	pub fn has_discriminant(&self) -> bool {
+		self.dis_type.is_some() || self.dis_from_ctx.is_some()
+	}
+
 	pub fn new_generics_for_parsable(&self) -> Generics {
 		let src_lifetime = &self.src_lifetime;
 		let mut new_generics: Vec<GenericParam> = vec![parse_quote! { #src_lifetime }];
@@ -122,24 +313,61 @@ impl From<OuterAttributes> for LocalContext {
 			ctx_type,
 			ctx_is_generic,
 			dis_type: value.dis,
+			dis_ctx: value.dis_ctx,
+			dis_from_ctx: value.dis_from_ctx,
+			validate: value.validate,
+			asserts: value.asserts,
+			owned: value.owned,
+			owned_name: value.owned_name,
+			owned_derive: value.owned_derive,
+			tag: value.tag,
+			fixed_size: value.fixed_size,
+			spans: value.spans,
+			max_depth: value.max_depth,
 		}
 	}
 }
 
+#[derive(Debug)]
+pub struct Temp {
+	pub pat: PatType,
+	pub calc: Option<Expr>,
+	pub context: Option<Expr>,
+}
+
 #[derive(Debug, Default)]
 pub struct FieldAttributes {
 	pub context: InnerContext,
 	pub matches: Option<Pat>,
 	pub assert_eq: Option<Expr>,
 	pub assert_ne: Option<Expr>,
+	pub dis: bool,
+	pub default: Option<Expr>,
+	pub temps: Vec<Temp>,
+	pub tag: Option<Tag>,
+	pub spans: bool,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub enum InnerContext {
 	#[default]
 	None,
 	Inherit,
 	Expr(Expr),
+	WithFields(Vec<Ident>),
+}
+
+impl InnerContext {
+	pub fn to_context_expr(&self, ctx_pat: &Pat) -> TokenStream {
+		match self {
+			InnerContext::None => quote! { () },
+			InnerContext::Inherit => quote! { #ctx_pat },
+			InnerContext::Expr(e) => quote! { { #e } },
+			InnerContext::WithFields(fields) => quote! {
+				(#ctx_pat, #( ::core::clone::Clone::clone(&#fields) ),*)
+			},
+		}
+	}
 }
 
 pub fn parse_field_attributes(input: &[Attribute]) -> FieldAttributes {
@@ -156,7 +384,7 @@ pub fn parse_field_attributes(input: &[Attribute]) -> FieldAttributes {
 						"ctx" => {
 							let value = meta.value().unwrap();
 							let litstring = value.parse::<LitStr>().unwrap();
-							let value = litstring.parse::<Expr>().unwrap();
+							let value = parse_expr(&litstring);
 							field_attributes.context = InnerContext::Expr(value);
 						}
 						"matches" => {
@@ -170,21 +398,85 @@ pub fn parse_field_attributes(input: &[Attribute]) -> FieldAttributes {
 						"assert_eq" => {
 							let value = meta.value().unwrap();
 							let litstring = value.parse::<LitStr>().unwrap();
-							let value = litstring.parse::<Expr>().unwrap();
+							let value = parse_expr(&litstring);
 							field_attributes.assert_eq = Some(value);
 						}
 						"assert_ne" => {
 							let value = meta.value().unwrap();
 							let litstring = value.parse::<LitStr>().unwrap();
-							let value = litstring.parse::<Expr>().unwrap();
+							let value = parse_expr(&litstring);
 							field_attributes.assert_ne = Some(value);
 						}
 						"with_context" => {
 							let value = meta.value().unwrap();
 							let litstring = value.parse::<LitStr>().unwrap();
-							let value = litstring.parse::<Expr>().unwrap();
+							let value = parse_expr(&litstring);
 							field_attributes.context = InnerContext::Expr(value);
 						}
+						"ctx_inherit" => field_attributes.context = InnerContext::Inherit,
+						"with_field_context" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let value = litstring
+								.parse_with(Punctuated::<Ident, Token![,]>::parse_terminated)
+								.unwrap();
+							field_attributes.context = InnerContext::WithFields(
+								value
+									.into_iter()
+									.map(|ident| field_alias(&ident).unwrap_or(ident))
+									.collect(),
+							);
+						}
+						"dis" => field_attributes.dis = true,
+						"skip" => {
+							field_attributes.default =
+								Some(parse_quote! { ::core::default::Default::default() });
+						}
+						"default" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let value = parse_expr(&litstring);
+							field_attributes.default = Some(value);
+						}
+						"temp" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let pat = match litstring.parse::<FnArg>().unwrap() {
+								FnArg::Receiver(_) => panic!(),
+								FnArg::Typed(t) => t,
+							};
+							field_attributes.temps.push(Temp {
+								pat,
+								calc: None,
+								context: None,
+							});
+						}
+						"calc" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let value = parse_expr(&litstring);
+							let temp = field_attributes
+								.temps
+								.last_mut()
+								.expect("calc must follow a temp");
+							temp.calc = Some(value);
+						}
+						"temp_ctx" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let value = parse_expr(&litstring);
+							let temp = field_attributes
+								.temps
+								.last_mut()
+								.expect("temp_ctx must follow a temp");
+							temp.context = Some(value);
+						}
+						name @ ("tag" | "tag_elements") => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							field_attributes.tag = Some(Tag::parse(name, litstring));
+						}
+						"spans" => field_attributes.spans = true,
 						x => panic!("unknown attribute {}", x),
 					}
 				}
@@ -200,6 +492,10 @@ pub fn parse_field_attributes(input: &[Attribute]) -> FieldAttributes {
 #[derive(Debug, Default)]
 pub struct VariantAttributes {
 	pub dis: Option<Expr>,
+	pub dis_pat: Option<Pat>,
+	pub validate: Vec<Path>,
+	pub asserts: Vec<Assertion>,
+	pub tag: Option<Tag>,
 }
 
 pub fn parse_variant_attributes(input: &[Attribute]) -> VariantAttributes {
@@ -216,9 +512,45 @@ pub fn parse_variant_attributes(input: &[Attribute]) -> VariantAttributes {
 						"dis" => {
 							let value = meta.value().unwrap();
 							let litstring = value.parse::<LitStr>().unwrap();
-							let value = litstring.parse::<Expr>().unwrap();
+							let value = parse_expr(&litstring);
 							variant_attributes.dis = Some(value);
 						}
+						"dis_pat" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let value = litstring
+								.parse_with(Pat::parse_multi_with_leading_vert)
+								.unwrap();
+							variant_attributes.dis_pat = Some(value);
+						}
+						"validate" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let value = litstring.parse::<Path>().unwrap();
+							variant_attributes.validate.push(value);
+						}
+						"assert" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let expr = parse_expr(&litstring);
+							variant_attributes
+								.asserts
+								.push(Assertion { expr, err: None });
+						}
+						"err" => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							let assertion = variant_attributes
+								.asserts
+								.last_mut()
+								.expect("err must follow an assert");
+							assertion.err = Some(litstring);
+						}
+						name @ ("tag" | "tag_elements") => {
+							let value = meta.value().unwrap();
+							let litstring = value.parse::<LitStr>().unwrap();
+							variant_attributes.tag = Some(Tag::parse(name, litstring));
+						}
 						x => panic!("unknown attribute {}", x),
 					}
 				}
@@ -1,9 +1,15 @@
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::{Data, DataEnum, DataStruct, DeriveInput, Field, Fields, Pat};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{
+	Data, DataEnum, DataStruct, DeriveInput, Expr, Field, Fields, LitStr, Pat, PatType, Path,
+};
 
-use crate::attributes::{
-	parse_field_attributes, parse_variant_attributes, InnerContext, LocalContext,
+use crate::{
+	attributes::{
+		parse_field_attributes, parse_variant_attributes, Assertion, InnerContext, LocalContext,
+		Tag, Temp,
+	},
+	helpers::{field_name, referenced_bindings},
 };
 
 pub fn generate_expression_deparsable(input: &DeriveInput, ctx: &LocalContext) -> TokenStream {
@@ -14,17 +20,15 @@ pub fn generate_expression_deparsable(input: &DeriveInput, ctx: &LocalContext) -
 	}
 }
 
-fn field_name((index, field): (usize, &Field)) -> TokenStream {
-	match &field.ident {
-		Some(ident) => quote! { #ident },
-		None => {
-			let ident = format_ident!("__field_{}", index);
-			quote! { #ident }
-		}
+fn generate_struct(input: &DataStruct, ctx: &LocalContext) -> TokenStream {
+	if input
+		.fields
+		.iter()
+		.any(|field| parse_field_attributes(&field.attrs).dis)
+	{
+		panic!("A dis field needs a discriminant given by its variant");
 	}
-}
 
-fn generate_struct(input: &DataStruct, ctx: &LocalContext) -> TokenStream {
 	let field_names = input
 		.fields
 		.iter()
@@ -36,7 +40,7 @@ fn generate_struct(input: &DataStruct, ctx: &LocalContext) -> TokenStream {
 		.fields
 		.iter()
 		.zip(field_names.iter())
-		.map(|(field, name)| gen_write(field, name, &ctx.ctx_pat))
+		.map(|(field, name)| gen_write(field, name, &ctx.ctx_pat, &field_names))
 		.collect::<Vec<_>>();
 
 	let pattern = match input.fields {
@@ -45,14 +49,74 @@ fn generate_struct(input: &DataStruct, ctx: &LocalContext) -> TokenStream {
 		Fields::Unit => quote! {},
 	};
 
+	let tag = gen_tag(&ctx.tag);
+	let asserts = gen_asserts(&ctx.asserts);
+	let validate = gen_validate(&ctx.validate);
+
+	let checks = (!ctx.asserts.is_empty()).then(|| {
+		quote! {
+			{
+				#[allow(unused_variables)]
+				let Self #pattern = self;
+				#asserts
+			}
+		}
+	});
+
 	quote! {
+		#checks
+		#validate
 		let Self #pattern = self;
+		#tag
 		#( #writes )*
 		Ok(())
 	}
 }
 
 fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
+	if !ctx.asserts.is_empty() {
+		panic!("Assertions on an enum must be given on each variant");
+	}
+
+	let tag = gen_tag(&ctx.tag);
+	let outer_validate = gen_validate(&ctx.validate);
+
+	// Variant checks all run before anything is written
+	let variant_checks = input
+		.variants
+		.iter()
+		.map(|variant| {
+			let variant_attributes = parse_variant_attributes(&variant.attrs);
+
+			let name = &variant.ident;
+
+			let field_names = variant
+				.fields
+				.iter()
+				.enumerate()
+				.map(field_name)
+				.collect::<Vec<_>>();
+
+			let pattern = match (variant_attributes.asserts.is_empty(), &variant.fields) {
+				(true, _) => quote! { { .. } },
+				(false, Fields::Named(_)) => quote! { { #(#field_names),* } },
+				(false, Fields::Unnamed(_)) => quote! { ( #(#field_names),* ) },
+				(false, Fields::Unit) => quote! {},
+			};
+
+			let asserts = gen_asserts(&variant_attributes.asserts);
+			let validate = gen_validate(&variant_attributes.validate);
+
+			quote! {
+				#[allow(unused_variables)]
+				Self::#name #pattern => {
+					#asserts
+					#validate
+				}
+			}
+		})
+		.collect::<TokenStream>();
+
 	let matches = input
 		.variants
 		.iter()
@@ -68,21 +132,62 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 				.map(field_name)
 				.collect::<Vec<_>>();
 
-			let discriminant_write = variant_attributes.dis.map(|value| {
-				let dis_type = ctx
-					.dis_type
-					.as_ref()
-					.expect("Must declare the type of the enum discriminant");
-				quote! {
-					<#dis_type as ::parst::Deparsable<_>>::write(&mut #value, __w, ())?;
+			// A variant matched by pattern writes either an explicit value or the field carrying it
+			let dis_field = variant
+				.fields
+				.iter()
+				.zip(field_names.iter())
+				.find(|(field, _)| parse_field_attributes(&field.attrs).dis)
+				.map(|(_, name)| name);
+			let dis_value = match (variant_attributes.dis, dis_field) {
+				(Some(value), _) => Some(quote! { &{ #value } }),
+				(None, Some(name)) => Some(quote! { #name }),
+				(None, None) => None,
+			};
+			if variant_attributes.dis_pat.is_some()
+				&& ctx.dis_from_ctx.is_none()
+				&& dis_value.is_none()
+			{
+				panic!("Must give a dis value or dis field for each dis_pat variant");
+			}
+
+			// The value written has to be one which reads back as this variant
+			let dis_check = match (&variant_attributes.dis_pat, &dis_value) {
+				(Some(dis_pat), Some(value)) => {
+					let message = LitStr::new(
+						&format!("discriminant of {} does not match its dis_pat", name),
+						name.span(),
+					);
+					Some(quote! {
+						if !matches!(*#value, #dis_pat) {
+							return Err(::std::io::Error::new(::std::io::ErrorKind::InvalidInput, #message));
+						}
+					})
 				}
-			});
+				_ => None,
+			};
 
+			// A discriminant taken from the context is written by the parent, not by the enum
+			let discriminant_write = match (&ctx.dis_from_ctx, dis_value) {
+				(None, Some(value)) => {
+					let dis_type = ctx
+						.dis_type
+						.as_ref()
+						.expect("Must declare the type of the enum discriminant");
+					let dis_ctx = ctx.dis_ctx.to_context_expr(&ctx.ctx_pat);
+					Some(quote! {
+						<#dis_type as ::parst::Deparsable<_>>::write(#value, __w, #dis_ctx)?;
+					})
+				}
+				_ => None,
+			};
+
+			let variant_tag = gen_tag(&variant_attributes.tag);
 			let writes = variant
 				.fields
 				.iter()
 				.zip(field_names.iter())
-				.map(|(field, name)| gen_write(field, name, &ctx.ctx_pat))
+				.map(|(field, name)| gen_write(field, name, &ctx.ctx_pat, &field_names))
 				.collect::<Vec<_>>();
 
 			let pattern = match variant.fields {
@@ -93,7 +198,9 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 
 			quote! {
 				Self::#name #pattern => {
+					#dis_check
 					#discriminant_write
+					#variant_tag
 					#( #writes )*
 				}
 			}
@@ -101,6 +208,11 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 		.collect::<TokenStream>();
 
 	quote! {
+		match self {
+			#variant_checks
+		}
+		#outer_validate
+		#tag
 		match self {
 			#matches
 		}
@@ -108,35 +220,107 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 	}
 }
 
-fn gen_write(Field { attrs, ty, .. }: &Field, name: &TokenStream, ctx_pat: &Pat) -> TokenStream {
+// Bindings are references when writing, so the ones a context refers to are cloned into values
+// to give the context the same meaning it has when parsing
+fn gen_write(
+	Field { attrs, ty, .. }: &Field,
+	name: &Ident,
+	ctx_pat: &Pat,
+	bindings: &[Ident],
+) -> TokenStream {
 	let field_attributes = parse_field_attributes(attrs);
 
-	let mut tokens = Vec::new();
+	let mut tokens = vec![gen_tag(&field_attributes.tag)];
+
+	// Temporaries are bound by value, as they are when parsing
+	for Temp { pat, calc, context } in &field_attributes.temps {
+		let PatType { pat, ty, .. } = pat;
+		let calc = calc.as_ref().expect("Must give a calc to write each temp");
+		let context = match context {
+			Some(e) => gen_context_expr(e, bindings),
+			None => quote! { () },
+		};
+		tokens.push(quote! {
+			let __temp: #ty = { #calc };
+			<#ty as ::parst::Deparsable<_>>::write(&__temp, __w, #context)?;
+			#[allow(unused_variables)]
+			let #pat = __temp;
+		});
+	}
+
+	if field_attributes.dis || field_attributes.default.is_some() {
+		return quote! { #( #tokens )* };
+	}
 
 	if let Some(e) = field_attributes.assert_eq {
 		tokens.push(quote! {
-			let mut __temp = #e;
-			let #name = &mut __temp;
+			let __temp = #e;
+			let #name = &__temp;
 		});
 	}
 
-	let assignment = match field_attributes.context {
-		InnerContext::None => quote! {
-			<#ty as ::parst::Deparsable<_>>::write(#name, __w, ())?;
-		},
-		InnerContext::Inherit => quote! {
-			<#ty as ::parst::Deparsable<_>>::write(#name, __w, #ctx_pat)?;
-		},
-		InnerContext::Expr(e) => quote! {
-			<#ty as ::parst::Deparsable<_>>::write(#name, __w, {
-				let __temp = *#e;
-				__temp
-			})?;
-		},
+	let context = match &field_attributes.context {
+		InnerContext::Expr(e) => gen_context_expr(e, bindings),
+		InnerContext::WithFields(fields) => {
+			let fields = fields.iter().map(|field| match bindings.contains(field) {
+				true => quote! { ::core::clone::Clone::clone(&*#field) },
+				false => quote! { ::core::clone::Clone::clone(&#field) },
+			});
+			quote! { (#ctx_pat, #( #fields ),*) }
+		}
+		context => context.to_context_expr(ctx_pat),
 	};
-	tokens.push(assignment);
+	tokens.push(quote! {
+		<#ty as ::parst::Deparsable<_>>::write(#name, __w, #context)?;
+	});
 
 	quote! {
 		#( #tokens )*
 	}
 }
+
+fn gen_context_expr(e: &Expr, bindings: &[Ident]) -> TokenStream {
+	let used = referenced_bindings(e, bindings);
+	quote! {
+		{
+			#( let #used = ::core::clone::Clone::clone(&*#used); )*
+			#e
+		}
+	}
+}
+
+fn gen_asserts(asserts: &[Assertion]) -> TokenStream {
+	let checks = asserts.iter().map(|assertion| {
+		let expr = &assertion.expr;
+		let message = assertion.message();
+		quote! {
+			if !(#expr) {
+				return Err(::parst::error::Error::AssertionFailed(#message).into());
+			}
+		}
+	});
+
+	quote! {
+		#( #checks )*
+	}
+}
+
+fn gen_tag(tag: &Option<Tag>) -> TokenStream {
+	match tag {
+		Some(Tag::Text(tag)) => quote! {
+			::parst::tag::write_tag(__w, #tag)?;
+		},
+		Some(Tag::Elements(elements)) => quote! {
+			::parst::tag::write_elements(__w, &#elements)?;
+		},
+		None => quote! {},
+	}
+}
+
+fn gen_validate(validate: &[Path]) -> TokenStream {
+	quote! {
+		#(
+			#validate(self)?;
+		)*
+	}
+}
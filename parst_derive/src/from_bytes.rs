@@ -0,0 +1,127 @@
+use crate::{
+	attributes::{LocalContext, OuterAttributes},
+	schema::generate_schema,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput};
+
+fn is_c_or_transparent(input: &DeriveInput) -> bool {
+	let mut found = false;
+	input
+		.attrs
+		.iter()
+		.filter(|a| a.path().is_ident("repr"))
+		.for_each(|a| {
+			a.parse_nested_meta(|meta| {
+				found |= meta.path.is_ident("C") || meta.path.is_ident("transparent");
+				Ok(())
+			})
+			.unwrap();
+		});
+	found
+}
+
+// The field types are required to be `FromBytes` by the where clause, and the layout is checked at
+// compile time, so the unsafe impl holds whenever the output compiles
+pub fn generate_from_bytes(input: &DeriveInput) -> TokenStream {
+	let ident = &input.ident;
+
+	let fields = match &input.data {
+		Data::Struct(s) => &s.fields,
+		_ => panic!("Can only derive FromBytes for structs"),
+	};
+	if !input.generics.params.is_empty() {
+		panic!("Can not derive FromBytes for generic structs");
+	}
+	if !is_c_or_transparent(input) {
+		panic!("Must use #[repr(C)] or #[repr(transparent)] to derive FromBytes");
+	}
+
+	let field_types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+	let align_message = format!("{} must have an alignment of 1", ident);
+	let size_message = format!("{} must not have padding", ident);
+	let schema = generate_schema(input, &LocalContext::from(OuterAttributes::default()));
+
+	quote! {
+		#[automatically_derived]
+		unsafe impl ::parst::pod::FromBytes for #ident
+		where
+			#( #field_types: ::parst::pod::FromBytes, )*
+		{
+		}
+
+		const _: () = {
+			assert!(::core::mem::align_of::<#ident>() == 1, #align_message);
+			assert!(
+				::core::mem::size_of::<#ident>() == 0 #( + ::core::mem::size_of::<#field_types>() )*,
+				#size_message
+			);
+		};
+
+		#[automatically_derived]
+		unsafe impl ::parst::pod::Bulk for #ident {
+			const NATIVE: bool = true;
+
+			#[inline]
+			fn swap(self) -> Self { self }
+		}
+
+		#[automatically_derived]
+		impl<'__src> ::parst::Parsable<'__src, [u8]> for #ident {
+			#[inline]
+			fn read(__source: &'__src [u8], _context: ()) -> ::parst::PResultBytes<'__src, Self> {
+				let (value, __source) = <&Self as ::parst::Parsable<[u8]>>::read(__source, ())?;
+				Ok((*value, __source))
+			}
+
+			#[inline]
+			fn read_array<const N: usize>(
+				__source: &'__src [u8],
+				_context: (),
+			) -> ::parst::PResultBytes<'__src, [Self; N]> {
+				::parst::pod::read_bulk_array(__source)
+			}
+
+			#[inline]
+			fn read_vec(
+				__source: &'__src [u8],
+				_context: (),
+				count: ::core::option::Option<usize>,
+			) -> ::parst::PResultBytes<'__src, ::std::vec::Vec<Self>> {
+				::parst::pod::read_bulk_vec(__source, count)
+			}
+		}
+
+		#[automatically_derived]
+		impl ::parst::Deparsable for #ident {
+			#[inline]
+			fn write(&self, __w: &mut impl ::std::io::Write, _context: ()) -> ::std::io::Result<()> {
+				__w.write_all(::parst::pod::FromBytes::as_bytes(self))
+			}
+
+			#[inline]
+			fn write_slice(
+				slice: &[Self],
+				__w: &mut impl ::std::io::Write,
+				_context: (),
+			) -> ::std::io::Result<()> {
+				::parst::pod::write_bulk(slice, __w)
+			}
+		}
+
+		#[automatically_derived]
+		impl ::parst::pod::FixedSize for #ident {
+			const SIZE: usize = ::core::mem::size_of::<#ident>();
+		}
+
+		#[automatically_derived]
+		impl ::parst::owned::ToStatic for #ident {
+			type Static = Self;
+
+			fn to_static(&self) -> Self { *self }
+		}
+
+		#schema
+	}
+}
@@ -1,10 +1,12 @@
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
+use proc_macro2::{Delimiter, Ident, Spacing, TokenStream, TokenTree};
+use quote::{format_ident, ToTokens};
 use std::collections::HashSet;
 use syn::{
 	punctuated::Punctuated,
 	token::{Comma, Where},
-	Field, GenericParam, Generics, WhereClause, WherePredicate,
+	visit::Visit,
+	Data, DeriveInput, Expr, ExprPath, Field, GenericParam, Generics, Path, Type, TypePath,
+	WhereClause, WherePredicate,
 };
 
 pub fn make_where_clause(i: impl Iterator<Item = WherePredicate>) -> Option<WhereClause> {
@@ -52,12 +54,174 @@ pub fn combine_generics(a: &Generics, b: &Generics) -> Generics {
 	c
 }
 
-pub fn field_name((index, field): (usize, &Field)) -> TokenStream {
+// Tuple fields are bound as `_0`, `_1`, ... so that attributes on later fields can name them
+pub fn field_name((index, field): (usize, &Field)) -> Ident {
 	match &field.ident {
-		Some(ident) => quote! { #ident },
-		None => {
-			let ident = format_ident!("__field_{}", index);
-			quote! { #ident }
+		Some(ident) => ident.clone(),
+		None => format_ident!("_{}", index),
+	}
+}
+
+pub fn all_fields(input: &DeriveInput) -> Vec<&Field> {
+	match &input.data {
+		Data::Struct(s) => s.fields.iter().collect(),
+		Data::Enum(e) => e.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+		Data::Union(u) => u.fields.named.iter().collect(),
+	}
+}
+
+struct Referenced<'a> {
+	bindings: &'a [Ident],
+	found: Vec<Ident>,
+}
+
+impl Referenced<'_> {
+	fn path(&mut self, path: &Path) {
+		if let Some(ident) = path.get_ident() {
+			if self.bindings.contains(ident) && !self.found.contains(ident) {
+				self.found.push(ident.clone());
+			}
+		}
+	}
+}
+
+impl<'ast> Visit<'ast> for Referenced<'_> {
+	fn visit_expr_path(&mut self, expr: &'ast ExprPath) {
+		if expr.qself.is_none() {
+			self.path(&expr.path);
+		}
+	}
+}
+
+// The bindings an expression uses as variables, leaving out any in longer paths or inside macros
+pub fn referenced_bindings(expr: &Expr, bindings: &[Ident]) -> Vec<Ident> {
+	let mut visitor = Referenced {
+		bindings,
+		found: Vec::new(),
+	};
+	visitor.visit_expr(expr);
+	visitor.found
+}
+
+// The type parameters a type names, on their own or as the start of a path such as `T::Item`
+pub fn referenced_params(ty: &Type, params: &[Ident]) -> Vec<Ident> {
+	struct Params<'a>(Referenced<'a>);
+
+	impl<'ast> Visit<'ast> for Params<'_> {
+		fn visit_type_path(&mut self, ty: &'ast TypePath) {
+			if ty.qself.is_none() {
+				if let Some(first) = ty.path.segments.first() {
+					self.0.path(&first.ident.clone().into());
+				}
+			}
+			syn::visit::visit_type_path(self, ty);
 		}
 	}
+
+	let mut visitor = Params(Referenced {
+		bindings: params,
+		found: Vec::new(),
+	});
+	visitor.visit_type(ty);
+	visitor.0.found
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Piece {
+	Word,
+	Close,
+	// An operator with space on both sides
+	Binary,
+	// Nothing follows without a space, such as after `.` or a unary operator
+	Tight,
+}
+
+// Prints tokens closer to how they would be written than `to_string` does, such as `a.len() * 2`
+// rather than `a . len () * 2`, or `1..=4` rather than `1 ..= 4`, for describing attributes. With
+// `generics`, `<` and `>` are read as brackets
+pub fn source_string(tokens: &impl ToTokens, generics: bool) -> String {
+	fn push(out: &mut String, tokens: TokenStream, generics: bool) {
+		let mut prev = None;
+		let mut iter = tokens.into_iter().peekable();
+		while let Some(token) = iter.next() {
+			let space = |out: &mut String, prev: Option<Piece>| {
+				if matches!(prev, Some(Piece::Word | Piece::Close | Piece::Binary)) {
+					out.push(' ');
+				}
+			};
+			match token {
+				TokenTree::Ident(_) | TokenTree::Literal(_) => {
+					space(out, prev);
+					out.push_str(&token.to_string());
+					prev = Some(Piece::Word);
+				}
+				TokenTree::Punct(p) => {
+					let mut op = p.as_char().to_string();
+					let mut joint = p.spacing() == Spacing::Joint && p.as_char() != '\'';
+					while joint {
+						match iter.peek() {
+							Some(TokenTree::Punct(next)) => {
+								op.push(next.as_char());
+								joint = next.spacing() == Spacing::Joint;
+								iter.next();
+							}
+							_ => break,
+						}
+					}
+					let unary = matches!(prev, None | Some(Piece::Binary | Piece::Tight));
+					let piece = match op.as_str() {
+						"." | "::" | "?" | ".." | "..=" => Piece::Tight,
+						"'" => {
+							space(out, prev);
+							Piece::Tight
+						}
+						"<" | ">" if generics => Piece::Tight,
+						"," | ";" => Piece::Binary,
+						"!" | "&" | "-" | "*" | "&&" if unary => {
+							space(out, prev);
+							Piece::Tight
+						}
+						_ => {
+							space(out, prev);
+							Piece::Binary
+						}
+					};
+					if matches!(op.as_str(), ">" if generics) && prev == Some(Piece::Binary) {
+						out.pop();
+					}
+					if matches!(op.as_str(), "," | ";") && out.ends_with(' ') {
+						out.pop();
+					}
+					out.push_str(&op);
+					prev = Some(match (op.as_str(), piece) {
+						(">", Piece::Tight) => Piece::Close,
+						(_, piece) => piece,
+					});
+				}
+				TokenTree::Group(g) => {
+					let (open, close) = match g.delimiter() {
+						Delimiter::Parenthesis => ("(", ")"),
+						Delimiter::Bracket => ("[", "]"),
+						Delimiter::Brace => ("{ ", " }"),
+						Delimiter::None => ("", ""),
+					};
+					// Calls and indexing follow straight on, except for slices in types
+					let call = matches!(prev, Some(Piece::Word | Piece::Close))
+						&& g.delimiter() != Delimiter::Brace
+						&& !(generics && g.delimiter() == Delimiter::Bracket);
+					if !call {
+						space(out, prev);
+					}
+					out.push_str(open);
+					push(out, g.stream(), generics);
+					out.push_str(close);
+					prev = Some(Piece::Close);
+				}
+			}
+		}
+	}
+
+	let mut out = String::new();
+	push(&mut out, tokens.to_token_stream(), generics);
+	out
 }
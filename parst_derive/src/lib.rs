@@ -1,19 +1,34 @@
 pub(crate) mod attributes;
 
+#[cfg(feature = "testing")]
+mod arbitrary;
 mod deparsable;
+mod from_bytes;
 mod helpers;
+mod owned;
 mod parsable;
+mod schema;
+mod spans;
 
 use crate::{
-	attributes::{LocalContext, OuterAttributes},
+	attributes::{
+		parse_field_attributes, parse_variant_attributes, InnerContext, LocalContext,
+		OuterAttributes, Tag,
+	},
 	deparsable::generate::generate_expression_deparsable,
-	parsable::generate::generate_expression_parsable,
+	from_bytes::generate_from_bytes,
+	owned::{add_field_bounds, generate_owned, owned_input},
+	parsable::generate::{generate_expression_parsable, TRACE},
+	schema::generate_schema,
+	spans::generate_spans,
 };
-use helpers::combine_generics;
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, parse_quote, DeriveInput};
+use helpers::{all_fields, combine_generics};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, LitStr, Type};
 
+// Fields are read in order, and each is in scope for the attributes of later fields, as `_0`, `_1`,
+// ... (or `__field_0`, ...) for tuple fields. The attributes are parsed in `attributes.rs`
 #[proc_macro_derive(
 	Parsable,
 	attributes(
@@ -32,32 +47,131 @@ pub fn derive_parsable(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 }
 
 fn process_input_parsable(input: &DeriveInput) -> TokenStream {
+	let outer_attributes = OuterAttributes::from_attributes(&input.attrs);
+	let local_context = LocalContext::from(outer_attributes);
+
+	let mut tokens = impl_parsable(input, &local_context, None);
+	tokens.extend(generate_schema(input, &local_context));
+	if local_context.spans {
+		let spans_ident = format_ident!("{}Spans", input.ident);
+		tokens.extend(generate_spans(input, &spans_ident));
+		tokens.extend(impl_parsable(input, &local_context, Some(&spans_ident)));
+	}
+	if local_context.fixed_size {
+		tokens.extend(impl_fixed_size(input, &local_context));
+	}
+	if let Some(owned_ident) = local_context.owned_ident(&input.ident) {
+		let mut owned = owned_input(input, owned_ident);
+		let local_context = local_context.for_owned();
+		let src_lifetime = &local_context.src_lifetime;
+		let src_type = &local_context.src_type;
+		add_field_bounds(&mut owned, |ty| {
+			parse_quote! { #ty: ::parst::Parsable<#src_lifetime, #src_type> }
+		});
+		tokens.extend(impl_parsable(&owned, &local_context, None));
+	}
+	tokens
+}
+
+// With `spans`, implements `ReadSpans` producing the named spans type rather than `Parsable`
+fn impl_parsable(
+	input: &DeriveInput,
+	local_context: &LocalContext,
+	spans: Option<&Ident>,
+) -> TokenStream {
 	let ident = &input.ident;
 	let generics = &input.generics;
 
-	let outer_attributes = OuterAttributes::from_attributes(&input.attrs);
-	let local_context = LocalContext::from(outer_attributes);
+	let src_lifetime = &local_context.src_lifetime;
+	let src_type = &local_context.src_type;
+	let expression = generate_expression_parsable(input, local_context, spans);
 
-	let expression = generate_expression_parsable(input, &local_context);
+	// The guard is held until the read returns, so it counts the reads nested inside this one
+	let expression = match &local_context.max_depth {
+		Some(max_depth) => quote! {
+			let __depth = ::parst::limits::DepthGuard::enter({ #max_depth })
+				.map_err(|e| (e, __source))?;
+			let __result = (move || -> ::parst::PResult<#src_lifetime, _, #src_type> {
+				#expression
+			})();
+			__depth.leave(__source, __result)
+		},
+		None => expression,
+	};
 
 	let mut combined_generics =
 		combine_generics(generics, &local_context.new_generics_for_parsable());
-	let src_lifetime = local_context.src_lifetime;
-	let src_type = local_context.src_type;
-	let ctx_pat = local_context.ctx_pat;
-	let ctx_type = local_context.ctx_type;
+	let ctx_pat = &local_context.ctx_pat;
+	let ctx_type = &local_context.ctx_type;
 
 	let predicates = &mut combined_generics.make_where_clause().predicates;
 	if local_context.src_is_generic {
 		predicates.push(parse_quote! { #src_type: ?Sized });
+		if has_tags(input, local_context) {
+			predicates.push(parse_quote! { #src_type: ::parst::tag::Tag });
+		}
+		if spans.is_some() {
+			predicates.push(parse_quote! { #src_type: ::parst::iter::Source });
+		}
+	}
+	if local_context.ctx_is_generic {
+		let inheriting = inheriting_types(input, local_context);
+		if !inheriting.is_empty() {
+			predicates.push(parse_quote! { #ctx_type: ::core::marker::Copy });
+		}
+		for (ty, nested_spans) in inheriting {
+			predicates.push(match spans.is_some() && nested_spans {
+				true => parse_quote! {
+					#ty: ::parst::spans::ReadSpans<#src_lifetime, #src_type, #ctx_type>
+				},
+				false => parse_quote! {
+					#ty: ::parst::Parsable<#src_lifetime, #src_type, #ctx_type>
+				},
+			});
+		}
 	}
 
 	let (combined_impl_generics, _, combined_where) = combined_generics.split_for_impl();
+	let (_, type_generics, _) = generics.split_for_impl();
+
+	if spans.is_some() {
+		return quote! {
+			#[automatically_derived]
+			impl #combined_impl_generics ::parst::spans::ReadSpans<#src_lifetime, #src_type, #ctx_type> for #ident #type_generics #combined_where {
+				fn read_with_spans(
+					__source: &#src_lifetime #src_type,
+					#ctx_pat: #ctx_type,
+					__origin: usize,
+				) -> ::parst::PResult<#src_lifetime, (Self, <Self as ::parst::spans::HasSpans>::Spans), #src_type> {
+					#![allow(non_snake_case)]
+					#expression
+				}
+			}
+		};
+	}
+
+	// The whole read is bracketed by entering and leaving the type, so that every way out is seen
+	let expression = match TRACE {
+		true => {
+			let ty = LitStr::new(&ident.to_string(), Span::call_site());
+			quote! {
+				::parst::trace::enter(#ty, ::parst::trace::remaining(__source));
+				let __result = (move || -> ::parst::PResult<#src_lifetime, Self, #src_type> {
+					#expression
+				})();
+				let (Ok((_, __remaining)) | Err((_, __remaining))) = &__result;
+				let __outcome = __result.as_ref().map(|_| ()).map_err(|(e, _)| *e);
+				::parst::trace::exit(#ty, ::parst::trace::remaining(*__remaining), __outcome);
+				__result
+			}
+		}
+		false => expression,
+	};
 
 	quote! {
 		#[automatically_derived]
-		impl #combined_impl_generics ::parst::Parsable<#src_lifetime, #src_type, #ctx_type> for #ident #generics #combined_where {
-			fn read(__source: &#src_lifetime #src_type, #ctx_pat: #ctx_type) -> ::parst::PResult<Self, #src_type> {
+		impl #combined_impl_generics ::parst::Parsable<#src_lifetime, #src_type, #ctx_type> for #ident #type_generics #combined_where {
+			fn read(__source: &#src_lifetime #src_type, #ctx_pat: #ctx_type) -> ::parst::PResult<#src_lifetime, Self, #src_type> {
 				#![allow(non_snake_case)]
 				#expression
 			}
@@ -65,6 +179,41 @@ fn process_input_parsable(input: &DeriveInput) -> TokenStream {
 	}
 }
 
+// The size is the sum of the tags and every field which is read, which is checked to be fixed by
+// the where clause
+fn impl_fixed_size(input: &DeriveInput, local_context: &LocalContext) -> TokenStream {
+	let ident = &input.ident;
+	let fields = match &input.data {
+		Data::Struct(s) => &s.fields,
+		_ => panic!("Can only derive a fixed size for structs"),
+	};
+
+	let mut tag_lens = local_context.tag.iter().map(Tag::len).collect::<Vec<_>>();
+	let mut types = Vec::new();
+	for field in fields {
+		let field_attributes = parse_field_attributes(&field.attrs);
+		tag_lens.extend(field_attributes.tag.iter().map(Tag::len));
+		types.extend(field_attributes.temps.into_iter().map(|temp| *temp.pat.ty));
+		if !field_attributes.dis && field_attributes.default.is_none() {
+			types.push(field.ty.clone());
+		}
+	}
+
+	let mut generics = input.generics.clone();
+	let predicates = &mut generics.make_where_clause().predicates;
+	for ty in &types {
+		predicates.push(parse_quote! { #ty: ::parst::pod::FixedSize });
+	}
+	let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics ::parst::pod::FixedSize for #ident #type_generics #where_clause {
+			const SIZE: usize = 0 #( + #tag_lens )* #( + <#types as ::parst::pod::FixedSize>::SIZE )*;
+		}
+	}
+}
+
 #[proc_macro_derive(
 	Deparsable,
 	attributes(parst, matches, assert_eq, assert_ne, with_context, with_field_context)
@@ -75,29 +224,119 @@ pub fn derive_deparsable(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 }
 
 fn process_input_deparsable(input: &DeriveInput) -> TokenStream {
-	let ident = &input.ident;
-	let generics = &input.generics;
-
 	let outer_attributes = OuterAttributes::from_attributes(&input.attrs);
 	let local_context = LocalContext::from(outer_attributes);
 
-	let expression = generate_expression_deparsable(input, &local_context);
+	let mut tokens = impl_deparsable(input, &local_context);
+	if let Some(owned_ident) = local_context.owned_ident(&input.ident) {
+		let mut owned = owned_input(input, owned_ident);
+		add_field_bounds(&mut owned, |ty| parse_quote! { #ty: ::parst::Deparsable });
+		tokens.extend(impl_deparsable(&owned, &local_context.for_owned()));
+	}
+	tokens
+}
+
+fn impl_deparsable(input: &DeriveInput, local_context: &LocalContext) -> TokenStream {
+	let ident = &input.ident;
+	let generics = &input.generics;
+
+	let expression = generate_expression_deparsable(input, local_context);
 
-	let combined_generics =
+	let mut combined_generics =
 		combine_generics(generics, &local_context.new_generics_for_deparsable());
-	let ctx_pat = local_context.ctx_pat;
-	let ctx_type = local_context.ctx_type;
+	let ctx_pat = &local_context.ctx_pat;
+	let ctx_type = &local_context.ctx_type;
+
+	if local_context.ctx_is_generic {
+		let predicates = &mut combined_generics.make_where_clause().predicates;
+		let inheriting = inheriting_types(input, local_context);
+		if !inheriting.is_empty() {
+			predicates.push(parse_quote! { #ctx_type: ::core::marker::Copy });
+		}
+		for (ty, _) in inheriting {
+			predicates.push(parse_quote! { #ty: ::parst::Deparsable<#ctx_type> });
+		}
+	}
 
 	let (combined_impl_generics, _, combined_where) = combined_generics.split_for_impl();
+	let (_, type_generics, _) = generics.split_for_impl();
 
 	quote! {
 		#[automatically_derived]
-		impl #combined_impl_generics ::parst::Deparsable<#ctx_type> for #ident #generics #combined_where
+		impl #combined_impl_generics ::parst::Deparsable<#ctx_type> for #ident #type_generics #combined_where
 		{
-			fn write(&mut self, __w: &mut impl ::std::io::Write, #ctx_pat: #ctx_type) -> ::std::io::Result<()> {
+			fn write(&self, __w: &mut impl ::std::io::Write, #ctx_pat: #ctx_type) -> ::std::io::Result<()> {
 				#![allow(non_snake_case)]
 				#expression
 			}
 		}
 	}
 }
+
+// Only text tags need the source to implement `Tag`
+fn has_tags(input: &DeriveInput, local_context: &LocalContext) -> bool {
+	let is_text = |tag: &Option<Tag>| matches!(tag, Some(Tag::Text(_)));
+	let variant_tags = match &input.data {
+		Data::Enum(e) => e
+			.variants
+			.iter()
+			.any(|v| is_text(&parse_variant_attributes(&v.attrs).tag)),
+		_ => false,
+	};
+	is_text(&local_context.tag)
+		|| variant_tags
+		|| all_fields(input)
+			.into_iter()
+			.any(|field| is_text(&parse_field_attributes(&field.attrs).tag))
+}
+
+// Generates values which read back as themselves, rejecting those which fail a check that can not
+// be met directly. Contexts are not known, so constraints involving them are not kept, and nor is
+// the order in which enums without a discriminant try their variants
+#[cfg(feature = "testing")]
+#[proc_macro_derive(Arbitrary, attributes(parst))]
+pub fn derive_arbitrary(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	let local_context = LocalContext::from(OuterAttributes::from_attributes(&input.attrs));
+
+	let mut tokens = arbitrary::generate_arbitrary(&input, &local_context);
+	if let Some(owned_ident) = local_context.owned_ident(&input.ident) {
+		let owned = owned_input(&input, owned_ident);
+		tokens.extend(arbitrary::generate_arbitrary(&owned, &local_context));
+	}
+	proc_macro::TokenStream::from(tokens)
+}
+
+// Defines the owned counterpart, named by `#[parst(owned = "...")]` or else `{}Owned`. With
+// `#[parst(owned)]`, the other derives also implement their traits for it
+#[proc_macro_derive(ToStatic, attributes(parst))]
+pub fn derive_to_static(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	let local_context = LocalContext::from(OuterAttributes::from_attributes(&input.attrs));
+
+	let owned = owned_input(&input, local_context.owned_type_ident(&input.ident));
+	proc_macro::TokenStream::from(generate_owned(&input, &owned, &local_context))
+}
+
+// For `#[repr(C)]` structs of `FromBytes` fields, also reading and writing them by copying
+#[proc_macro_derive(FromBytes)]
+pub fn derive_from_bytes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let derive_input = parse_macro_input!(input as DeriveInput);
+	proc_macro::TokenStream::from(generate_from_bytes(&derive_input))
+}
+
+// Types which are handed the context of the type being derived, and so need bounds on it when it is
+// generic, along with whether they are read with their own spans
+fn inheriting_types(input: &DeriveInput, local_context: &LocalContext) -> Vec<(Type, bool)> {
+	let fields = all_fields(input).into_iter().filter_map(|field| {
+		let field_attributes = parse_field_attributes(&field.attrs);
+		matches!(field_attributes.context, InnerContext::Inherit)
+			.then(|| (field.ty.clone(), field_attributes.spans))
+	});
+	let discriminant = local_context
+		.dis_type
+		.clone()
+		.filter(|_| matches!(local_context.dis_ctx, InnerContext::Inherit))
+		.map(|ty| (ty, false));
+	fields.chain(discriminant).collect()
+}
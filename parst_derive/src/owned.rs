@@ -0,0 +1,232 @@
+use crate::{
+	attributes::{parse_field_attributes, InnerContext, LocalContext},
+	helpers::{all_fields, field_name, referenced_params},
+};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{
+	parse_quote, punctuated::Punctuated, visit_mut::VisitMut, Attribute, Data, DeriveInput, Fields,
+	GenericParam, Lifetime, Token, Type, TypeParamBound, WherePredicate,
+};
+
+struct StaticLifetimes;
+
+impl VisitMut for StaticLifetimes {
+	fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+		*lifetime = parse_quote! { 'static };
+	}
+}
+
+#[derive(Default)]
+struct HasLifetime(bool);
+
+impl VisitMut for HasLifetime {
+	fn visit_lifetime_mut(&mut self, _lifetime: &mut Lifetime) { self.0 = true; }
+}
+
+fn has_lifetime(bound: &TypeParamBound) -> bool {
+	let mut visitor = HasLifetime::default();
+	visitor.visit_type_param_bound_mut(&mut bound.clone());
+	visitor.0
+}
+
+fn type_has_lifetime(ty: &Type) -> bool {
+	let mut visitor = HasLifetime::default();
+	visitor.visit_type_mut(&mut ty.clone());
+	visitor.0
+}
+
+fn without_lifetimes(
+	bounds: &Punctuated<TypeParamBound, Token![+]>,
+) -> Punctuated<TypeParamBound, Token![+]> {
+	bounds
+		.iter()
+		.filter(|bound| !has_lifetime(bound))
+		.cloned()
+		.collect()
+}
+
+pub fn static_lifetimes(ty: &Type) -> Type {
+	let mut ty = ty.clone();
+	StaticLifetimes.visit_type_mut(&mut ty);
+	ty
+}
+
+fn owned_type(ty: &Type) -> Type {
+	let ty = static_lifetimes(ty);
+	parse_quote! { <#ty as ::parst::owned::ToStatic>::Static }
+}
+
+fn retain_docs(attrs: &mut Vec<Attribute>) { attrs.retain(|a| a.path().is_ident("doc")) }
+
+// The owned counterpart drops every lifetime parameter, along with any bound or where predicate
+// naming one, and holds the `ToStatic` form of each field
+pub fn owned_input(input: &DeriveInput, ident: Ident) -> DeriveInput {
+	let mut owned = input.clone();
+	owned.ident = ident;
+
+	owned.generics.params = input
+		.generics
+		.params
+		.iter()
+		.filter(|param| !matches!(param, GenericParam::Lifetime(_)))
+		.cloned()
+		.map(|mut param| {
+			if let GenericParam::Type(t) = &mut param {
+				t.bounds = without_lifetimes(&t.bounds);
+				t.bounds.push(parse_quote! { ::parst::owned::ToStatic });
+			}
+			param
+		})
+		.collect();
+	if let Some(where_clause) = &mut owned.generics.where_clause {
+		where_clause.predicates = where_clause
+			.predicates
+			.iter()
+			.filter_map(|predicate| match predicate {
+				WherePredicate::Type(p) if !type_has_lifetime(&p.bounded_ty) => {
+					let mut p = p.clone();
+					p.bounds = without_lifetimes(&p.bounds);
+					(!p.bounds.is_empty()).then_some(WherePredicate::Type(p))
+				}
+				_ => None,
+			})
+			.collect();
+	}
+
+	let fields: Box<dyn Iterator<Item = _>> = match &mut owned.data {
+		Data::Struct(s) => Box::new(s.fields.iter_mut()),
+		Data::Enum(e) => Box::new(e.variants.iter_mut().flat_map(|v| v.fields.iter_mut())),
+		Data::Union(_) => panic!("Can not derive an owned counterpart for union"),
+	};
+	fields.for_each(|field| field.ty = owned_type(&field.ty));
+
+	owned
+}
+
+// Bounds that would have named the source lifetime are dropped from the owned counterpart, so its
+// impls instead require each generic field parsed without a context to implement `bound`
+pub fn add_field_bounds(owned: &mut DeriveInput, bound: impl Fn(&Type) -> WherePredicate) {
+	let params = owned
+		.generics
+		.type_params()
+		.map(|param| param.ident.clone())
+		.collect::<Vec<_>>();
+	let predicates = all_fields(owned)
+		.into_iter()
+		.filter(|field| {
+			let attributes = parse_field_attributes(&field.attrs);
+			matches!(attributes.context, InnerContext::None)
+				&& !attributes.dis
+				&& attributes.default.is_none()
+				&& !referenced_params(&field.ty, &params).is_empty()
+		})
+		.map(|field| bound(&field.ty))
+		.collect::<Vec<_>>();
+	owned
+		.generics
+		.make_where_clause()
+		.predicates
+		.extend(predicates);
+}
+
+pub fn generate_owned(
+	input: &DeriveInput,
+	owned: &DeriveInput,
+	local_context: &LocalContext,
+) -> TokenStream {
+	let ident = &input.ident;
+	let owned_ident = &owned.ident;
+
+	let mut definition = owned.clone();
+	retain_docs(&mut definition.attrs);
+	match &mut definition.data {
+		Data::Struct(s) => s.fields.iter_mut().for_each(|f| retain_docs(&mut f.attrs)),
+		Data::Enum(e) => e.variants.iter_mut().for_each(|v| {
+			retain_docs(&mut v.attrs);
+			v.fields.iter_mut().for_each(|f| retain_docs(&mut f.attrs));
+		}),
+		Data::Union(_) => (),
+	}
+	let derives = &local_context.owned_derive;
+	let derive = (!derives.is_empty()).then(|| quote! { #[derive(#( #derives ),*)] });
+
+	let conversion = match &input.data {
+		Data::Struct(s) => {
+			let (pattern, construct) = convert_fields(&s.fields);
+			quote! {
+				let Self #pattern = self;
+				#owned_ident #construct
+			}
+		}
+		Data::Enum(e) => {
+			let arms = e.variants.iter().map(|variant| {
+				let name = &variant.ident;
+				let (pattern, construct) = convert_fields(&variant.fields);
+				quote! {
+					Self::#name #pattern => #owned_ident::#name #construct,
+				}
+			});
+			quote! {
+				match self {
+					#( #arms )*
+				}
+			}
+		}
+		Data::Union(_) => panic!("Can not derive an owned counterpart for union"),
+	};
+
+	let mut generics = input.generics.clone();
+	let predicates = &mut generics.make_where_clause().predicates;
+	for param in input.generics.type_params() {
+		let param = &param.ident;
+		predicates.push(parse_quote! { #param: ::parst::owned::ToStatic + 'static });
+	}
+	let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+	let (_, owned_type_generics, _) = owned.generics.split_for_impl();
+
+	quote! {
+		#derive
+		#definition
+
+		#[automatically_derived]
+		impl #impl_generics ::parst::owned::ToStatic for #ident #type_generics #where_clause {
+			type Static = #owned_ident #owned_type_generics;
+
+			fn to_static(&self) -> Self::Static {
+				#![allow(non_snake_case)]
+				#conversion
+			}
+		}
+
+		#[automatically_derived]
+		impl #impl_generics #ident #type_generics #where_clause {
+			pub fn to_owned_copy(&self) -> #owned_ident #owned_type_generics {
+				::parst::owned::ToStatic::to_static(self)
+			}
+		}
+	}
+}
+
+fn convert_fields(fields: &Fields) -> (TokenStream, TokenStream) {
+	let field_names = fields
+		.iter()
+		.enumerate()
+		.map(field_name)
+		.collect::<Vec<_>>();
+	let conversions = field_names
+		.iter()
+		.map(|name| quote! { ::parst::owned::ToStatic::to_static(#name) });
+
+	match fields {
+		Fields::Named(_) => (
+			quote! { { #( #field_names ),* } },
+			quote! { { #( #field_names: #conversions ),* } },
+		),
+		Fields::Unnamed(_) => (
+			quote! { ( #( #field_names ),* ) },
+			quote! { ( #( #conversions ),* ) },
+		),
+		Fields::Unit => (quote! {}, quote! {}),
+	}
+}
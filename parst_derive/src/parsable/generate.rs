@@ -1,20 +1,38 @@
 use crate::{
-	attributes::{parse_field_attributes, parse_variant_attributes, InnerContext, LocalContext},
-	helpers::field_name,
+	attributes::{
+		parse_field_attributes, parse_variant_attributes, Assertion, LocalContext, Tag, Temp,
+	},
+	helpers::{field_name, source_string},
 };
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::{Data, DataEnum, DataStruct, DeriveInput, Field, Fields, Pat};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Data, DataEnum, DataStruct, DeriveInput, Field, Fields, LitStr, Pat, PatType, Path};
 
-pub fn generate_expression_parsable(input: &DeriveInput, ctx: &LocalContext) -> TokenStream {
+// Derived impls report each field they read with the `trace` feature
+pub const TRACE: bool = cfg!(feature = "trace");
+
+// With `spans`, the expression also builds the named spans type, and evaluates to both
+pub fn generate_expression_parsable(
+	input: &DeriveInput,
+	ctx: &LocalContext,
+	spans: Option<&Ident>,
+) -> TokenStream {
 	match &input.data {
-		Data::Struct(s) => generate_struct(s, ctx),
-		Data::Enum(e) => generate_enum(e, ctx),
+		Data::Struct(s) => generate_struct(s, ctx, spans),
+		Data::Enum(e) => generate_enum(e, ctx, spans),
 		_ => panic!("Can not derive parsable for union"),
 	}
 }
 
-fn generate_struct(input: &DataStruct, ctx: &LocalContext) -> TokenStream {
+fn generate_struct(input: &DataStruct, ctx: &LocalContext, spans: Option<&Ident>) -> TokenStream {
+	if input
+		.fields
+		.iter()
+		.any(|field| parse_field_attributes(&field.attrs).dis)
+	{
+		panic!("A dis field needs a discriminant given by its variant");
+	}
+
 	let field_names = input
 		.fields
 		.iter()
@@ -26,7 +44,7 @@ fn generate_struct(input: &DataStruct, ctx: &LocalContext) -> TokenStream {
 		.fields
 		.iter()
 		.zip(field_names.iter())
-		.map(|(field, name)| gen_assign(field, name, &ctx.ctx_pat))
+		.map(|(field, name)| gen_assign(field, name, &ctx.ctx_pat, spans.is_some()))
 		.collect::<Vec<_>>();
 
 	let pattern = match input.fields {
@@ -35,20 +53,44 @@ fn generate_struct(input: &DataStruct, ctx: &LocalContext) -> TokenStream {
 		Fields::Unit => quote! {},
 	};
 
+	let begin = spans.map(|_| gen_begin());
+	let tag = gen_tag(&ctx.tag);
+	let asserts = gen_asserts(&field_names, &ctx.asserts);
+	let validate = gen_validate(&ctx.validate);
+	let result = gen_result(spans.map(|ident| quote! { #ident }), &field_names);
+
 	quote! {
+		#begin
+		#tag
 		#( #assignments )*
-		Ok((Self #pattern, __source))
+		#asserts
+		let __value = Self #pattern;
+		#validate
+		#result
 	}
 }
 
-fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
-	let discriminant = ctx.dis_type.as_ref().map(|ty| {
-		quote! {
-			let (__discriminant, __source) = <#ty as ::parst::Parsable<_, _>>::read(__source, ())?;
+fn generate_enum(input: &DataEnum, ctx: &LocalContext, spans: Option<&Ident>) -> TokenStream {
+	if !ctx.asserts.is_empty() {
+		panic!("Assertions on an enum must be given on each variant");
+	}
+
+	let discriminant = match (&ctx.dis_from_ctx, &ctx.dis_type) {
+		(Some(e), _) => Some(quote! {
+			let __discriminant = { #e };
+		}),
+		(None, Some(ty)) => {
+			let dis_ctx = ctx.dis_ctx.to_context_expr(&ctx.ctx_pat);
+			Some(quote! {
+				let (__discriminant, __source) = <#ty as ::parst::Parsable<_, _>>::read(__source, #dis_ctx)?;
+			})
 		}
-	});
+		(None, None) => None,
+	};
 
+	let tag = gen_tag(&ctx.tag);
 	let src_type = &ctx.src_type;
+	let outer_validate = gen_validate(&ctx.validate);
 	let function_calls = input
 		.variants
 		.iter()
@@ -59,13 +101,7 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 				.fields
 				.iter()
 				.enumerate()
-				.map(|(n, field)| match &field.ident {
-					Some(ident) => quote! { #ident },
-					None => {
-						let ident = format_ident!("__field_{}", n);
-						quote! { #ident }
-					}
-				})
+				.map(field_name)
 				.collect::<Vec<_>>();
 
 			let name = &variant.ident;
@@ -75,7 +111,7 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 				.fields
 				.iter()
 				.zip(field_names.iter())
-				.map(|(field, name)| gen_assign(field, name, &ctx.ctx_pat))
+				.map(|(field, name)| gen_assign(field, name, &ctx.ctx_pat, spans.is_some()))
 				.collect::<Vec<_>>();
 
 			let pattern = match variant.fields {
@@ -84,35 +120,80 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 				Fields::Unit => quote! {},
 			};
 
+			let tag = gen_tag(&variant_attributes.tag);
+			let asserts = gen_asserts(&field_names, &variant_attributes.asserts);
+			let validate = gen_validate(&variant_attributes.validate);
+
+			let result = gen_result(spans.map(|ident| quote! { #ident::#name }), &field_names);
 			let return_expr = quote! {
-				Ok((Self::#name #pattern, __source))
+				let __value = Self::#name #pattern;
+				#validate
+				#result
 			};
 
+			let output = match spans {
+				Some(ident) => quote! { (Self, #ident) },
+				None => quote! { Self },
+			};
 			let function_def = quote! {
-				let #fn_name = || -> ::parst::PResult<Self, #src_type> {
+				let #fn_name = || -> ::parst::PResult<#output, #src_type> {
+					#tag
 					#( #assignments )*
+					#asserts
 					#return_expr
 				};
 			};
 
-			let function_call = quote! {
-				if let Ok(x) = #fn_name() {
-					return Ok(x);
-				}
+			// A variant the discriminant selects fails the whole read with its own error. Without a
+			// discriminant, only running out of a limit does, and otherwise the next variant is tried
+			let failed = match ctx.has_discriminant() {
+				true => quote! {
+					Err(e) => return Err(e),
+				},
+				false => quote! {
+					Err((e, s)) if e.is_limit() => return Err((e, s)),
+					Err((e, _)) => __short |= e == ::parst::error::Error::NotEnoughBytes,
+				},
+			};
+			let function_call = match spans {
+				Some(_) => quote! {
+					match #fn_name() {
+						Ok(((__value, __spans), __source)) => {
+							#outer_validate
+							return Ok(((__value, __spans), __source));
+						}
+						#failed
+					}
+				},
+				None => quote! {
+					match #fn_name() {
+						Ok((__value, __source)) => {
+							#outer_validate
+							return Ok((__value, __source));
+						}
+						#failed
+					}
+				},
 			};
 
-			match discriminant.is_some() {
-				true => {
-					let dis_value = variant_attributes
-						.dis
-						.expect("Must give a discriminant value for each variant");
-					quote! {
+			match ctx.has_discriminant() {
+				true => match (variant_attributes.dis_pat, variant_attributes.dis) {
+					(Some(dis_pat), _) => quote! {
+						if matches!(__discriminant, #dis_pat) {
+							#function_def
+							#function_call
+						}
+					},
+					(None, Some(dis_value)) => quote! {
 						if __discriminant == { #dis_value } {
 							#function_def
 							#function_call
 						}
+					},
+					(None, None) => {
+						panic!("Must give a discriminant value or pattern for each variant")
 					}
-				}
+				},
 				false => quote! {
 					#function_def
 					#function_call
@@ -121,49 +202,156 @@ fn generate_enum(input: &DataEnum, ctx: &LocalContext) -> TokenStream {
 		})
 		.collect::<Vec<_>>();
 
+	let begin = spans.map(|_| gen_begin());
+
+	// When every variant fails and one of them ran out of bytes, more input might still give a value,
+	// which readers waiting on more of it need to know
+	let (short, error) = match ctx.has_discriminant() {
+		true => (None, quote! { ::parst::error::Error::InvalidInput }),
+		false => (
+			Some(quote! {
+				#[allow(unused_mut)]
+				let mut __short = false;
+			}),
+			quote! {
+				match __short {
+					true => ::parst::error::Error::NotEnoughBytes,
+					false => ::parst::error::Error::InvalidInput,
+				}
+			},
+		),
+	};
+
 	quote! {
+		#begin
+		#tag
 		#discriminant
+		#short
 		#( #function_calls )*
-		Err((::parst::error::Error::InvalidInput, __source))
+		Err((#error, __source))
 	}
 }
 
-fn gen_assign(Field { attrs, ty, .. }: &Field, name: &TokenStream, ctx_pat: &Pat) -> TokenStream {
+fn gen_assign(
+	Field { attrs, ty, .. }: &Field,
+	name: &Ident,
+	ctx_pat: &Pat,
+	spans: bool,
+) -> TokenStream {
 	let field_attributes = parse_field_attributes(attrs);
+	let span_name = format_ident!("__span_{}", name);
 
-	let mut tokens = Vec::new();
+	let mut tokens = vec![gen_tag(&field_attributes.tag)];
 
-	let assignment = match field_attributes.context {
-		InnerContext::None => quote! {
-			let (#name, __source) = <#ty as ::parst::Parsable<_, _>>::read(__source, ())?;
-		},
-		InnerContext::Inherit => quote! {
-			let (#name, __source) = <#ty as ::parst::Parsable<_, _>>::read(__source, #ctx_pat)?;
-		},
-		InnerContext::Expr(e) => quote! {
-			let (#name, __source) = <#ty as ::parst::Parsable<_, _>>::read(__source, { #e })?;
-		},
+	// Temporaries are read in front of the field and stay in scope for later fields
+	for Temp { pat, context, .. } in &field_attributes.temps {
+		let PatType { pat, ty, .. } = pat;
+		let context = match context {
+			Some(e) => quote! { { #e } },
+			None => quote! { () },
+		};
+		tokens.push(quote! {
+			let (#pat, __source) = <#ty as ::parst::Parsable<_, _>>::read(__source, #context)?;
+		});
+	}
+
+	// Fields which are not read take up no bytes, and have nested spans left empty
+	let empty_span = match field_attributes.spans {
+		true => quote! { ::core::default::Default::default() },
+		false => {
+			let position = gen_position();
+			quote! { #position..#position }
+		}
+	};
+
+	if field_attributes.dis {
+		tokens.push(quote! {
+			let #name = ::core::clone::Clone::clone(&__discriminant);
+		});
+		if spans {
+			tokens.push(quote! { let #span_name = #empty_span; });
+		}
+		return quote! { #( #tokens )* };
+	}
+
+	if let Some(e) = field_attributes.default {
+		tokens.push(quote! {
+			let #name = { #e };
+		});
+		if spans {
+			tokens.push(quote! { let #span_name = #empty_span; });
+		}
+		return quote! { #( #tokens )* };
+	}
+
+	let context = field_attributes.context.to_context_expr(ctx_pat);
+	let (binding, read) = match (spans, field_attributes.spans) {
+		(true, true) => (
+			quote! { (#name, #span_name) },
+			quote! { <#ty as ::parst::spans::ReadSpans<_, _>>::read_with_spans(__source, #context, __origin) },
+		),
+		_ => (
+			quote! { #name },
+			quote! { <#ty as ::parst::Parsable<_, _>>::read(__source, #context) },
+		),
 	};
-	tokens.push(assignment);
+
+	// Traced fields are reported with what was left of the source before and after them, whether or
+	// not spans are being read
+	let traced = TRACE.then(|| LitStr::new(&name.to_string(), Span::call_site()));
+	let read = match &traced {
+		Some(name_str) => {
+			tokens.push(quote! { let __remaining = ::parst::trace::remaining(__source); });
+			quote! {
+				#read.map_err(|(e, s)| {
+					::parst::trace::field_failed(#name_str, __remaining, ::parst::trace::remaining(s), e);
+					(e, s)
+				})
+			}
+		}
+		None => read,
+	};
+
+	let position = gen_position();
+	let own_span = spans && !field_attributes.spans;
+	if own_span {
+		tokens.push(quote! { let __start = #position; });
+	}
+	tokens.push(quote! { let (#binding, __source) = #read?; });
+	if own_span {
+		tokens.push(quote! { let #span_name = __start..#position; });
+	}
+	if let Some(name_str) = traced {
+		tokens.push(quote! {
+			{
+				use ::parst::trace::{ViaDebug as _, ViaOpaque as _};
+				let __consumed = __remaining - ::parst::trace::remaining(__source);
+				::parst::trace::field(#name_str, __remaining, __consumed, (&::parst::trace::Wrap(&#name)).trace_value());
+			}
+		});
+	}
 
 	if let Some(pat) = field_attributes.matches {
+		let message = failure_message(name, "does not match", &pat);
 		tokens.push(quote! {
 			if !matches!(#name, #pat) {
-				return Err((::parst::error::Error::AssertionFailed, __source));
+				return Err((::parst::error::Error::AssertionFailed(#message), __source));
 			}
 		})
 	}
 	if let Some(e) = field_attributes.assert_eq {
+		let message = failure_message(name, "is not equal to", &e);
 		tokens.push(quote! {
 			if #name != #e {
-				return Err((::parst::error::Error::AssertionFailed, __source));
+				return Err((::parst::error::Error::AssertionFailed(#message), __source));
 			}
 		});
 	}
 	if let Some(e) = field_attributes.assert_ne {
+		let message = failure_message(name, "is equal to", &e);
 		tokens.push(quote! {
 			if #name == #e {
-				return Err((::parst::error::Error::AssertionFailed, __source));
+				return Err((::parst::error::Error::AssertionFailed(#message), __source));
 			}
 		});
 	}
@@ -172,3 +360,86 @@ fn gen_assign(Field { attrs, ty, .. }: &Field, name: &TokenStream, ctx_pat: &Pat
 		#( #tokens )*
 	}
 }
+
+fn failure_message(name: &Ident, relation: &str, expected: &impl ToTokens) -> LitStr {
+	let message = format!("{} {} {}", name, relation, source_string(expected, false));
+	LitStr::new(&message, Span::call_site())
+}
+
+// Assertions see every field by reference, the same as when writing
+fn gen_asserts(field_names: &[Ident], asserts: &[Assertion]) -> TokenStream {
+	if asserts.is_empty() {
+		return quote! {};
+	}
+
+	let checks = asserts.iter().map(|assertion| {
+		let expr = &assertion.expr;
+		let message = assertion.message();
+		quote! {
+			if !(#expr) {
+				return Err((::parst::error::Error::AssertionFailed(#message), __source));
+			}
+		}
+	});
+
+	quote! {
+		{
+			#(
+				#[allow(unused_variables)]
+				let #field_names = &#field_names;
+			)*
+			#( #checks )*
+		}
+	}
+}
+
+// How far into the whole input `__source` starts, where `__origin` is its length
+fn gen_position() -> TokenStream {
+	quote! { (__origin - ::parst::iter::Source::len(__source)) }
+}
+
+fn gen_begin() -> TokenStream {
+	let position = gen_position();
+	quote! { let __begin = #position; }
+}
+
+fn gen_result(spans: Option<TokenStream>, field_names: &[Ident]) -> TokenStream {
+	match spans {
+		Some(spans) => {
+			let position = gen_position();
+			let span_names = field_names
+				.iter()
+				.map(|name| format_ident!("__span_{}", name));
+			quote! {
+				let __spans = #spans {
+					span: __begin..#position,
+					#( #field_names: #span_names, )*
+				};
+				Ok(((__value, __spans), __source))
+			}
+		}
+		None => quote! { Ok((__value, __source)) },
+	}
+}
+
+fn gen_tag(tag: &Option<Tag>) -> TokenStream {
+	match tag {
+		Some(Tag::Text(tag)) => quote! {
+			let ((), __source) = ::parst::tag::read_tag(__source, #tag)?;
+		},
+		Some(Tag::Elements(elements)) => quote! {
+			let ((), __source) = ::parst::tag::read_elements(__source, &#elements)?;
+		},
+		None => quote! {},
+	}
+}
+
+fn gen_validate(validate: &[Path]) -> TokenStream {
+	quote! {
+		#(
+			if let Err(e) = #validate(&__value) {
+				return Err((e, __source));
+			}
+		)*
+	}
+}
@@ -0,0 +1,253 @@
+use crate::{
+	attributes::{
+		parse_field_attributes, parse_variant_attributes, InnerContext, LocalContext, Tag, Temp,
+	},
+	helpers::{all_fields, field_name, referenced_params, source_string},
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Fields, GenericParam, LitStr, Path, Type};
+
+fn lit(text: &str) -> LitStr { LitStr::new(text, Span::call_site()) }
+
+fn lit_option(text: Option<String>) -> TokenStream {
+	match text {
+		Some(text) => {
+			let text = lit(&text);
+			quote! { ::core::option::Option::Some(#text) }
+		}
+		None => quote! { ::core::option::Option::None },
+	}
+}
+
+// Tags of elements other than bytes have no text to show
+fn tag_option(tag: &Option<Tag>) -> TokenStream { lit_option(tag.as_ref().and_then(Tag::text)) }
+
+// Field types which do not implement `Schema` are described as opaque, rather than requiring it.
+// Those naming a generic parameter are bounded by `Schema` instead, as the probe can only see
+// through them once they are
+fn probe(ty: &Type) -> TokenStream {
+	let name = lit(&source_string(ty, true));
+	quote! {
+		{
+			use ::parst::schema::{ViaOpaque as _, ViaSchema as _};
+			(&::parst::schema::Probe::<#ty>::new(#name)).schema()
+		}
+	}
+}
+
+fn field_schemas(fields: &Fields, ctx: &LocalContext) -> TokenStream {
+	let schemas = fields.iter().enumerate().flat_map(|(index, field)| {
+		let field_attributes = parse_field_attributes(&field.attrs);
+		let mut tag = Some(field_attributes.tag.clone());
+		let mut schemas = Vec::new();
+
+		// The tag is read in front of any temporaries
+		for Temp { pat, context, .. } in &field_attributes.temps {
+			let name = lit(&source_string(&pat.pat, false));
+			let context = lit_option(context.as_ref().map(|e| source_string(e, false)));
+			let ty = probe(&pat.ty);
+			let tag = tag_option(&tag.take().unwrap_or_default());
+			schemas.push(quote! {
+				::parst::schema::FieldSchema {
+					name: #name,
+					ty: #ty,
+					source: ::parst::schema::FieldSource::Temporary,
+					tag: #tag,
+					context: #context,
+					asserts: ::std::vec::Vec::new(),
+				}
+			});
+		}
+
+		let name = lit(&field_name((index, field)).to_string());
+		let ty = probe(&field.ty);
+		let source = match (&field_attributes.default, field_attributes.dis) {
+			(_, true) => quote! { ::parst::schema::FieldSource::Discriminant },
+			(Some(e), false) => {
+				let e = lit(&source_string(e, false));
+				quote! { ::parst::schema::FieldSource::Computed(#e) }
+			}
+			(None, false) => quote! { ::parst::schema::FieldSource::Read },
+		};
+		let tag = tag_option(&tag.take().unwrap_or_default());
+		let context = lit_option(match &field_attributes.context {
+			InnerContext::None => None,
+			InnerContext::Inherit => Some(source_string(&ctx.ctx_pat, false)),
+			InnerContext::Expr(e) => Some(source_string(e, false)),
+			InnerContext::WithFields(_) => Some(source_string(
+				&field_attributes.context.to_context_expr(&ctx.ctx_pat),
+				false,
+			)),
+		});
+
+		let mut asserts = Vec::new();
+		if let Some(pat) = &field_attributes.matches {
+			asserts.push(format!("matches {}", source_string(pat, false)));
+		}
+		if let Some(e) = &field_attributes.assert_eq {
+			asserts.push(format!("== {}", source_string(e, false)));
+		}
+		if let Some(e) = &field_attributes.assert_ne {
+			asserts.push(format!("!= {}", source_string(e, false)));
+		}
+
+		schemas.push(quote! {
+			::parst::schema::FieldSchema {
+				name: #name,
+				ty: #ty,
+				source: #source,
+				tag: #tag,
+				context: #context,
+				asserts: ::std::vec![#( ::std::string::String::from(#asserts) ),*],
+			}
+		});
+		schemas
+	});
+
+	quote! { ::std::vec![#( #schemas ),*] }
+}
+
+// Validation functions are called with the parsed value
+fn validate_asserts(validate: &[Path]) -> impl Iterator<Item = LitStr> + '_ {
+	validate
+		.iter()
+		.map(|path| lit(&format!("{}(&self)", source_string(path, false))))
+}
+
+// Generic types are named with their arguments, so that `Foo<u8>` and `Foo<u16>` are told apart
+fn type_name(input: &DeriveInput) -> TokenStream {
+	let ident = lit(&input.ident.to_string());
+	let args = input
+		.generics
+		.params
+		.iter()
+		.filter_map(|param| match param {
+			GenericParam::Type(param) => {
+				let ident = &param.ident;
+				Some(quote! { ::parst::schema::type_name::<#ident>() })
+			}
+			GenericParam::Const(param) => {
+				let ident = &param.ident;
+				Some(quote! { ::std::string::ToString::to_string(&#ident) })
+			}
+			GenericParam::Lifetime(_) => None,
+		})
+		.collect::<Vec<_>>();
+	match args.is_empty() {
+		true => quote! { #ident },
+		false => quote! {
+			::std::format!("{}<{}>", #ident, [#( #args ),*].join(", "))
+		},
+	}
+}
+
+// The types read by the derived type which name one of its type parameters
+fn generic_types(input: &DeriveInput, ctx: &LocalContext) -> Vec<Type> {
+	let params = input
+		.generics
+		.type_params()
+		.map(|param| param.ident.clone())
+		.collect::<Vec<_>>();
+	let mut types = Vec::new();
+	for field in all_fields(input) {
+		let field_attributes = parse_field_attributes(&field.attrs);
+		types.extend(field_attributes.temps.into_iter().map(|temp| *temp.pat.ty));
+		types.push(field.ty.clone());
+	}
+	types.extend(ctx.dis_type.clone());
+	types.retain(|ty| !referenced_params(ty, &params).is_empty());
+	types
+}
+
+pub fn generate_schema(input: &DeriveInput, ctx: &LocalContext) -> TokenStream {
+	let ident = &input.ident;
+	let name = type_name(input);
+	let tag = tag_option(&ctx.tag);
+
+	let schema = match &input.data {
+		Data::Struct(s) => {
+			let fields = field_schemas(&s.fields, ctx);
+			let asserts = ctx
+				.asserts
+				.iter()
+				.map(|assertion| lit(&source_string(&assertion.expr, false)))
+				.chain(validate_asserts(&ctx.validate));
+			quote! {
+				::parst::schema::TypeSchema::structure(#name, ::parst::schema::StructSchema {
+					tag: #tag,
+					fields: #fields,
+					asserts: ::std::vec![#( #asserts ),*],
+				})
+			}
+		}
+		Data::Enum(e) => {
+			let discriminant = match (&ctx.dis_from_ctx, &ctx.dis_type) {
+				(Some(e), _) => {
+					let e = lit(&source_string(e, false));
+					quote! { ::parst::schema::Discriminant::FromContext(#e) }
+				}
+				(None, Some(ty)) => {
+					let ty = probe(ty);
+					quote! { ::parst::schema::Discriminant::Read(::std::boxed::Box::new(#ty)) }
+				}
+				(None, None) => quote! { ::parst::schema::Discriminant::None },
+			};
+			let variants = e.variants.iter().map(|variant| {
+				let variant_attributes = parse_variant_attributes(&variant.attrs);
+				let name = lit(&variant.ident.to_string());
+				let discriminant = lit_option(
+					match (&variant_attributes.dis_pat, &variant_attributes.dis) {
+						(Some(pat), _) => Some(source_string(pat, false)),
+						(None, Some(e)) => Some(source_string(e, false)),
+						(None, None) => None,
+					},
+				);
+				let tag = tag_option(&variant_attributes.tag);
+				let fields = field_schemas(&variant.fields, ctx);
+				let asserts = variant_attributes
+					.asserts
+					.iter()
+					.map(|assertion| lit(&source_string(&assertion.expr, false)))
+					.chain(validate_asserts(&variant_attributes.validate));
+				quote! {
+					::parst::schema::VariantSchema {
+						name: #name,
+						discriminant: #discriminant,
+						tag: #tag,
+						fields: #fields,
+						asserts: ::std::vec![#( #asserts ),*],
+					}
+				}
+			});
+			let asserts = validate_asserts(&ctx.validate);
+			quote! {
+				::parst::schema::TypeSchema::enumeration(#name, ::parst::schema::EnumSchema {
+					tag: #tag,
+					discriminant: #discriminant,
+					variants: ::std::vec![#( #variants ),*],
+					asserts: ::std::vec![#( #asserts ),*],
+				})
+			}
+		}
+		Data::Union(_) => panic!("Can not describe a union"),
+	};
+
+	let mut generics = input.generics.clone();
+	let bounds = generic_types(input, ctx)
+		.into_iter()
+		.map(|ty| -> syn::WherePredicate {
+			parse_quote! { #ty: ::parst::schema::Schema }
+		});
+	generics.make_where_clause().predicates.extend(bounds);
+	let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics ::parst::schema::Schema for #ident #type_generics #where_clause {
+			fn schema() -> ::parst::schema::TypeSchema {
+				#schema
+			}
+		}
+	}
+}
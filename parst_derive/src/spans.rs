@@ -0,0 +1,93 @@
+use crate::{attributes::parse_field_attributes, helpers::field_name, owned::static_lifetimes};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields};
+
+// Fields marked with `#[parst(spans)]` hold the spans of their own type, every other field the range
+// it was read from
+fn span_type(field: &Field) -> TokenStream {
+	match parse_field_attributes(&field.attrs).spans {
+		true => {
+			let ty = static_lifetimes(&field.ty);
+			quote! { <#ty as ::parst::spans::HasSpans>::Spans }
+		}
+		false => quote! { ::core::ops::Range<usize> },
+	}
+}
+
+// Enum variants can not give their fields a visibility
+fn span_fields(fields: &Fields, vis: TokenStream) -> TokenStream {
+	let field_names = fields
+		.iter()
+		.enumerate()
+		.map(field_name)
+		.collect::<Vec<_>>();
+	if field_names.iter().any(|name| name == "span") {
+		panic!("A field named `span` would clash with the range of the whole value");
+	}
+	let types = fields.iter().map(span_type);
+	quote! {
+		{
+			#vis span: ::core::ops::Range<usize>,
+			#( #vis #field_names: #types, )*
+		}
+	}
+}
+
+pub fn generate_spans(input: &DeriveInput, spans_ident: &Ident) -> TokenStream {
+	let ident = &input.ident;
+	let vis = &input.vis;
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	let definition = match &input.data {
+		Data::Struct(s) => {
+			let fields = span_fields(&s.fields, quote! { pub });
+			quote! {
+				#[derive(Debug, Clone, Default, PartialEq, Eq)]
+				#vis struct #spans_ident #fields
+			}
+		}
+		Data::Enum(e) => {
+			let variants = e.variants.iter().map(|variant| {
+				let name = &variant.ident;
+				let fields = span_fields(&variant.fields, quote! {});
+				quote! { #name #fields }
+			});
+			// Fields which are skipped have their nested spans left empty, so enums need a default
+			// too, which is the first variant with every span empty
+			let default = e.variants.first().map(|variant| {
+				let name = &variant.ident;
+				let field_names = variant.fields.iter().enumerate().map(field_name);
+				quote! {
+					#[automatically_derived]
+					impl ::core::default::Default for #spans_ident {
+						fn default() -> Self {
+							Self::#name {
+								span: ::core::default::Default::default(),
+								#( #field_names: ::core::default::Default::default(), )*
+							}
+						}
+					}
+				}
+			});
+			quote! {
+				#[derive(Debug, Clone, PartialEq, Eq)]
+				#vis enum #spans_ident {
+					#( #variants, )*
+				}
+
+				#default
+			}
+		}
+		Data::Union(_) => panic!("Can not derive spans for union"),
+	};
+
+	quote! {
+		#definition
+
+		#[automatically_derived]
+		impl #impl_generics ::parst::spans::HasSpans for #ident #type_generics #where_clause {
+			type Spans = #spans_ident;
+		}
+	}
+}
@@ -0,0 +1,178 @@
+use crate::var::try_split_at;
+use parst::{error::Error, limits, pod::FixedSize, Deparsable, PResultBytes, Parsable};
+use std::{cell::OnceCell, marker::PhantomData};
+
+// A run of fixed size values which are only parsed when they are looked at. The context is the number
+// of values, and only the total length is checked up front
+#[derive(Debug, Clone, Copy)]
+pub struct LazyArray<'a, T> {
+	bytes: &'a [u8],
+	len: usize,
+	element: PhantomData<T>,
+}
+
+impl<'a, T> LazyArray<'a, T>
+where
+	T: Parsable<'a, [u8]> + FixedSize,
+{
+	pub fn len(&self) -> usize { self.len }
+
+	pub fn is_empty(&self) -> bool { self.len == 0 }
+
+	pub fn as_bytes(&self) -> &'a [u8] { self.bytes }
+
+	fn read_at(bytes: &'a [u8], index: usize) -> Result<T, Error> {
+		let start = index * T::SIZE;
+		T::read(&bytes[start..start + T::SIZE], ())
+			.map(|(value, _)| value)
+			.map_err(|(e, _)| e)
+	}
+
+	pub fn get(&self, index: usize) -> Option<Result<T, Error>> {
+		(index < self.len).then(|| Self::read_at(self.bytes, index))
+	}
+
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = Result<T, Error>> + 'a {
+		let bytes = self.bytes;
+		(0..self.len).map(move |index| Self::read_at(bytes, index))
+	}
+}
+
+impl<'a, T> Parsable<'a, [u8], usize> for LazyArray<'a, T>
+where
+	T: FixedSize,
+{
+	fn read(source: &'a [u8], context: usize) -> PResultBytes<'a, Self> {
+		let size = T::SIZE
+			.checked_mul(context)
+			.ok_or((Error::NotEnoughBytes, source))?;
+		let (bytes, source) = try_split_at(source, size).ok_or((Error::NotEnoughBytes, source))?;
+
+		Ok((
+			Self {
+				bytes,
+				len: context,
+				element: PhantomData,
+			},
+			source,
+		))
+	}
+}
+
+// The bytes are written back as they were read, so the count given as the context is not needed
+impl<T, Ctx> Deparsable<Ctx> for LazyArray<'_, T> {
+	fn write(&self, w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> {
+		w.write_all(self.bytes)
+	}
+}
+
+// A run of values of any size which are only parsed when they are looked at. The context is the
+// length in bytes, or without one the rest of the source is taken. Random access scans the values
+// once to build an index of where each one starts
+#[derive(Debug, Clone)]
+pub struct LazySeq<'a, T> {
+	bytes: &'a [u8],
+	offsets: OnceCell<Result<Vec<usize>, Error>>,
+	element: PhantomData<T>,
+}
+
+impl<'a, T> LazySeq<'a, T>
+where
+	T: Parsable<'a, [u8]>,
+{
+	fn new(bytes: &'a [u8]) -> Self {
+		Self {
+			bytes,
+			offsets: OnceCell::new(),
+			element: PhantomData,
+		}
+	}
+
+	fn offsets(&self) -> Result<&[usize], Error> {
+		let offsets = self.offsets.get_or_init(|| {
+			let mut offsets = Vec::new();
+			let mut source = self.bytes;
+			while !source.is_empty() {
+				limits::reserve::<usize>(1)?;
+				offsets.push(self.bytes.len() - source.len());
+				let (_, remainder) = T::read(source, ()).map_err(|(e, _)| e)?;
+				// An element taking up none of the bytes would be found forever
+				if remainder.len() == source.len() {
+					return Err(Error::InvalidInput);
+				}
+				source = remainder;
+			}
+			Ok(offsets)
+		});
+		offsets.as_deref().map_err(|e| *e)
+	}
+
+	pub fn as_bytes(&self) -> &'a [u8] { self.bytes }
+
+	pub fn len(&self) -> Result<usize, Error> { self.offsets().map(<[_]>::len) }
+
+	// Like `len`, fails when the values can not all be found, even if the first of them can
+	pub fn is_empty(&self) -> Result<bool, Error> { self.len().map(|len| len == 0) }
+
+	pub fn get(&self, index: usize) -> Option<Result<T, Error>> {
+		let offset = match self.offsets() {
+			Ok(offsets) => *offsets.get(index)?,
+			Err(e) => return Some(Err(e)),
+		};
+		Some(
+			T::read(&self.bytes[offset..], ())
+				.map(|(value, _)| value)
+				.map_err(|(e, _)| e),
+		)
+	}
+
+	// Parses each value in order without building the index, stopping after the first error
+	pub fn iter(&self) -> impl Iterator<Item = Result<T, Error>> + 'a {
+		let mut source = self.bytes;
+		std::iter::from_fn(move || {
+			if source.is_empty() {
+				return None;
+			}
+			match T::read(source, ()) {
+				Ok((_, remainder)) if remainder.len() == source.len() => {
+					source = &[];
+					Some(Err(Error::InvalidInput))
+				}
+				Ok((value, remainder)) => {
+					source = remainder;
+					Some(Ok(value))
+				}
+				Err((e, _)) => {
+					source = &[];
+					Some(Err(e))
+				}
+			}
+		})
+	}
+}
+
+impl<'a, T> Parsable<'a, [u8]> for LazySeq<'a, T>
+where
+	T: Parsable<'a, [u8]>,
+{
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		Ok((Self::new(source), &[]))
+	}
+}
+
+impl<'a, T> Parsable<'a, [u8], usize> for LazySeq<'a, T>
+where
+	T: Parsable<'a, [u8]>,
+{
+	fn read(source: &'a [u8], context: usize) -> PResultBytes<'a, Self> {
+		let (bytes, source) =
+			try_split_at(source, context).ok_or((Error::NotEnoughBytes, source))?;
+		Ok((Self::new(bytes), source))
+	}
+}
+
+impl<T, Ctx> Deparsable<Ctx> for LazySeq<'_, T> {
+	fn write(&self, w: &mut impl std::io::Write, _context: Ctx) -> std::io::Result<()> {
+		w.write_all(self.bytes)
+	}
+}
@@ -1,6 +1,12 @@
+pub mod lazy;
+pub mod strings;
+pub mod text;
 pub mod var;
 
-use parst::{Deparsable, Parsable};
+#[cfg(feature = "testing")]
+mod testing;
+
+use parst::{owned::ToStatic, Deparsable, Parsable};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct Never;
@@ -9,16 +15,22 @@ impl<'a, Src, Ctx> Parsable<'a, Src, Ctx> for Never
 where
 	Src: ?Sized,
 {
-	fn read(source: &'a Src, _context: Ctx) -> parst::PResult<Self, Src> {
+	fn read(source: &'a Src, _context: Ctx) -> parst::PResult<'a, Self, Src> {
 		Err((parst::error::Error::InvalidInput, source))
 	}
 }
 
 impl Deparsable for Never {
-	fn write(&mut self, _w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+	fn write(&self, _w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
 		Err(std::io::Error::new(
 			std::io::ErrorKind::PermissionDenied,
 			"Can not deparse a Never",
 		))
 	}
 }
+
+impl ToStatic for Never {
+	type Static = Self;
+
+	fn to_static(&self) -> Self { *self }
+}
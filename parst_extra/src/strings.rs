@@ -0,0 +1,244 @@
+use crate::var::try_split_at;
+use parst::{error::Error, owned::ToStatic, Deparsable, PResultBytes, Parsable};
+use std::{borrow::Cow, ffi::CStr, marker::PhantomData, ops::Deref};
+
+fn utf8(bytes: &[u8]) -> Result<&str, Error> {
+	std::str::from_utf8(bytes).map_err(|_| Error::InvalidEncoding("utf-8"))
+}
+
+fn invalid_input(message: &'static str) -> std::io::Error {
+	std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+// A NUL terminated string, the terminator is consumed but not included
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NulTerminated<'a>(pub Cow<'a, CStr>);
+
+impl Deref for NulTerminated<'_> {
+	type Target = CStr;
+
+	fn deref(&self) -> &CStr { &self.0 }
+}
+
+impl<'a> Parsable<'a, [u8]> for NulTerminated<'a> {
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		let string =
+			CStr::from_bytes_until_nul(source).map_err(|_| (Error::NotEnoughBytes, source))?;
+		let source = &source[string.count_bytes() + 1..];
+
+		Ok((Self(Cow::Borrowed(string)), source))
+	}
+}
+
+impl Deparsable for NulTerminated<'_> {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(self.0.to_bytes_with_nul())
+	}
+}
+
+impl ToStatic for NulTerminated<'_> {
+	type Static = NulTerminated<'static>;
+
+	fn to_static(&self) -> Self::Static { NulTerminated(Cow::Owned(self.0.clone().into_owned())) }
+}
+
+// UTF-8 in exactly `N` bytes, padded with NUL after the end of the string
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FixedStr<'a, const N: usize>(pub Cow<'a, str>);
+
+impl<const N: usize> Deref for FixedStr<'_, N> {
+	type Target = str;
+
+	fn deref(&self) -> &str { &self.0 }
+}
+
+impl<'a, const N: usize> Parsable<'a, [u8]> for FixedStr<'a, N> {
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		let (bytes, remainder) = try_split_at(source, N).ok_or((Error::NotEnoughBytes, source))?;
+		let length = bytes.iter().position(|&b| b == 0).unwrap_or(N);
+		let (string, padding) = bytes.split_at(length);
+		if padding.iter().any(|&b| b != 0) {
+			return Err((Error::InvalidEncoding("padding"), source));
+		}
+		let string = utf8(string).map_err(|e| (e, source))?;
+
+		Ok((Self(Cow::Borrowed(string)), remainder))
+	}
+}
+
+impl<const N: usize> Deparsable for FixedStr<'_, N> {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		let bytes = self.0.as_bytes();
+		if bytes.len() > N || bytes.contains(&0) {
+			return Err(invalid_input("string does not fit in FixedStr"));
+		}
+		w.write_all(bytes)?;
+		w.write_all(&[0; N][bytes.len()..])
+	}
+}
+
+impl<const N: usize> ToStatic for FixedStr<'_, N> {
+	type Static = FixedStr<'static, N>;
+
+	fn to_static(&self) -> Self::Static { FixedStr(Cow::Owned(self.0.to_string())) }
+}
+
+// UTF-8 preceded by its length in bytes
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct VarStr<'a, L> {
+	string: Cow<'a, str>,
+	length: PhantomData<L>,
+}
+
+// A string preceded by a single byte length
+pub type PascalStr<'a> = VarStr<'a, u8>;
+
+impl<'a, L> VarStr<'a, L> {
+	pub fn new(string: impl Into<Cow<'a, str>>) -> Self {
+		Self {
+			string: string.into(),
+			length: PhantomData,
+		}
+	}
+}
+
+impl<L> Deref for VarStr<'_, L> {
+	type Target = str;
+
+	fn deref(&self) -> &str { &self.string }
+}
+
+impl<'a, L> Parsable<'a, [u8]> for VarStr<'a, L>
+where
+	L: Copy + Into<u64> + Parsable<'a, [u8], ()>,
+{
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		let (length, remainder) = L::read(source, ())?;
+		let (bytes, remainder) =
+			try_split_at(remainder, length.into() as _).ok_or((Error::NotEnoughBytes, source))?;
+		let string = utf8(bytes).map_err(|e| (e, source))?;
+
+		Ok((Self::new(string), remainder))
+	}
+}
+
+impl<L> Deparsable for VarStr<'_, L>
+where
+	L: TryFrom<usize> + Deparsable,
+{
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		let bytes = self.string.as_bytes();
+		let length = L::try_from(bytes.len())
+			.map_err(|_| invalid_input("string is too long for its length prefix"))?;
+		length.write(&mut *w, ())?;
+		w.write_all(bytes)
+	}
+}
+
+impl<L> ToStatic for VarStr<'_, L>
+where
+	L: 'static,
+{
+	type Static = VarStr<'static, L>;
+
+	fn to_static(&self) -> Self::Static { VarStr::new(self.string.to_string()) }
+}
+
+// Encodings which can not borrow from the source. Each reads the rest of the source, or as many code
+// units as its context gives, and writes as many as the context gives when it has one
+macro_rules! impl_decoded {
+	($ty:ident $unit:literal $decode:ident $encode:ident) => {
+		impl<'a> Parsable<'a, [u8]> for $ty {
+			fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+				let string = $decode(source).map_err(|e| (e, source))?;
+				Ok((Self(string), &[]))
+			}
+		}
+
+		impl<'a> Parsable<'a, [u8], usize> for $ty {
+			fn read(source: &'a [u8], context: usize) -> PResultBytes<'a, Self> {
+				let (bytes, remainder) = context
+					.checked_mul($unit)
+					.and_then(|len| try_split_at(source, len))
+					.ok_or((Error::NotEnoughBytes, source))?;
+				let string = $decode(bytes).map_err(|e| (e, source))?;
+				Ok((Self(string), remainder))
+			}
+		}
+
+		impl Deparsable for $ty {
+			fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+				w.write_all(&$encode(&self.0)?)
+			}
+		}
+
+		impl Deparsable<usize> for $ty {
+			fn write(&self, w: &mut impl std::io::Write, context: usize) -> std::io::Result<()> {
+				let bytes = $encode(&self.0)?;
+				if context.checked_mul($unit) != Some(bytes.len()) {
+					return Err(invalid_input(
+						"string is not as many code units as its context",
+					));
+				}
+				w.write_all(&bytes)
+			}
+		}
+
+		impl Deref for $ty {
+			type Target = str;
+
+			fn deref(&self) -> &str { &self.0 }
+		}
+
+		impl ToStatic for $ty {
+			type Static = Self;
+
+			fn to_static(&self) -> Self { self.clone() }
+		}
+	};
+}
+
+macro_rules! impl_utf16 {
+	($ty:ident $from_bytes:ident $to_bytes:ident $decode:ident $encode:ident) => {
+		#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+		pub struct $ty(pub String);
+
+		fn $decode(bytes: &[u8]) -> Result<String, Error> {
+			if bytes.len() % 2 != 0 {
+				return Err(Error::NotEnoughBytes);
+			}
+			let units = bytes
+				.chunks_exact(2)
+				.map(|unit| u16::$from_bytes([unit[0], unit[1]]));
+			char::decode_utf16(units)
+				.collect::<Result<String, _>>()
+				.map_err(|_| Error::InvalidEncoding("utf-16"))
+		}
+
+		fn $encode(string: &str) -> std::io::Result<Vec<u8>> {
+			Ok(string.encode_utf16().flat_map(u16::$to_bytes).collect())
+		}
+
+		impl_decoded!($ty 2 $decode $encode);
+	};
+}
+
+impl_utf16!(Utf16Le from_le_bytes to_le_bytes decode_utf16_le encode_utf16_le);
+impl_utf16!(Utf16Be from_be_bytes to_be_bytes decode_utf16_be encode_utf16_be);
+
+// ISO 8859-1, where every byte is the code point of the same value
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Latin1(pub String);
+
+fn decode_latin1(bytes: &[u8]) -> Result<String, Error> {
+	Ok(bytes.iter().map(|&b| char::from(b)).collect())
+}
+
+fn encode_latin1(string: &str) -> std::io::Result<Vec<u8>> {
+	string
+		.chars()
+		.map(|c| u8::try_from(c).map_err(|_| invalid_input("character is not in latin-1")))
+		.collect()
+}
+
+impl_decoded!(Latin1 1 decode_latin1 encode_latin1);
@@ -0,0 +1,26 @@
+// Values of these types for the checks in `parst::testing`
+
+use crate::{
+	strings::NulTerminated,
+	var::{Cond, Counted, Limited},
+};
+use parst::testing::arbitrary::{Arbitrary, Result, Unstructured};
+use std::{borrow::Cow, ffi::CString};
+
+impl<'a> Arbitrary<'a> for NulTerminated<'_> {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		Ok(Self(Cow::Owned(CString::arbitrary(u)?)))
+	}
+}
+
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Counted<T> {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> { Ok(Self(u.arbitrary()?)) }
+}
+
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Limited<T> {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> { Ok(Self(u.arbitrary()?)) }
+}
+
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Cond<T> {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> { Ok(Self(u.arbitrary()?)) }
+}
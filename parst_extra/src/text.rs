@@ -0,0 +1,436 @@
+use parst::{error::Error, limits, owned::ToStatic, Deparsable, PResult, PResultStr, Parsable};
+use std::{borrow::Cow, fmt::Write as _, marker::PhantomData, ops::Deref};
+
+fn split_while(source: &str, f: impl Fn(char) -> bool) -> (&str, &str) {
+	let end = source.find(|c| !f(c)).unwrap_or(source.len());
+	source.split_at(end)
+}
+
+// Any amount of whitespace, including none. Nothing is written back, so values either side of it
+// which do not end by themselves, like two `Dec`s, run together and read back as one. `Ws1` keeps
+// them apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Ws;
+
+impl<'a> Parsable<'a, str> for Ws {
+	#[inline]
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> { Ok((Ws, source.trim_start())) }
+}
+
+impl Deparsable for Ws {
+	#[inline]
+	fn write(&self, _w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> { Ok(()) }
+}
+
+// At least one whitespace character, written back as a single space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Ws1;
+
+impl<'a> Parsable<'a, str> for Ws1 {
+	#[inline]
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> {
+		let remainder = source.trim_start();
+		if remainder.len() == source.len() {
+			return Err((Error::Expected("whitespace"), source));
+		}
+		Ok((Ws1, remainder))
+	}
+}
+
+impl Deparsable for Ws1 {
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(b" ")
+	}
+}
+
+// Defines a unit struct which reads and writes a literal, for use as a separator or keyword
+#[macro_export]
+macro_rules! tag_type {
+	($( #[$attr:meta] )* $vis:vis $name:ident = $tag:literal) => {
+		$( #[$attr] )*
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+		$vis struct $name;
+
+		impl<'a, Src> ::parst::Parsable<'a, Src> for $name
+		where
+			Src: ::parst::tag::Tag + ?Sized,
+		{
+			#[inline]
+			fn read(source: &'a Src, _context: ()) -> ::parst::PResult<'a, Self, Src> {
+				let ((), source) = ::parst::tag::read_tag(source, $tag)?;
+				Ok(($name, source))
+			}
+		}
+
+		impl ::parst::Deparsable for $name {
+			#[inline]
+			fn write(&self, w: &mut impl ::std::io::Write, _context: ()) -> ::std::io::Result<()> {
+				::parst::tag::write_tag(w, $tag)
+			}
+		}
+	};
+}
+
+tag_type!(pub Comma = ",");
+tag_type!(pub Semicolon = ";");
+tag_type!(pub Colon = ":");
+tag_type!(pub Space = " ");
+tag_type!(pub Newline = "\n");
+
+// A number in decimal, written back in its canonical form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Dec<T>(pub T);
+
+// A number in hexadecimal digits of either case without a prefix, written back in lowercase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex<T>(pub T);
+
+fn integer_len(source: &str, signed: bool) -> usize {
+	let sign = usize::from(signed && source.starts_with('-'));
+	let (digits, _) = split_while(&source[sign..], |c| c.is_ascii_digit());
+	match digits.len() {
+		0 => 0,
+		n => sign + n,
+	}
+}
+
+fn float_len(source: &str) -> usize {
+	let sign = usize::from(source.starts_with('-'));
+	let unsigned = &source[sign..];
+	if let Some(special) = ["inf", "NaN"].iter().find(|s| unsigned.starts_with(*s)) {
+		return sign + special.len();
+	}
+
+	let (whole, rest) = split_while(unsigned, |c| c.is_ascii_digit());
+	if whole.is_empty() {
+		return 0;
+	}
+	let mut len = sign + whole.len();
+	if let Some(fraction) = rest.strip_prefix('.') {
+		let (digits, _) = split_while(fraction, |c| c.is_ascii_digit());
+		if !digits.is_empty() {
+			len += 1 + digits.len();
+		}
+	}
+	if let Some(exponent) = source[len..].strip_prefix(['e', 'E']) {
+		let exponent_sign = usize::from(exponent.starts_with(['-', '+']));
+		let (digits, _) = split_while(&exponent[exponent_sign..], |c| c.is_ascii_digit());
+		if !digits.is_empty() {
+			len += 1 + exponent_sign + digits.len();
+		}
+	}
+	len
+}
+
+fn read_number<'a, T>(
+	source: &'a str,
+	len: usize,
+	parse: impl FnOnce(&str) -> Option<T>,
+) -> PResultStr<'a, T> {
+	if len == 0 {
+		return Err((Error::Expected("digits"), source));
+	}
+	let (digits, remainder) = source.split_at(len);
+	let value = parse(digits).ok_or((Error::InvalidInput, source))?;
+	Ok((value, remainder))
+}
+
+fn write_display(
+	w: &mut impl std::io::Write,
+	value: impl std::fmt::Display,
+) -> std::io::Result<()> {
+	write!(w, "{}", value)
+}
+
+macro_rules! impl_dec {
+	($len:expr => $( $ty:ident )*) => {
+		$(
+			impl<'a> Parsable<'a, str> for Dec<$ty> {
+				#[inline]
+				fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> {
+					let len: fn(&str) -> usize = $len;
+					let (value, source) = read_number(source, len(source), |s| s.parse().ok())?;
+					Ok((Dec(value), source))
+				}
+			}
+
+			impl Deparsable for Dec<$ty> {
+				#[inline]
+				fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+					write_display(w, self.0)
+				}
+			}
+		)*
+	};
+}
+
+impl_dec!(|s| integer_len(s, false) => u8 u16 u32 u64 u128 usize);
+impl_dec!(|s| integer_len(s, true) => i8 i16 i32 i64 i128 isize);
+impl_dec!(float_len => f32 f64);
+
+macro_rules! impl_hex {
+	($( $ty:ident )*) => {
+		$(
+			impl<'a> Parsable<'a, str> for Hex<$ty> {
+				#[inline]
+				fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> {
+					let (digits, _) = split_while(source, |c| c.is_ascii_hexdigit());
+					let parse = |s: &str| $ty::from_str_radix(s, 16).ok();
+					let (value, source) = read_number(source, digits.len(), parse)?;
+					Ok((Hex(value), source))
+				}
+			}
+
+			impl Deparsable for Hex<$ty> {
+				#[inline]
+				fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+					write!(w, "{:x}", self.0)
+				}
+			}
+		)*
+	};
+}
+
+impl_hex!(u8 u16 u32 u64 u128 usize);
+
+impl<T> ToStatic for Dec<T>
+where
+	T: ToStatic,
+{
+	type Static = Dec<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Dec(self.0.to_static()) }
+}
+
+impl<T> ToStatic for Hex<T>
+where
+	T: ToStatic,
+{
+	type Static = Hex<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Hex(self.0.to_static()) }
+}
+
+// A letter or underscore followed by any number of letters, digits or underscores
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Ident<'a>(pub Cow<'a, str>);
+
+impl Deref for Ident<'_> {
+	type Target = str;
+
+	fn deref(&self) -> &str { &self.0 }
+}
+
+impl<'a> Parsable<'a, str> for Ident<'a> {
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> {
+		if !source.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+			return Err((Error::Expected("identifier"), source));
+		}
+		let (ident, source) = split_while(source, |c| c.is_alphanumeric() || c == '_');
+		Ok((Ident(Cow::Borrowed(ident)), source))
+	}
+}
+
+impl Deparsable for Ident<'_> {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		w.write_all(self.0.as_bytes())
+	}
+}
+
+impl ToStatic for Ident<'_> {
+	type Static = Ident<'static>;
+
+	fn to_static(&self) -> Self::Static { Ident(Cow::Owned(self.0.to_string())) }
+}
+
+// A double quoted string. The escapes `\"`, `\\`, `\n`, `\r`, `\t`, `\0` and `\u{..}` are
+// understood, and the string is only copied out of the source when it contains one
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Quoted<'a>(pub Cow<'a, str>);
+
+impl Deref for Quoted<'_> {
+	type Target = str;
+
+	fn deref(&self) -> &str { &self.0 }
+}
+
+// Reads up to and including the closing quote
+fn unescape(mut rest: &str) -> Result<(String, &str), Error> {
+	fn next(rest: &mut &str) -> Result<char, Error> {
+		let mut chars = rest.chars();
+		let c = chars.next().ok_or(Error::NotEnoughBytes)?;
+		*rest = chars.as_str();
+		Ok(c)
+	}
+
+	let mut string = String::new();
+	loop {
+		let c = match next(&mut rest)? {
+			'"' => return Ok((string, rest)),
+			'\\' => match next(&mut rest)? {
+				'"' => '"',
+				'\\' => '\\',
+				'n' => '\n',
+				'r' => '\r',
+				't' => '\t',
+				'0' => '\0',
+				'u' => {
+					let (code, tail) = rest
+						.strip_prefix('{')
+						.and_then(|rest| rest.split_once('}'))
+						.ok_or(Error::InvalidEncoding("escape"))?;
+					rest = tail;
+					u32::from_str_radix(code, 16)
+						.ok()
+						.and_then(char::from_u32)
+						.ok_or(Error::InvalidEncoding("escape"))?
+				}
+				_ => return Err(Error::InvalidEncoding("escape")),
+			},
+			c => c,
+		};
+		string.push(c);
+	}
+}
+
+impl<'a> Parsable<'a, str> for Quoted<'a> {
+	fn read(source: &'a str, _context: ()) -> PResultStr<'a, Self> {
+		let body = source
+			.strip_prefix('"')
+			.ok_or((Error::Expected("\""), source))?;
+		match body.find(['"', '\\']) {
+			Some(end) if body[end..].starts_with('"') => {
+				Ok((Quoted(Cow::Borrowed(&body[..end])), &body[end + 1..]))
+			}
+			_ => {
+				let (string, remainder) = unescape(body).map_err(|e| (e, source))?;
+				Ok((Quoted(Cow::Owned(string)), remainder))
+			}
+		}
+	}
+}
+
+impl Deparsable for Quoted<'_> {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		let mut escaped = String::with_capacity(self.0.len() + 2);
+		escaped.push('"');
+		for c in self.0.chars() {
+			match c {
+				'"' => escaped.push_str("\\\""),
+				'\\' => escaped.push_str("\\\\"),
+				'\n' => escaped.push_str("\\n"),
+				'\r' => escaped.push_str("\\r"),
+				'\t' => escaped.push_str("\\t"),
+				'\0' => escaped.push_str("\\0"),
+				c if c.is_control() => {
+					let _ = write!(escaped, "\\u{{{:x}}}", c as u32);
+				}
+				c => escaped.push(c),
+			}
+		}
+		escaped.push('"');
+		w.write_all(escaped.as_bytes())
+	}
+}
+
+impl ToStatic for Quoted<'_> {
+	type Static = Quoted<'static>;
+
+	fn to_static(&self) -> Self::Static { Quoted(Cow::Owned(self.0.to_string())) }
+}
+
+// Any number of `T` with a `Sep` between each. A separator which is not followed by a `T` is left in
+// the source. The default `Sep` is written between elements
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeparatedBy<T, Sep> {
+	pub items: Vec<T>,
+	separator: PhantomData<Sep>,
+}
+
+impl<T, Sep> SeparatedBy<T, Sep> {
+	pub fn new(items: Vec<T>) -> Self {
+		Self {
+			items,
+			separator: PhantomData,
+		}
+	}
+}
+
+impl<T, Sep> Default for SeparatedBy<T, Sep> {
+	fn default() -> Self { Self::new(Vec::new()) }
+}
+
+impl<T, Sep> Deref for SeparatedBy<T, Sep> {
+	type Target = [T];
+
+	fn deref(&self) -> &[T] { &self.items }
+}
+
+impl<'a, Src, Ctx, T, Sep> Parsable<'a, Src, Ctx> for SeparatedBy<T, Sep>
+where
+	Src: ?Sized,
+	Ctx: Copy,
+	T: Parsable<'a, Src, Ctx>,
+	Sep: Parsable<'a, Src>,
+{
+	fn read(source: &'a Src, context: Ctx) -> PResult<'a, Self, Src> {
+		let mut items = Vec::new();
+		let (first, mut source) = match T::read(source, context) {
+			Ok(read) => read,
+			Err((e, s)) if e.is_limit() => return Err((e, s)),
+			Err(_) => return Ok((Self::new(items), source)),
+		};
+		limits::reserve::<T>(1).map_err(|e| (e, source))?;
+		items.push(first);
+		loop {
+			let after_separator = match Sep::read(source, ()) {
+				Ok((_, after_separator)) => after_separator,
+				Err((e, s)) if e.is_limit() => return Err((e, s)),
+				Err(_) => break,
+			};
+			let (item, remainder) = match T::read(after_separator, context) {
+				Ok(read) => read,
+				Err((e, s)) if e.is_limit() => return Err((e, s)),
+				Err(_) => break,
+			};
+			limits::reserve::<T>(1).map_err(|e| (e, source))?;
+			items.push(item);
+			// A separator and item taking up none of the source would be read forever
+			let progressed = !std::ptr::eq(source, remainder);
+			source = remainder;
+			if !progressed {
+				break;
+			}
+		}
+		Ok((Self::new(items), source))
+	}
+}
+
+impl<T, Sep, Ctx> Deparsable<Ctx> for SeparatedBy<T, Sep>
+where
+	Ctx: Copy,
+	T: Deparsable<Ctx>,
+	Sep: Deparsable + Default,
+{
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		let separator = Sep::default();
+		for (i, item) in self.items.iter().enumerate() {
+			if i != 0 {
+				separator.write(&mut *w, ())?;
+			}
+			item.write(&mut *w, context)?;
+		}
+		Ok(())
+	}
+}
+
+impl<T, Sep> ToStatic for SeparatedBy<T, Sep>
+where
+	T: ToStatic,
+	Sep: 'static,
+{
+	type Static = SeparatedBy<T::Static, Sep>;
+
+	fn to_static(&self) -> Self::Static { SeparatedBy::new(self.items.to_static()) }
+}
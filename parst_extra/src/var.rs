@@ -1,4 +1,9 @@
-use parst::{error::Error, Deparsable, PResult, PResultBytes, Parsable};
+use parst::{
+	error::Error,
+	owned::ToStatic,
+	schema::{Schema, TypeSchema},
+	Deparsable, PResult, PResultBytes, Parsable,
+};
 
 pub fn try_split_at<S>(input: &[S], at: usize) -> Option<(&[S], &[S])> {
 	(input.len() >= at).then(|| input.split_at(at))
@@ -18,7 +23,7 @@ impl<'a, L> Parsable<'a, [u8]> for VarBytes<'a, L>
 where
 	L: Copy + Into<u64> + Parsable<'a, [u8], ()>,
 {
-	fn read(source: &'a [u8], _context: ()) -> PResultBytes<Self> {
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
 		let (length, source) = L::read(source, ())?;
 		let (slice, source) =
 			try_split_at(source, length.into() as _).ok_or((Error::NotEnoughBytes, source))?;
@@ -31,13 +36,133 @@ impl<L> Deparsable for VarBytes<'_, L>
 where
 	L: Deparsable,
 {
-	fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
 		self.length.write(&mut *w, ())?;
 		self.slice.write(&mut *w, ())?;
 		Ok(())
 	}
 }
 
+impl<L> ToStatic for VarBytes<'_, L>
+where
+	L: ToStatic,
+{
+	type Static = VarBytesOwned<L::Static>;
+
+	fn to_static(&self) -> Self::Static {
+		VarBytesOwned {
+			length: self.length.to_static(),
+			vec: self.slice.to_vec(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VarBytesOwned<L> {
+	length: L,
+	vec: Vec<u8>,
+}
+
+impl<L> AsRef<[u8]> for VarBytesOwned<L> {
+	fn as_ref(&self) -> &[u8] { &self.vec }
+}
+
+impl<'a, L> Parsable<'a, [u8]> for VarBytesOwned<L>
+where
+	L: Copy + Into<u64> + Parsable<'a, [u8], ()>,
+{
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		let (VarBytes { length, slice }, source) = VarBytes::read(source, ())?;
+		let vec = slice.to_vec();
+
+		Ok((Self { length, vec }, source))
+	}
+}
+
+impl<L> Deparsable for VarBytesOwned<L>
+where
+	L: Deparsable,
+{
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		self.length.write(&mut *w, ())?;
+		self.vec.as_slice().write(&mut *w, ())?;
+		Ok(())
+	}
+}
+
+impl<L> ToStatic for VarBytesOwned<L>
+where
+	L: ToStatic,
+{
+	type Static = VarBytesOwned<L::Static>;
+
+	fn to_static(&self) -> Self::Static {
+		VarBytesOwned {
+			length: self.length.to_static(),
+			vec: self.vec.clone(),
+		}
+	}
+}
+
+// Holds a handle on the bytes rather than a copy, which shares the buffer when read with
+// `parst::bytes::read`
+#[cfg(feature = "bytes")]
+#[derive(Debug, Clone, Default)]
+pub struct VarBytesShared<L> {
+	length: L,
+	bytes: parst::bytes::Bytes,
+}
+
+#[cfg(feature = "bytes")]
+impl<L> AsRef<[u8]> for VarBytesShared<L> {
+	fn as_ref(&self) -> &[u8] { &self.bytes }
+}
+
+#[cfg(feature = "bytes")]
+impl<L> VarBytesShared<L> {
+	pub fn bytes(&self) -> &parst::bytes::Bytes { &self.bytes }
+}
+
+#[cfg(feature = "bytes")]
+impl<'a, L> Parsable<'a, [u8]> for VarBytesShared<L>
+where
+	L: Copy + Into<u64> + Parsable<'a, [u8], ()>,
+{
+	fn read(source: &'a [u8], _context: ()) -> PResultBytes<'a, Self> {
+		let (length, source) = L::read(source, ())?;
+		let len = usize::try_from(length.into()).map_err(|_| (Error::NotEnoughBytes, source))?;
+		let (bytes, source) = parst::bytes::Bytes::read(source, len)?;
+
+		Ok((Self { length, bytes }, source))
+	}
+}
+
+#[cfg(feature = "bytes")]
+impl<L> Deparsable for VarBytesShared<L>
+where
+	L: Deparsable,
+{
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+		self.length.write(&mut *w, ())?;
+		w.write_all(&self.bytes)
+	}
+}
+
+#[cfg(feature = "bytes")]
+impl<L> ToStatic for VarBytesShared<L>
+where
+	L: ToStatic,
+{
+	type Static = VarBytesShared<L::Static>;
+
+	fn to_static(&self) -> Self::Static {
+		VarBytesShared {
+			length: self.length.to_static(),
+			bytes: self.bytes.clone(),
+		}
+	}
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct VarStructs<L, T> {
 	length: L,
@@ -55,15 +180,10 @@ where
 	L: Copy + Into<u64> + Parsable<'a, S, ()>,
 	T: Parsable<'a, S, Ctx>,
 {
-	fn read(source: &'a S, context: Ctx) -> PResult<Self, S> {
-		let (length, mut source) = L::read(source, ())?;
-		let vec = (0..length.into())
-			.map(|_| {
-				let (t, tail) = T::read(source, context)?;
-				source = tail;
-				Ok(t)
-			})
-			.collect::<Result<Vec<_>, _>>()?;
+	fn read(source: &'a S, context: Ctx) -> PResult<'a, Self, S> {
+		let (length, source) = L::read(source, ())?;
+		let count = usize::try_from(length.into()).map_err(|_| (Error::InvalidInput, source))?;
+		let (vec, source) = T::read_vec(source, context, Some(count))?;
 
 		Ok((Self { length, vec }, source))
 	}
@@ -74,13 +194,28 @@ where
 	L: Deparsable,
 	T: Deparsable,
 {
-	fn write(&mut self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
+	fn write(&self, w: &mut impl std::io::Write, _context: ()) -> std::io::Result<()> {
 		self.length.write(&mut *w, ())?;
 		self.vec.write(&mut *w, ())?;
 		Ok(())
 	}
 }
 
+impl<L, T> ToStatic for VarStructs<L, T>
+where
+	L: ToStatic,
+	T: ToStatic,
+{
+	type Static = VarStructs<L::Static, T::Static>;
+
+	fn to_static(&self) -> Self::Static {
+		VarStructs {
+			length: self.length.to_static(),
+			vec: self.vec.to_static(),
+		}
+	}
+}
+
 pub struct ConsumingVec<T>(Vec<T>);
 
 impl<'a, Ctx, T> Parsable<'a, [u8], Ctx> for ConsumingVec<T>
@@ -89,12 +224,8 @@ where
 	T: Parsable<'a, [u8], Ctx>,
 {
 	#[inline]
-	fn read(mut source: &'a [u8], context: Ctx) -> PResult<Self, [u8]> {
-		let mut v = Vec::new();
-		while let Ok((element, remainder)) = Parsable::read(source, context) {
-			v.push(element);
-			source = remainder;
-		}
+	fn read(source: &'a [u8], context: Ctx) -> PResult<'a, Self, [u8]> {
+		let (v, source) = T::read_vec(source, context, None)?;
 		if !source.is_empty() {
 			return Err((Error::InvalidInput, source));
 		}
@@ -108,14 +239,167 @@ where
 	T: Deparsable<Ctx>,
 {
 	#[inline]
-	fn write(&mut self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
-		for element in self.0.as_mut_slice() {
-			element.write(&mut *w, context)?;
-		}
-		Ok(())
+	fn write(&self, w: &mut impl std::io::Write, context: Ctx) -> std::io::Result<()> {
+		T::write_slice(&self.0, w, context)
 	}
 }
 
 impl<T> AsRef<[T]> for ConsumingVec<T> {
 	fn as_ref(&self) -> &[T] { self.0.as_ref() }
 }
+
+impl<T> ToStatic for ConsumingVec<T>
+where
+	T: ToStatic,
+{
+	type Static = ConsumingVec<T::Static>;
+
+	fn to_static(&self) -> Self::Static { ConsumingVec(self.0.to_static()) }
+}
+
+// Reads as many elements as the context gives
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counted<T>(pub Vec<T>);
+
+impl<'a, S, T> Parsable<'a, S, usize> for Counted<T>
+where
+	S: ?Sized,
+	T: Parsable<'a, S>,
+{
+	#[inline]
+	fn read(source: &'a S, count: usize) -> PResult<'a, Self, S> {
+		let (v, source) = T::read_vec(source, (), Some(count))?;
+		Ok((Counted(v), source))
+	}
+}
+
+// Writing checks the count against the elements held, as it is written elsewhere
+impl<T> Deparsable<usize> for Counted<T>
+where
+	T: Deparsable,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, count: usize) -> std::io::Result<()> {
+		if self.0.len() != count {
+			return Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"count does not match the number of elements",
+			));
+		}
+		T::write_slice(&self.0, w, ())
+	}
+}
+
+impl<T> ToStatic for Counted<T>
+where
+	T: ToStatic,
+{
+	type Static = Counted<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Counted(self.0.to_static()) }
+}
+
+// The count is the context of the field
+impl<T> Schema for Counted<T>
+where
+	T: Schema,
+{
+	fn schema() -> TypeSchema { TypeSchema::array(T::schema(), None) }
+}
+
+// Reads a value out of the number of bytes the context gives, skipping whatever it leaves of them.
+// Writing pads the value with zeroes up to the same number of bytes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Limited<T>(pub T);
+
+impl<'a, T> Parsable<'a, [u8], usize> for Limited<T>
+where
+	T: Parsable<'a, [u8]>,
+{
+	#[inline]
+	fn read(source: &'a [u8], len: usize) -> PResult<'a, Self, [u8]> {
+		let (head, rest) = try_split_at(source, len).ok_or((Error::NotEnoughBytes, source))?;
+		let (value, _) = T::read(head, ())?;
+		Ok((Limited(value), rest))
+	}
+}
+
+impl<T> Deparsable<usize> for Limited<T>
+where
+	T: Deparsable,
+{
+	fn write(&self, w: &mut impl std::io::Write, len: usize) -> std::io::Result<()> {
+		let mut buffer = Vec::with_capacity(len);
+		self.0.write(&mut buffer, ())?;
+		if buffer.len() > len {
+			return Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"value is longer than its limit",
+			));
+		}
+		buffer.resize(len, 0);
+		w.write_all(&buffer)
+	}
+}
+
+impl<T> ToStatic for Limited<T>
+where
+	T: ToStatic,
+{
+	type Static = Limited<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Limited(self.0.to_static()) }
+}
+
+// Reads a value only when the context is true
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cond<T>(pub Option<T>);
+
+impl<'a, S, T> Parsable<'a, S, bool> for Cond<T>
+where
+	S: ?Sized,
+	T: Parsable<'a, S>,
+{
+	#[inline]
+	fn read(source: &'a S, present: bool) -> PResult<'a, Self, S> {
+		match present {
+			true => {
+				let (value, source) = T::read(source, ())?;
+				Ok((Cond(Some(value)), source))
+			}
+			false => Ok((Cond(None), source)),
+		}
+	}
+}
+
+impl<T> Deparsable<bool> for Cond<T>
+where
+	T: Deparsable,
+{
+	#[inline]
+	fn write(&self, w: &mut impl std::io::Write, present: bool) -> std::io::Result<()> {
+		if self.0.is_some() != present {
+			return Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"presence does not match the condition",
+			));
+		}
+		self.0.write(w, ())
+	}
+}
+
+impl<T> ToStatic for Cond<T>
+where
+	T: ToStatic,
+{
+	type Static = Cond<T::Static>;
+
+	fn to_static(&self) -> Self::Static { Cond(self.0.to_static()) }
+}
+
+impl<T> Schema for Cond<T>
+where
+	T: Schema,
+{
+	fn schema() -> TypeSchema { TypeSchema::conditional("Cond", T::schema()) }
+}
@@ -0,0 +1,99 @@
+use parst::{endian::LE, error::Error, Deparsable, Parsable};
+use parst_extra::{
+	lazy::{LazyArray, LazySeq},
+	strings::PascalStr,
+};
+
+#[test]
+fn lazy_array() {
+	let bytes = [1, 0, 2, 0, 3, 0, 9];
+	let (array, rest) = LazyArray::<LE<u16>>::read(&bytes, 3).unwrap();
+	assert_eq!(rest, [9]);
+	assert_eq!(array.len(), 3);
+	assert!(!array.is_empty());
+	let values = array.iter().map(|v| v.unwrap().0).collect::<Vec<_>>();
+	assert_eq!(values, [1, 2, 3]);
+	assert_eq!(array.get(2).unwrap().unwrap().0, 3);
+	assert!(array.get(3).is_none());
+	assert!(array.get(usize::MAX).is_none());
+
+	let mut written = Vec::new();
+	array.write(&mut written, 3).unwrap();
+	assert_eq!(written, bytes[..6]);
+
+	let (array, _) = LazyArray::<LE<u16>>::read(&bytes, 0).unwrap();
+	assert!(array.is_empty() && array.get(0).is_none());
+}
+
+#[test]
+fn lazy_array_too_long() {
+	let bytes = [0; 6];
+	assert_eq!(
+		LazyArray::<LE<u16>>::read(&bytes, 4).unwrap_err(),
+		(Error::NotEnoughBytes, &bytes[..])
+	);
+	// The size of every element together does not fit in a usize
+	assert_eq!(
+		LazyArray::<LE<u32>>::read(&bytes, usize::MAX / 2).unwrap_err(),
+		(Error::NotEnoughBytes, &bytes[..])
+	);
+}
+
+#[test]
+fn lazy_seq_index_matches_iter() {
+	let bytes = b"\x01a\x00\x03bcd\x02ef";
+	let (seq, rest) = LazySeq::<PascalStr>::read(&bytes[..], ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(seq.len(), Ok(4));
+	assert_eq!(seq.is_empty(), Ok(false));
+
+	let iterated = seq.iter().collect::<Result<Vec<_>, _>>().unwrap();
+	let indexed = (0..4)
+		.map(|i| seq.get(i).unwrap())
+		.collect::<Result<Vec<_>, _>>();
+	assert_eq!(indexed.unwrap(), iterated);
+	assert_eq!(
+		iterated.iter().map(|s| &**s).collect::<Vec<_>>(),
+		["a", "", "bcd", "ef"]
+	);
+	assert!(seq.get(4).is_none());
+
+	// Only the bytes the context gives are taken
+	let (seq, rest) = LazySeq::<PascalStr>::read(&bytes[..], 3).unwrap();
+	assert_eq!(seq.len(), Ok(2));
+	assert_eq!(rest, &bytes[3..]);
+	assert_eq!(
+		LazySeq::<PascalStr>::read(&bytes[..], 11).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+
+	let (seq, _) = LazySeq::<PascalStr>::read(&[][..], ()).unwrap();
+	assert_eq!(seq.is_empty(), Ok(true));
+}
+
+#[test]
+fn lazy_seq_corrupt_tail() {
+	let bytes = b"\x01a\x05bc";
+	let (seq, _) = LazySeq::<PascalStr>::read(&bytes[..], ()).unwrap();
+	assert_eq!(seq.len(), Err(Error::NotEnoughBytes));
+	assert_eq!(seq.is_empty(), Err(Error::NotEnoughBytes));
+	// Random access needs the whole index, while iterating gives what comes before the error
+	assert_eq!(seq.get(0), Some(Err(Error::NotEnoughBytes)));
+	let iterated = seq.iter().collect::<Vec<_>>();
+	assert_eq!(iterated.len(), 2);
+	assert_eq!(&**iterated[0].as_ref().unwrap(), "a");
+	assert_eq!(iterated[1], Err(Error::NotEnoughBytes));
+
+	// The bytes still write back as they were
+	let mut written = Vec::new();
+	seq.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+}
+
+#[test]
+fn lazy_seq_empty_elements() {
+	let (seq, _) = LazySeq::<()>::read(&[1][..], ()).unwrap();
+	assert_eq!(seq.len(), Err(Error::InvalidInput));
+	assert_eq!(seq.get(0), Some(Err(Error::InvalidInput)));
+	assert_eq!(seq.iter().collect::<Vec<_>>(), [Err(Error::InvalidInput)]);
+}
@@ -0,0 +1,42 @@
+use parst::{
+	schema::{to_c_header, to_kaitai, to_markdown, Layout, Schema},
+	Parsable,
+};
+use parst_extra::var::{Cond, Counted};
+
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "[u8]")]
+struct Record {
+	flags: u8,
+	count: u8,
+	#[parst(ctx = "flags & 1 != 0")]
+	extra: Cond<u16>,
+	#[parst(ctx = "count as usize")]
+	items: Counted<u32>,
+}
+
+#[test]
+fn conditions_described() {
+	let Layout::Struct(s) = Record::schema().layout else {
+		panic!("not a struct");
+	};
+	assert_eq!(s.fields[2].ty.name, "Cond<u16>");
+	assert_eq!(
+		s.fields[2].ty.layout,
+		Layout::Conditional(Box::new(u16::schema()))
+	);
+	assert_eq!(s.fields[3].ty.name, "[u32]");
+
+	let ksy = to_kaitai(&Record::schema());
+	assert!(ksy.contains("  - id: extra\n    type: u2le\n    if: flags & 1 != 0\n"));
+	assert!(ksy.contains(
+		"  - id: items\n    type: u4le\n    repeat: expr\n    repeat-expr: count as usize\n"
+	));
+
+	let markdown = to_markdown(&Record::schema());
+	assert!(markdown
+		.contains("| 2 | extra | `Cond<u16>` | variable | present when `flags & 1 != 0` |\n"));
+
+	let header = to_c_header(&Record::schema());
+	assert!(header.contains("\t/* extra: Cond<u16>, present when its context is true */\n"));
+}
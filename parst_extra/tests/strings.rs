@@ -0,0 +1,156 @@
+use parst::{error::Error, Deparsable, Parsable};
+use parst_extra::strings::{FixedStr, Latin1, NulTerminated, PascalStr, Utf16Be, Utf16Le, VarStr};
+use std::{borrow::Cow, fmt::Debug, io::ErrorKind};
+
+#[track_caller]
+fn roundtrip<'a, T>(value: T, bytes: &'a [u8])
+where
+	T: Parsable<'a, [u8]> + Deparsable + PartialEq + Debug,
+{
+	let mut written = Vec::new();
+	value.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+	assert_eq!(T::read(bytes, ()), Ok((value, &[][..])));
+}
+
+#[track_caller]
+fn roundtrip_counted<'a, T>(value: T, units: usize, bytes: &'a [u8])
+where
+	T: Parsable<'a, [u8], usize> + Deparsable<usize> + PartialEq + Debug,
+{
+	let mut written = Vec::new();
+	value.write(&mut written, units).unwrap();
+	assert_eq!(written, bytes);
+	assert_eq!(T::read(bytes, units), Ok((value, &[][..])));
+}
+
+#[track_caller]
+fn write_error<T>(value: T) -> ErrorKind
+where
+	T: Deparsable,
+{
+	value.write(&mut Vec::new(), ()).unwrap_err().kind()
+}
+
+#[test]
+fn nul_terminated() {
+	let string = NulTerminated(Cow::Borrowed(c"hi"));
+	roundtrip(string, b"hi\0");
+	let (string, rest) = NulTerminated::read(&b"a\0b"[..], ()).unwrap();
+	assert_eq!(string.to_bytes(), b"a");
+	assert_eq!(rest, &b"b"[..]);
+	assert_eq!(
+		NulTerminated::read(&b"abc"[..], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
+
+#[test]
+fn fixed_str() {
+	roundtrip(FixedStr::<4>(Cow::Borrowed("ab")), b"ab\0\0");
+	roundtrip(FixedStr::<2>(Cow::Borrowed("ab")), b"ab");
+	assert_eq!(
+		FixedStr::<4>::read(&b"ab\0"[..], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(
+		FixedStr::<4>::read(&b"a\0b\0"[..], ()).unwrap_err().0,
+		Error::InvalidEncoding("padding")
+	);
+	assert_eq!(
+		FixedStr::<2>::read(&b"\xff\0"[..], ()).unwrap_err().0,
+		Error::InvalidEncoding("utf-8")
+	);
+	assert_eq!(
+		write_error(FixedStr::<2>(Cow::Borrowed("abc"))),
+		ErrorKind::InvalidInput
+	);
+	assert_eq!(
+		write_error(FixedStr::<4>(Cow::Borrowed("a\0"))),
+		ErrorKind::InvalidInput
+	);
+}
+
+#[test]
+fn var_str() {
+	roundtrip(PascalStr::new("héllo"), b"\x06h\xc3\xa9llo");
+	roundtrip(VarStr::<u16>::new(""), &0u16.to_ne_bytes());
+	assert_eq!(
+		PascalStr::read(&b"\x03ab"[..], ()).unwrap_err(),
+		(Error::NotEnoughBytes, &b"\x03ab"[..])
+	);
+	assert_eq!(
+		PascalStr::read(&b"\x01\xff"[..], ()).unwrap_err().0,
+		Error::InvalidEncoding("utf-8")
+	);
+	assert_eq!(
+		write_error(PascalStr::new("a".repeat(256))),
+		ErrorKind::InvalidInput
+	);
+}
+
+#[test]
+fn utf16() {
+	let string = "a\u{e9}\u{1f600}";
+	roundtrip(Utf16Le(string.into()), b"a\0\xe9\0\x3d\xd8\x00\xde");
+	roundtrip(Utf16Be(string.into()), b"\0a\0\xe9\xd8\x3d\xde\x00");
+	roundtrip_counted(Utf16Le(string.into()), 4, b"a\0\xe9\0\x3d\xd8\x00\xde");
+	roundtrip_counted(Utf16Be("".into()), 0, b"");
+
+	// A lone surrogate, and one pair in the wrong order
+	for bytes in [&b"\x00\xd8"[..], b"\x00\xdc\x00\xd8"] {
+		assert_eq!(
+			Utf16Le::read(bytes, ()).unwrap_err().0,
+			Error::InvalidEncoding("utf-16")
+		);
+	}
+	assert_eq!(
+		Utf16Be::read(&b"\xd8\x00"[..], 1).unwrap_err().0,
+		Error::InvalidEncoding("utf-16")
+	);
+
+	// Odd lengths and fewer units than the context gives
+	assert_eq!(
+		Utf16Le::read(&b"a\0b"[..], ()).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(
+		Utf16Be::read(&b"\0a\0"[..], 2).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(
+		Utf16Le::read(&b"a\0"[..], usize::MAX).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	let (string, rest) = Utf16Le::read(&b"a\0b\0"[..], 1).unwrap();
+	assert_eq!(&*string, "a");
+	assert_eq!(rest, &b"b\0"[..]);
+
+	let error = Utf16Le("ab".into()).write(&mut Vec::new(), 3).unwrap_err();
+	assert_eq!(error.kind(), ErrorKind::InvalidInput);
+	// A surrogate pair is two code units
+	let error = Utf16Le("\u{1f600}".into())
+		.write(&mut Vec::new(), 1)
+		.unwrap_err();
+	assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn latin1() {
+	roundtrip(Latin1("caf\u{e9}".into()), b"caf\xe9");
+	roundtrip_counted(Latin1("\u{ff}".into()), 1, b"\xff");
+	let (string, rest) = Latin1::read(&b"abc"[..], 2).unwrap();
+	assert_eq!(&*string, "ab");
+	assert_eq!(rest, &b"c"[..]);
+	assert_eq!(
+		Latin1::read(&b"ab"[..], 3).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+
+	assert_eq!(
+		write_error(Latin1("\u{100}".into())),
+		ErrorKind::InvalidInput
+	);
+	let error = Latin1("ab".into()).write(&mut Vec::new(), 1).unwrap_err();
+	assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,210 @@
+use parst::{
+	error::Error,
+	limits::{with_limits, Limits},
+	Deparsable, Parsable,
+};
+use parst_extra::text::{Comma, Dec, Hex, Quoted, SeparatedBy, Ws, Ws1};
+use std::borrow::Cow;
+
+// Bracketed groups of groups, such as `[[],[[]]]`
+#[derive(Debug, PartialEq, Parsable)]
+#[parst(src = "str", tag = "[", max_depth = "3")]
+struct Group {
+	items: SeparatedBy<Group, Comma>,
+	#[parst(tag = "]")]
+	end: (),
+}
+
+fn written(value: &impl Deparsable) -> String {
+	let mut bytes = Vec::new();
+	value.write(&mut bytes, ()).unwrap();
+	String::from_utf8(bytes).unwrap()
+}
+
+#[track_caller]
+fn read<'a, T>(source: &'a str) -> Result<(T, &'a str), Error>
+where
+	T: Parsable<'a, str>,
+{
+	T::read(source, ()).map_err(|(e, _)| e)
+}
+
+#[test]
+fn dec_integers() {
+	assert_eq!(read("-12x"), Ok((Dec(-12i8), "x")));
+	assert_eq!(
+		read("+1"),
+		Err::<(Dec<i8>, _), _>(Error::Expected("digits"))
+	);
+	assert_eq!(read("-"), Err::<(Dec<i8>, _), _>(Error::Expected("digits")));
+	// Unsigned numbers take no sign
+	assert_eq!(
+		read("-1"),
+		Err::<(Dec<u8>, _), _>(Error::Expected("digits"))
+	);
+	assert_eq!(read("007 "), Ok((Dec(7u8), " ")));
+
+	assert_eq!(read("255"), Ok((Dec(u8::MAX), "")));
+	assert_eq!(read("256"), Err::<(Dec<u8>, _), _>(Error::InvalidInput));
+	assert_eq!(read("-129"), Err::<(Dec<i8>, _), _>(Error::InvalidInput));
+	assert_eq!(written(&Dec(i64::MIN)), i64::MIN.to_string());
+}
+
+#[test]
+fn dec_floats() {
+	assert_eq!(read("1.5e3x"), Ok((Dec(1500f64), "x")));
+	assert_eq!(read("-2E-2"), Ok((Dec(-0.02f64), "")));
+	assert_eq!(read("4e+1"), Ok((Dec(40f32), "")));
+	// A dot or exponent without digits is left in the source
+	assert_eq!(read("1.e"), Ok((Dec(1f64), ".e")));
+	assert_eq!(read("1e-"), Ok((Dec(1f64), "e-")));
+	assert_eq!(
+		read(".5"),
+		Err::<(Dec<f64>, _), _>(Error::Expected("digits"))
+	);
+
+	assert_eq!(read("inf,"), Ok((Dec(f64::INFINITY), ",")));
+	assert_eq!(read("-inf"), Ok((Dec(f64::NEG_INFINITY), "")));
+	let (Dec(nan), rest) = read::<Dec<f64>>("NaN").unwrap();
+	assert!(nan.is_nan() && rest.is_empty());
+
+	for value in [
+		f64::INFINITY,
+		f64::NEG_INFINITY,
+		1e300,
+		-0.5,
+		f64::MIN_POSITIVE,
+	] {
+		let text = written(&Dec(value));
+		assert_eq!(read(&text), Ok((Dec(value), "")));
+	}
+	let (Dec(nan), _) = read::<Dec<f64>>(&written(&Dec(f64::NAN))).unwrap();
+	assert!(nan.is_nan());
+}
+
+#[test]
+fn hex() {
+	assert_eq!(read("FFz"), Ok((Hex(0xffu8), "z")));
+	assert_eq!(read("1ff"), Err::<(Hex<u8>, _), _>(Error::InvalidInput));
+	assert_eq!(read("0x1"), Ok((Hex(0u8), "x1")));
+	assert_eq!(read("g"), Err::<(Hex<u8>, _), _>(Error::Expected("digits")));
+	assert_eq!(written(&Hex(0xABCDu16)), "abcd");
+}
+
+#[test]
+fn quoted_borrows_without_escapes() {
+	let (quoted, rest) = read::<Quoted>(r#""plain" rest"#).unwrap();
+	assert!(matches!(quoted.0, Cow::Borrowed("plain")));
+	assert_eq!(rest, " rest");
+
+	let (quoted, rest) = read::<Quoted>(r#""a\"b\\c\u{e9}" rest"#).unwrap();
+	assert!(matches!(&quoted.0, Cow::Owned(s) if s == "a\"b\\c\u{e9}"));
+	assert_eq!(rest, " rest");
+}
+
+#[test]
+fn quoted_errors() {
+	let read = read::<Quoted>;
+	assert_eq!(read("plain").unwrap_err(), Error::Expected("\""));
+	assert_eq!(read(r#""open"#).unwrap_err(), Error::NotEnoughBytes);
+	assert_eq!(read(r#""open\"#).unwrap_err(), Error::NotEnoughBytes);
+	assert_eq!(
+		read(r#""escaped \" open"#).unwrap_err(),
+		Error::NotEnoughBytes
+	);
+	assert_eq!(
+		read(r#""\q""#).unwrap_err(),
+		Error::InvalidEncoding("escape")
+	);
+	assert_eq!(
+		read(r#""\u{d800}""#).unwrap_err(),
+		Error::InvalidEncoding("escape")
+	);
+	assert_eq!(
+		read(r#""\u{41""#).unwrap_err(),
+		Error::InvalidEncoding("escape")
+	);
+}
+
+#[test]
+fn quoted_roundtrip() {
+	let string = "\"\\\n\r\t\0\u{1}\u{7f}\u{9f} é";
+	let text = written(&Quoted(Cow::Borrowed(string)));
+	assert!(!text.contains(['\u{1}', '\u{7f}', '\u{9f}']));
+	let (quoted, rest) = read::<Quoted>(&text).unwrap();
+	assert_eq!(&*quoted, string);
+	assert!(rest.is_empty());
+}
+
+#[test]
+fn separated_by() {
+	type List = SeparatedBy<Dec<u8>, Comma>;
+	let (list, rest) = read::<List>("1,2,3;").unwrap();
+	assert_eq!(list.items, [Dec(1), Dec(2), Dec(3)]);
+	assert_eq!(rest, ";");
+	assert_eq!(written(&list), "1,2,3");
+
+	// A trailing separator is left in the source
+	let (list, rest) = read::<List>("1,2,").unwrap();
+	assert_eq!(list.items, [Dec(1), Dec(2)]);
+	assert_eq!(rest, ",");
+
+	let (list, rest) = read::<List>("").unwrap();
+	assert!(list.is_empty() && rest.is_empty());
+	let (list, rest) = read::<List>(",1").unwrap();
+	assert!(list.is_empty());
+	assert_eq!(rest, ",1");
+	assert_eq!(written(&List::default()), "");
+}
+
+#[test]
+fn ws_writes_nothing() {
+	let pair = (Dec(1u8), Ws, Dec(2u8));
+	assert_eq!(written(&pair), "12");
+	assert_eq!(read("12"), Ok(((Dec(12u8), Ws), "")));
+	assert_eq!(
+		read::<(Dec<u8>, Ws, Dec<u8>)>("12").unwrap_err(),
+		Error::Expected("digits")
+	);
+	assert_eq!(read("1 \t\n2"), Ok(((Dec(1u8), Ws, Dec(2u8)), "")));
+
+	let pair = (Dec(1u8), Ws1, Dec(2u8));
+	assert_eq!(written(&pair), "1 2");
+	assert_eq!(read(&written(&pair)), Ok((pair, "")));
+	assert_eq!(
+		read::<(Dec<u8>, Ws1)>("1").unwrap_err(),
+		Error::Expected("whitespace")
+	);
+}
+
+#[test]
+fn separated_by_keeps_recursion_limit() {
+	let (group, rest) = read::<Group>("[[],[[]]]").unwrap();
+	assert_eq!(group.items.len(), 2);
+	assert!(rest.is_empty());
+
+	// Too deep in the first item, and in an item after a separator
+	assert_eq!(
+		read::<Group>("[[[[]]]]").unwrap_err(),
+		Error::RecursionLimit
+	);
+	assert_eq!(
+		read::<Group>("[[],[[[]]]]").unwrap_err(),
+		Error::RecursionLimit
+	);
+}
+
+#[test]
+fn separated_by_keeps_limit_exceeded() {
+	let limits = Limits::UNLIMITED.with_max_bytes(2);
+	let error = with_limits(limits, || read::<SeparatedBy<Dec<u8>, Comma>>("1,2,3,4")).unwrap_err();
+	assert_eq!(error, Error::LimitExceeded);
+
+	// Raised inside the first item, and inside an item after a separator
+	let limits = Limits::UNLIMITED.with_max_bytes(2 * size_of::<Group>());
+	let read = |source| with_limits(limits, || read::<Group>(source).map(|_| ()));
+	assert_eq!(read("[[],[]]"), Ok(()));
+	assert_eq!(read("[[],[],[]]"), Err(Error::LimitExceeded));
+	assert_eq!(read("[[[],[],[]]]"), Err(Error::LimitExceeded));
+	assert_eq!(read("[[],[[],[]]]"), Err(Error::LimitExceeded));
+}
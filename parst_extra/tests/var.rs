@@ -0,0 +1,179 @@
+use parst::{
+	error::Error,
+	limits::{self, with_limits, Limits},
+	Deparsable, Parsable,
+};
+use parst_extra::var::{Cond, Counted, Limited, VarStructs};
+use std::io::ErrorKind;
+
+// Each wrapper taking its context from a field read before it
+#[derive(Debug, PartialEq, Parsable, Deparsable)]
+#[parst(src = "[u8]")]
+struct Record {
+	count: u8,
+	#[parst(ctx = "count as usize")]
+	items: Counted<u8>,
+	len: u8,
+	#[parst(ctx = "len as usize")]
+	name: Limited<u16>,
+	flagged: bool,
+	#[parst(ctx = "flagged")]
+	flag: Cond<u8>,
+}
+
+#[test]
+fn counted_read() {
+	assert_eq!(
+		Counted::<u8>::read(&[1, 2, 3][..], 2),
+		Ok((Counted(vec![1, 2]), &[3][..]))
+	);
+	assert_eq!(
+		Counted::<u8>::read(&[1][..], 0),
+		Ok((Counted(vec![]), &[1][..]))
+	);
+	assert_eq!(
+		Counted::<u8>::read(&[1][..], 2).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
+
+#[test]
+fn limited_read_and_write() {
+	// What the value leaves of its bytes is skipped
+	let bytes = 7u16.to_ne_bytes();
+	let source = [bytes[0], bytes[1], 9, 9, 5];
+	assert_eq!(
+		Limited::<u16>::read(&source[..], 4),
+		Ok((Limited(7), &[5][..]))
+	);
+	assert_eq!(
+		Limited::<u16>::read(&source[..], 1).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(
+		Limited::<u16>::read(&source[..], 6).unwrap_err(),
+		(Error::NotEnoughBytes, &source[..])
+	);
+
+	let mut written = Vec::new();
+	Limited(7u16).write(&mut written, 4).unwrap();
+	assert_eq!(written, [bytes[0], bytes[1], 0, 0]);
+	let error = Limited(7u16).write(&mut Vec::new(), 1).unwrap_err();
+	assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn cond_read() {
+	assert_eq!(
+		Cond::<u8>::read(&[4][..], true),
+		Ok((Cond(Some(4)), &[][..]))
+	);
+	assert_eq!(
+		Cond::<u8>::read(&[4][..], false),
+		Ok((Cond(None), &[4][..]))
+	);
+	assert_eq!(
+		Cond::<u8>::read(&[][..], true).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
+
+#[test]
+fn contexts_from_fields() {
+	let bytes = [2, 10, 11, 3, 1, 0, 0, 1, 8];
+	let (record, rest) = Record::read(&bytes, ()).unwrap();
+	assert!(rest.is_empty());
+	assert_eq!(record.items, Counted(vec![10, 11]));
+	assert_eq!(record.name, Limited(u16::from_ne_bytes([1, 0])));
+	assert_eq!(record.flag, Cond(Some(8)));
+
+	let mut written = Vec::new();
+	record.write(&mut written, ()).unwrap();
+	assert_eq!(written, bytes);
+
+	let (record, _) = Record::read(&[0, 2, 1, 0, 0, 9], ()).unwrap();
+	assert_eq!(record.items, Counted(vec![]));
+	assert_eq!(record.flag, Cond(None));
+
+	// Fields out of step with the wrappers they describe are not written
+	let mismatched = Record { count: 1, ..record };
+	assert!(mismatched.write(&mut Vec::new(), ()).is_err());
+}
+
+#[test]
+fn counted_write_checks_count() {
+	let counted = Counted(vec![1u8, 2, 3]);
+	let mut written = Vec::new();
+	counted.write(&mut written, 3).unwrap();
+	assert_eq!(written, [1, 2, 3]);
+
+	let error = counted.write(&mut Vec::new(), 2).unwrap_err();
+	assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn cond_write_checks_condition() {
+	let mut written = Vec::new();
+	Cond(Some(7u8)).write(&mut written, true).unwrap();
+	Cond::<u8>(None).write(&mut written, false).unwrap();
+	assert_eq!(written, [7]);
+
+	let error = Cond(Some(7u8)).write(&mut Vec::new(), false).unwrap_err();
+	assert_eq!(error.kind(), ErrorKind::InvalidInput);
+	let error = Cond::<u8>(None).write(&mut Vec::new(), true).unwrap_err();
+	assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn counts_of_empty_elements_read() {
+	let (value, rest) = VarStructs::<u8, ()>::read(&[3, 9], ()).unwrap();
+	assert_eq!(value.as_ref(), [(), (), ()]);
+	assert_eq!(rest, [9]);
+
+	let (value, rest) = Counted::<()>::read(&[0u8; 0][..], 5).unwrap();
+	assert_eq!(value.0.len(), 5);
+	assert!(rest.is_empty());
+}
+
+#[test]
+fn counts_past_the_input_not_enough_bytes() {
+	assert_eq!(
+		VarStructs::<u8, u16>::read(&[3, 1, 0, 2, 0], ())
+			.unwrap_err()
+			.0,
+		Error::NotEnoughBytes
+	);
+	assert_eq!(
+		Counted::<u16>::read(&[1, 0][..], usize::MAX).unwrap_err().0,
+		Error::NotEnoughBytes
+	);
+}
+
+#[test]
+fn counts_of_empty_elements_limited() {
+	with_limits(Limits::UNLIMITED.with_max_bytes(1024), || {
+		assert_eq!(
+			Counted::<()>::read(&[0u8; 0][..], usize::MAX)
+				.unwrap_err()
+				.0,
+			Error::LimitExceeded
+		);
+	});
+}
+
+#[test]
+fn counts_of_empty_elements_limited_by_default() {
+	let bytes = [0xff, 0xff, 0xff, 0x0f];
+	let error = VarStructs::<u32, Option<u8>>::read(&bytes, ()).unwrap_err();
+	assert_eq!(error.0, Error::LimitExceeded);
+	assert_eq!(
+		Counted::<()>::read(&[0u8; 0][..], usize::MAX)
+			.unwrap_err()
+			.0,
+		Error::LimitExceeded
+	);
+
+	let count = limits::MAX_EMPTY_VALUES;
+	let (value, _) = Counted::<()>::read(&[0u8; 0][..], count).unwrap();
+	assert_eq!(value.0.len(), count);
+}